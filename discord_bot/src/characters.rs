@@ -17,98 +17,98 @@ pub fn character(mod_path: &str, fighter_option: &str) -> Option<&'static str> {
 #[rustfmt::skip]
 pub fn brawl(token: &str) -> Option<&'static str> {
     match token {
-        "bowser"          => Some("Bowser"),
-        "captain"         => Some("Captain%20Falcon"),
-        "falcon"          => Some("Captain%20Falcon"),
-        "captainfalcon"   => Some("Captain%20Falcon"),
-        "cf"              => Some("Captain%20Falcon"),
-        "charizard"       => Some("Charizard"),
-        "zard"            => Some("Charizard"),
-        "diddy"           => Some("Diddy%20Kong"),
-        "diddykong"       => Some("Diddy%20Kong"),
-        "donkey"          => Some("Donkey%20Kong"),
-        "donkeykong"      => Some("Donkey%20Kong"),
-        "dk"              => Some("Donkey%20Kong"),
-        "falco"           => Some("Falco"),
-        "fox"             => Some("Fox"),
-        "game"            => Some("Game%20&%20Watch"),
-        "game&watch"      => Some("Game%20&%20Watch"),
-        "gameandwatch"    => Some("Game%20&%20Watch"),
-        "gamewatch"       => Some("Game%20&%20Watch"),
-        "g&w"             => Some("Game%20&%20Watch"),
-        "gaw"             => Some("Game%20&%20Watch"),
-        "gw"              => Some("Game%20&%20Watch"),
-        "gnw"             => Some("Game%20&%20Watch"),
-        "ganondorf"       => Some("Ganondorf"),
-        "ganon"           => Some("Ganondorf"),
-        "dorf"            => Some("Ganondorf"),
-        "giga bowser"     => Some("Giga%20Bowser"),
-        "gigabowser"      => Some("Giga%20Bowser"),
-        "gb"              => Some("Giga%20Bowser"),
-        "iceclimbers"     => Some("Ice%20Climbers"),
-        "iceclimber"      => Some("Ice%20Climbers"),
-        "ice"             => Some("Ice%20Climbers"),
-        "ic"              => Some("Ice%20Climbers"),
-        "ics"             => Some("Ice%20Climbers"),
-        "ices"            => Some("Ice%20Climbers"),
-        "icies"           => Some("Ice%20Climbers"),
-        "ike"             => Some("Ike"),
-        "ivysaur"         => Some("Ivysaur"),
-        "ivy"             => Some("Ivysaur"),
-        "jigglypuff"      => Some("Jigglypuff"),
-        "jiggly"          => Some("Jigglypuff"),
-        "jiggs"           => Some("Jigglypuff"),
-        "puff"            => Some("Jigglypuff"),
-        "kingdedede"      => Some("King%20Dedede"),
-        "king"            => Some("King%20Dedede"),
-        "dedede"          => Some("King%20Dedede"),
-        "d3"              => Some("King%20Dedede"),
-        "ddd"             => Some("King%20Dedede"),
-        "kd"              => Some("King%20Dedede"),
-        "kirby"           => Some("Kirby"),
-        "kirb"            => Some("Kirby"),
-        "link"            => Some("Link"),
-        "lucario"         => Some("Lucario"),
-        "lucas"           => Some("Lucas"),
-        "luigi"           => Some("Luigi"),
-        "mario"           => Some("Mario"),
-        "marth"           => Some("Marth"),
-        "swordball"       => Some("Meta%20Knight"),
-        "meta"            => Some("Meta%20Knight"),
-        "metaknight"      => Some("Meta%20Knight"),
-        "mk"              => Some("Meta%20Knight"),
-        "ness"            => Some("Ness"),
-        "olimar"          => Some("Olimar"),
-        "oli"             => Some("Olimar"),
-        "peach"           => Some("Peach"),
-        "pikachu"         => Some("Pikachu"),
-        "pika"            => Some("Pikachu"),
-        "pit"             => Some("Pit"),
-        "rob"             => Some("R.O.B"),
-        "r.o.b"           => Some("R.O.B"),
-        "samus"           => Some("Samus"),
-        "sheik"           => Some("Sheik"),
-        "solid"           => Some("Snake"),
-        "solidsnake"      => Some("Snake"),
-        "snake"           => Some("Snake"),
-        "sonic"           => Some("Sonic"),
-        "squirtle"        => Some("Squirtle"),
-        "squirt"          => Some("Squirtle"),
-        "toon"            => Some("Toon%20Link"),
-        "toonlink"        => Some("Toon%20Link"),
-        "tink"            => Some("Toon%20Link"),
-        "tlink"           => Some("Toon%20Link"),
-        "tl"              => Some("Toon%20Link"),
-        "wario"           => Some("Wario"),
-        "wario-man"       => Some("Wario-Man"),
-        "warioman"        => Some("Wario-Man"),
-        "wolf"            => Some("Wolf"),
-        "yoshi"           => Some("Yoshi"),
-        "yosh"            => Some("Yoshi"),
-        "zelda"           => Some("Zelda"),
-        "zero"            => Some("Zero%20Suit%20Samus"),
-        "zerosuitsamus"   => Some("Zero%20Suit%20Samus"),
-        "zss"             => Some("Zero%20Suit%20Samus"),
+        "bowser"          => Some("bowser"),
+        "captain"         => Some("captain-falcon"),
+        "falcon"          => Some("captain-falcon"),
+        "captainfalcon"   => Some("captain-falcon"),
+        "cf"              => Some("captain-falcon"),
+        "charizard"       => Some("charizard"),
+        "zard"            => Some("charizard"),
+        "diddy"           => Some("diddy-kong"),
+        "diddykong"       => Some("diddy-kong"),
+        "donkey"          => Some("donkey-kong"),
+        "donkeykong"      => Some("donkey-kong"),
+        "dk"              => Some("donkey-kong"),
+        "falco"           => Some("falco"),
+        "fox"             => Some("fox"),
+        "game"            => Some("game-and-watch"),
+        "game&watch"      => Some("game-and-watch"),
+        "gameandwatch"    => Some("game-and-watch"),
+        "gamewatch"       => Some("game-and-watch"),
+        "g&w"             => Some("game-and-watch"),
+        "gaw"             => Some("game-and-watch"),
+        "gw"              => Some("game-and-watch"),
+        "gnw"             => Some("game-and-watch"),
+        "ganondorf"       => Some("ganondorf"),
+        "ganon"           => Some("ganondorf"),
+        "dorf"            => Some("ganondorf"),
+        "giga bowser"     => Some("giga-bowser"),
+        "gigabowser"      => Some("giga-bowser"),
+        "gb"              => Some("giga-bowser"),
+        "iceclimbers"     => Some("ice-climbers"),
+        "iceclimber"      => Some("ice-climbers"),
+        "ice"             => Some("ice-climbers"),
+        "ic"              => Some("ice-climbers"),
+        "ics"             => Some("ice-climbers"),
+        "ices"            => Some("ice-climbers"),
+        "icies"           => Some("ice-climbers"),
+        "ike"             => Some("ike"),
+        "ivysaur"         => Some("ivysaur"),
+        "ivy"             => Some("ivysaur"),
+        "jigglypuff"      => Some("jigglypuff"),
+        "jiggly"          => Some("jigglypuff"),
+        "jiggs"           => Some("jigglypuff"),
+        "puff"            => Some("jigglypuff"),
+        "kingdedede"      => Some("king-dedede"),
+        "king"            => Some("king-dedede"),
+        "dedede"          => Some("king-dedede"),
+        "d3"              => Some("king-dedede"),
+        "ddd"             => Some("king-dedede"),
+        "kd"              => Some("king-dedede"),
+        "kirby"           => Some("kirby"),
+        "kirb"            => Some("kirby"),
+        "link"            => Some("link"),
+        "lucario"         => Some("lucario"),
+        "lucas"           => Some("lucas"),
+        "luigi"           => Some("luigi"),
+        "mario"           => Some("mario"),
+        "marth"           => Some("marth"),
+        "swordball"       => Some("meta-knight"),
+        "meta"            => Some("meta-knight"),
+        "metaknight"      => Some("meta-knight"),
+        "mk"              => Some("meta-knight"),
+        "ness"            => Some("ness"),
+        "olimar"          => Some("olimar"),
+        "oli"             => Some("olimar"),
+        "peach"           => Some("peach"),
+        "pikachu"         => Some("pikachu"),
+        "pika"            => Some("pikachu"),
+        "pit"             => Some("pit"),
+        "rob"             => Some("rob"),
+        "r.o.b"           => Some("rob"),
+        "samus"           => Some("samus"),
+        "sheik"           => Some("sheik"),
+        "solid"           => Some("snake"),
+        "solidsnake"      => Some("snake"),
+        "snake"           => Some("snake"),
+        "sonic"           => Some("sonic"),
+        "squirtle"        => Some("squirtle"),
+        "squirt"          => Some("squirtle"),
+        "toon"            => Some("toon-link"),
+        "toonlink"        => Some("toon-link"),
+        "tink"            => Some("toon-link"),
+        "tlink"           => Some("toon-link"),
+        "tl"              => Some("toon-link"),
+        "wario"           => Some("wario"),
+        "wario-man"       => Some("wario-man"),
+        "warioman"        => Some("wario-man"),
+        "wolf"            => Some("wolf"),
+        "yoshi"           => Some("yoshi"),
+        "yosh"            => Some("yoshi"),
+        "zelda"           => Some("zelda"),
+        "zero"            => Some("zero-suit-samus"),
+        "zerosuitsamus"   => Some("zero-suit-samus"),
+        "zss"             => Some("zero-suit-samus"),
         _                 => None,
     }
 }
@@ -116,10 +116,10 @@ pub fn brawl(token: &str) -> Option<&'static str> {
 #[rustfmt::skip]
 pub fn pm(token: &str) -> Option<&'static str> {
     match token {
-        "mewtwo" => Some("Mewtwo"),
-        "mew2"   => Some("Mewtwo"),
-        "m2"     => Some("Mewtwo"),
-        "roy"    => Some("Roy"),
+        "mewtwo" => Some("mewtwo"),
+        "mew2"   => Some("mewtwo"),
+        "m2"     => Some("mewtwo"),
+        "roy"    => Some("roy"),
         _        => None,
     }
 }
@@ -127,11 +127,11 @@ pub fn pm(token: &str) -> Option<&'static str> {
 #[rustfmt::skip]
 pub fn pplus(token: &str) -> Option<&'static str> {
     match token {
-        "knuckles"      => Some("Knuckles"),
-        "knucks"        => Some("Knuckles"),
-        "knux"          => Some("Knuckles"),
-        "fightingfreak" => Some("Knuckles"),
-        "&"             => Some("Knuckles"),
+        "knuckles"      => Some("knuckles"),
+        "knucks"        => Some("knuckles"),
+        "knux"          => Some("knuckles"),
+        "fightingfreak" => Some("knuckles"),
+        "&"             => Some("knuckles"),
         _               => None,
     }
 }
@@ -140,14 +140,14 @@ pub fn pplus(token: &str) -> Option<&'static str> {
 // Sssssh
 pub fn secret(token: &str) -> Option<&'static str> {
     match token {
-        "knuckles"      => Some("Knuckles"),
-        "knucks"        => Some("Knuckles"),
-        "knux"          => Some("Knuckles"),
-        "fightingfreak" => Some("Knuckles"),
-        "&"             => Some("Knuckles"),
-        "donald"        => Some("Donald"),
-        "ronald"        => Some("Donald"),
-        "mcdonald"      => Some("Donald"),
+        "knuckles"      => Some("knuckles"),
+        "knucks"        => Some("knuckles"),
+        "knux"          => Some("knuckles"),
+        "fightingfreak" => Some("knuckles"),
+        "&"             => Some("knuckles"),
+        "donald"        => Some("donald"),
+        "ronald"        => Some("donald"),
+        "mcdonald"      => Some("donald"),
         _               => None,
     }
 }
@@ -155,35 +155,35 @@ pub fn secret(token: &str) -> Option<&'static str> {
 #[rustfmt::skip]
 pub fn lxp(token: &str) -> Option<&'static str> {
     match token {
-        "doctor"        => Some("DoctorMario"),
-        "doctormario"   => Some("DoctorMario"),
-        "doc"           => Some("DoctorMario"),
-        "dr"            => Some("DoctorMario"),
-        "drmario"       => Some("DoctorMario"),
-        "dm"            => Some("DoctorMario"),
-        "mage"          => Some("Ganon-Mage"),
-        "mageganon"     => Some("Ganon-Mage"),
-        "mg"            => Some("Ganon-Mage"),
-        "geno"          => Some("Geno"),
-        "lucina"        => Some("Lucina"),
-        "metal"         => Some("MetalSonic"),
-        "metalsonic"    => Some("MetalSonic"),
-        "ms"            => Some("MetalSonic"),
-        "pichu"         => Some("Pichu"),
-        "ridley"        => Some("Ridley-Classic"),
-        "classic"       => Some("Ridley-Classic"),
-        "classicridley" => Some("Ridley-Classic"),
-        "ridleyclassic" => Some("Ridley-Classic"),
-        "modern"        => Some("Ridley-Modern"),
-        "modernridley"  => Some("Ridley-Modern"),
-        "ridleymodern"  => Some("Ridley-Modern"),
-        "shadow"        => Some("Shadow"),
-        "waluigi"       => Some("Waluigi"),
-        "yl"            => Some("YoungLink"),
-        "young"         => Some("YoungLink"),
-        "younglink"     => Some("YoungLink"),
-        "yink"          => Some("YoungLink"),
-        "ylink"         => Some("YoungLink"),
+        "doctor"        => Some("doctormario"),
+        "doctormario"   => Some("doctormario"),
+        "doc"           => Some("doctormario"),
+        "dr"            => Some("doctormario"),
+        "drmario"       => Some("doctormario"),
+        "dm"            => Some("doctormario"),
+        "mage"          => Some("ganon-mage"),
+        "mageganon"     => Some("ganon-mage"),
+        "mg"            => Some("ganon-mage"),
+        "geno"          => Some("geno"),
+        "lucina"        => Some("lucina"),
+        "metal"         => Some("metalsonic"),
+        "metalsonic"    => Some("metalsonic"),
+        "ms"            => Some("metalsonic"),
+        "pichu"         => Some("pichu"),
+        "ridley"        => Some("ridley-classic"),
+        "classic"       => Some("ridley-classic"),
+        "classicridley" => Some("ridley-classic"),
+        "ridleyclassic" => Some("ridley-classic"),
+        "modern"        => Some("ridley-modern"),
+        "modernridley"  => Some("ridley-modern"),
+        "ridleymodern"  => Some("ridley-modern"),
+        "shadow"        => Some("shadow"),
+        "waluigi"       => Some("waluigi"),
+        "yl"            => Some("younglink"),
+        "young"         => Some("younglink"),
+        "younglink"     => Some("younglink"),
+        "yink"          => Some("younglink"),
+        "ylink"         => Some("younglink"),
         _               => None,
     }
 }
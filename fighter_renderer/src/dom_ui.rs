@@ -1,5 +1,7 @@
 use std::sync::mpsc::Sender;
 
+use crate::script_stepper::FrameTrace;
+
 use brawllib_rs::renderer::app::state::{
     AppEventIncoming, AppEventOutgoing, AppEventOutgoingHandler, InvulnerableType, State,
 };
@@ -13,6 +15,7 @@ pub fn init(
     document: &Document,
     event_tx: Sender<AppEventIncoming>,
     frames_len: usize,
+    script_traces: Vec<FrameTrace>,
 ) -> AppEventOutgoingHandler {
     setup_frame_buttons(document, event_tx.clone(), frames_len);
     setup_run_toggle(document, event_tx.clone());
@@ -30,10 +33,10 @@ pub fn init(
         .unwrap();
     event_tx.send(AppEventIncoming::SetFrame(0)).unwrap();
 
-    Box::new(move |event| app_event_handler(event, frames_len))
+    Box::new(move |event| app_event_handler(event, frames_len, &script_traces))
 }
 
-fn app_event_handler(event: AppEventOutgoing, frames_len: usize) {
+fn app_event_handler(event: AppEventOutgoing, frames_len: usize, script_traces: &[FrameTrace]) {
     let document = web_sys::window().unwrap().document().unwrap();
 
     match event {
@@ -65,6 +68,10 @@ fn app_event_handler(event: AppEventOutgoing, frames_len: usize) {
                 .class_list()
                 .add_1("current-frame-button")
                 .unwrap();
+
+            if let Some(trace) = script_traces.get(frame) {
+                show_script_trace(&document, trace);
+            }
         }
         AppEventOutgoing::NewInvulnerableType(invulnerable_type) => {
             let checkbox = document.get_element_by_id("invulnerable-select").unwrap();
@@ -101,6 +108,27 @@ fn app_event_handler(event: AppEventOutgoing, frames_len: usize) {
     }
 }
 
+/// Highlights the script events that ran on this frame and displays the state of the scripts.
+fn show_script_trace(document: &Document, trace: &FrameTrace) {
+    // The collection is live, so removing the class also removes the element from it.
+    let executed = document.get_elements_by_class_name("script-event-executed");
+    while let Some(element) = executed.item(0) {
+        element
+            .class_list()
+            .remove_1("script-event-executed")
+            .unwrap();
+    }
+    for id in &trace.executed {
+        if let Some(element) = document.get_element_by_id(id) {
+            element.class_list().add_1("script-event-executed").unwrap();
+        }
+    }
+
+    if let Some(state) = document.get_element_by_id("script-state") {
+        state.set_inner_html(&trace.state);
+    }
+}
+
 fn setup_frame_buttons(document: &Document, event_tx: Sender<AppEventIncoming>, frames_len: usize) {
     for i in 0..frames_len {
         let event_tx = event_tx.clone();
@@ -0,0 +1,134 @@
+//! The knockback calculator on subaction pages.
+//!
+//! The formulas are shared with the website's kill percents through `website/src/knockback_formulas.rs`.
+//! The hitboxes and targets are embedded in the page as json by the website.
+
+use crate::knockback_formulas::{
+    BlastZone, KnockbackHit, KnockbackTarget, hitstun, kill_percent, knockback, launch_angle,
+};
+use serde::Deserialize;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use web_sys::{Document, HtmlElement, HtmlInputElement, HtmlSelectElement};
+
+/// Knockback at or above this puts the target into tumble.
+const TUMBLE_KNOCKBACK: f32 = 80.0;
+
+#[derive(Deserialize)]
+struct CalculatorData {
+    targets: Vec<KnockbackTarget>,
+    blast_zone: BlastZone,
+    hits: Vec<Hit>,
+}
+
+#[derive(Deserialize)]
+struct Hit {
+    frames: String,
+    set_id: u8,
+    hitbox_id: u8,
+    #[serde(flatten)]
+    knockback: KnockbackHit,
+}
+
+/// Does nothing when the page has no knockback calculator.
+pub fn init(document: &Document) {
+    let Some(data) = document.get_element_by_id("knockback-data") else {
+        return;
+    };
+    let data: CalculatorData = serde_json::from_str(&data.text_content().unwrap()).unwrap();
+
+    let select = document.get_element_by_id("knockback-target").unwrap();
+    let mut options = String::new();
+    for (i, target) in data.targets.iter().enumerate() {
+        options.push_str(&format!("<option value=\"{}\">{}</option>", i, target.name));
+    }
+    select.set_inner_html(&options);
+    input(document, "knockback-blast-horizontal")
+        .set_value(&data.blast_zone.horizontal.to_string());
+    input(document, "knockback-blast-vertical").set_value(&data.blast_zone.vertical.to_string());
+
+    let data = Rc::new(data);
+    for id in [
+        "knockback-target",
+        "knockback-percent",
+        "knockback-aerial",
+        "knockback-blast-horizontal",
+        "knockback-blast-vertical",
+    ] {
+        let data = data.clone();
+        let closure = Closure::wrap(Box::new(move || update(&data)) as Box<dyn FnMut()>);
+        let element = document.get_element_by_id(id).unwrap();
+        let element = element.dyn_ref::<HtmlElement>().unwrap();
+        element.set_oninput(Some(closure.as_ref().unchecked_ref()));
+        element.set_onchange(Some(closure.as_ref().unchecked_ref()));
+
+        // Need to forget closure otherwise the destructor destroys it ;-;
+        closure.forget();
+    }
+
+    update(&data);
+}
+
+fn input(document: &Document, id: &str) -> HtmlInputElement {
+    document
+        .get_element_by_id(id)
+        .unwrap()
+        .dyn_into::<HtmlInputElement>()
+        .unwrap()
+}
+
+fn update(data: &CalculatorData) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let select = document.get_element_by_id("knockback-target").unwrap();
+    let target_index: usize = select
+        .dyn_ref::<HtmlSelectElement>()
+        .unwrap()
+        .value()
+        .parse()
+        .unwrap_or(0);
+    let Some(target) = data.targets.get(target_index) else {
+        return;
+    };
+    let percent: f32 = input(&document, "knockback-percent")
+        .value()
+        .parse()
+        .unwrap_or(0.0);
+    let aerial = input(&document, "knockback-aerial").checked();
+    let blast_zone = BlastZone {
+        horizontal: input(&document, "knockback-blast-horizontal")
+            .value()
+            .parse()
+            .unwrap_or(data.blast_zone.horizontal),
+        vertical: input(&document, "knockback-blast-vertical")
+            .value()
+            .parse()
+            .unwrap_or(data.blast_zone.vertical),
+    };
+
+    let mut rows = String::new();
+    for hit in &data.hits {
+        let knockback = knockback(&hit.knockback, percent, target.weight);
+        let angle = launch_angle(hit.knockback.angle, knockback, aerial);
+        let kill_percent = kill_percent(&hit.knockback, target, aerial, &blast_zone)
+            .map(|x| x.to_string())
+            .unwrap_or_else(|| "None".into());
+        rows.push_str(&format!(
+            "<tr class=\"hitbox-table-id-{}\"><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.3}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            hit.hitbox_id,
+            hit.frames,
+            hit.set_id,
+            hit.hitbox_id,
+            knockback,
+            knockback * 0.03,
+            angle,
+            hitstun(knockback),
+            if knockback >= TUMBLE_KNOCKBACK { "Yes" } else { "No" },
+            kill_percent,
+        ));
+    }
+    document
+        .get_element_by_id("knockback-results")
+        .unwrap()
+        .set_inner_html(&rows);
+}
@@ -4,11 +4,18 @@ use base64::{Engine as _, engine::general_purpose};
 use brawllib_rs::high_level_fighter::HighLevelSubaction;
 use brawllib_rs::renderer::app::App;
 use log::Level;
+use std::collections::BTreeMap;
 use wasm_bindgen::prelude::*;
 use web_sys::Document;
 
 mod dom_ui;
 mod hitbox_table_angles;
+mod knockback_calculator;
+#[path = "../../website/src/knockback_formulas.rs"]
+mod knockback_formulas;
+mod script_stepper;
+#[path = "../../website/src/variable_key.rs"]
+mod variable_key;
 
 #[wasm_bindgen]
 pub fn run(subaction_bincode: String) {
@@ -21,6 +28,7 @@ pub fn run(subaction_bincode: String) {
 async fn run_async(subaction_bincode: String) {
     let document = web_sys::window().unwrap().document().unwrap();
     hitbox_table_angles::draw_hitbox_table_angles(&document);
+    knockback_calculator::init(&document);
 
     let subaction = get_subaction(&subaction_bincode).await;
 
@@ -37,9 +45,19 @@ async fn get_subaction(subaction_bincode: &str) -> HighLevelSubaction {
 pub async fn run_renderer(document: Document, subaction: HighLevelSubaction) {
     let visualiser_span = document.get_element_by_id("fighter-render").unwrap();
     let frames_len = subaction.frames.len();
+    let variable_names = match document.get_element_by_id("variable-names") {
+        Some(element) => serde_json::from_str(&element.text_content().unwrap()).unwrap(),
+        None => BTreeMap::new(),
+    };
+    let script_traces = script_stepper::trace(&subaction, frames_len, &variable_names);
     let mut app = App::new_insert_into_element(visualiser_span, subaction).await;
 
-    app.set_event_handler(dom_ui::init(&document, app.get_event_tx(), frames_len));
+    app.set_event_handler(dom_ui::init(
+        &document,
+        app.get_event_tx(),
+        frames_len,
+        script_traces,
+    ));
 
     app.run();
 }
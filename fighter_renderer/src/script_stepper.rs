@@ -0,0 +1,527 @@
+//! Steps through the scripts of a subaction, recording which events run on each frame so the
+//! script listings on the page can highlight them in sync with the renderer.
+//!
+//! The control flow follows brawllib_rs's ScriptRunner, which can't be used directly as it needs the whole fighter.
+//! Without the fighter, Subroutine and Goto events are highlighted when they run but the scripts they point to are not followed.
+//! Requirements that depend on the game state are evaluated the same way ScriptRunner does, as if standing on the ground facing right.
+
+use brawllib_rs::high_level_fighter::HighLevelSubaction;
+use brawllib_rs::script::{Requirement, VariableDataType};
+use brawllib_rs::script_ast::variable_ast::VariableAst;
+use brawllib_rs::script_ast::{
+    Block, ComparisonOperator, EventAst, Expression, FloatValue, ForLoop, IfStatement, Iterations,
+};
+use std::collections::BTreeMap;
+
+use crate::variable_key::VariableKey;
+
+/// What the scripts did on a single frame.
+pub struct FrameTrace {
+    /// Ids of the `<li>` elements of every event that ran this frame.
+    pub executed: Vec<String>,
+    /// HTML describing the wait timers, loops and variables after this frame.
+    pub state: String,
+}
+
+/// Returns one trace for each frame of the subaction.
+/// `variable_names` are the names the website gives the variables, keyed by e.g. `LA-Basic[1]`.
+pub fn trace(
+    subaction: &HighLevelSubaction,
+    frames_len: usize,
+    variable_names: &BTreeMap<String, String>,
+) -> Vec<FrameTrace> {
+    let scripts = &subaction.scripts;
+    let scripts = [
+        &scripts.script_main.block,
+        &scripts.script_gfx.block,
+        &scripts.script_sfx.block,
+        &scripts.script_other.block,
+    ];
+    trace_scripts(scripts, frames_len, variable_names)
+}
+
+/// Same as `trace` for the main, GFX, SFX and other scripts.
+fn trace_scripts(
+    [main, gfx, sfx, other]: [&Block; 4],
+    frames_len: usize,
+    variable_names: &BTreeMap<String, String>,
+) -> Vec<FrameTrace> {
+    let mut stepper = Stepper {
+        scripts: vec![
+            Script::new("Main", "event-main", main),
+            Script::new("GFX", "event-gfx", gfx),
+            Script::new("SFX", "event-sfx", sfx),
+            Script::new("Other", "event-other", other),
+        ],
+        frame_index: 0.0,
+        frame_speed_modifier: 1.0,
+        variables: BTreeMap::new(),
+        variable_names,
+    };
+
+    let mut traces = Vec::with_capacity(frames_len);
+    for frame in 0..frames_len {
+        if frame != 0 {
+            stepper.frame_index += stepper.frame_speed_modifier;
+        }
+        let executed = stepper.step();
+        traces.push(FrameTrace {
+            executed,
+            state: stepper.state_html(frame),
+        });
+    }
+    traces
+}
+
+struct Stepper<'a> {
+    scripts: Vec<Script<'a>>,
+    frame_index: f32,
+    frame_speed_modifier: f32,
+    variables: BTreeMap<VariableKey, Value>,
+    variable_names: &'a BTreeMap<String, String>,
+}
+
+struct Script<'a> {
+    name: &'static str,
+    calls: Vec<Call<'a>>,
+    wait_until: f32,
+}
+
+impl<'a> Script<'a> {
+    fn new(name: &'static str, id_prefix: &str, block: &'a Block) -> Self {
+        Script {
+            name,
+            calls: vec![Call::new(block, id_prefix.to_string())],
+            wait_until: 0.0,
+        }
+    }
+}
+
+struct Call<'a> {
+    block: &'a Block,
+    /// Ids of the events in this block are `{id_prefix}-{index}`, matching process_events_with_ids in the website.
+    id_prefix: String,
+    index: usize,
+    else_branch: Option<&'a Block>,
+    if_statement: bool,
+    execute: bool,
+    /// (current iteration, total iterations) when the block is the body of a ForLoop.
+    iterations: Option<(i32, i32)>,
+}
+
+impl<'a> Call<'a> {
+    fn new(block: &'a Block, id_prefix: String) -> Self {
+        Call {
+            block,
+            id_prefix,
+            index: 0,
+            else_branch: None,
+            if_statement: false,
+            execute: true,
+            iterations: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Value {
+    Int(i32),
+    Float(f32),
+    Bool(bool),
+}
+
+impl Value {
+    fn int(self) -> i32 {
+        match self {
+            Value::Int(value) => value,
+            Value::Float(value) => value as i32,
+            Value::Bool(value) => value as i32,
+        }
+    }
+
+    fn float(self) -> f32 {
+        match self {
+            Value::Int(value) => value as f32,
+            Value::Float(value) => value,
+            Value::Bool(value) => value as i32 as f32,
+        }
+    }
+
+    fn bool(self) -> bool {
+        match self {
+            Value::Int(value) => value != 0,
+            Value::Float(value) => value != 0.0,
+            Value::Bool(value) => value,
+        }
+    }
+}
+
+impl<'a> Stepper<'a> {
+    /// Runs every script until it reaches a wait that hasn't finished yet, returning the ids of the events that ran.
+    fn step(&mut self) -> Vec<String> {
+        let mut executed = vec![];
+        for script_index in 0..self.scripts.len() {
+            loop {
+                let script = &mut self.scripts[script_index];
+                if self.frame_index < script.wait_until {
+                    break;
+                }
+                let Some(call) = script.calls.last_mut() else {
+                    break;
+                };
+
+                let block: &'a Block = call.block;
+                let Some(event) = block.events.get(call.index) else {
+                    // Reached the end of the block
+                    if let Some(else_branch) = call.else_branch.take() {
+                        call.block = else_branch;
+                        call.index = 0;
+                        call.execute = !call.execute;
+                        call.id_prefix = format!("{}-else", call.id_prefix);
+                    } else if let Some((iteration, total)) = call
+                        .iterations
+                        .filter(|(iteration, total)| iteration < total)
+                    {
+                        call.iterations = Some((iteration + 1, total));
+                        call.index = 0;
+                    } else {
+                        script.calls.pop();
+                    }
+                    continue;
+                };
+                let id = format!("{}-{}", call.id_prefix, call.index);
+                call.index += 1;
+
+                if !call.execute {
+                    // Only an Or can resume execution of a failed if statement.
+                    if let EventAst::IfStatementOr(test) = event {
+                        let if_statement = call.if_statement;
+                        if if_statement && self.evaluate(test).bool() {
+                            self.scripts[script_index].calls.last_mut().unwrap().execute = true;
+                            executed.push(id);
+                        }
+                    }
+                    continue;
+                }
+                executed.push(id.clone());
+
+                match event {
+                    EventAst::SyncWait(value) => {
+                        self.scripts[script_index].wait_until = self.frame_index + *value;
+                    }
+                    EventAst::AsyncWait(value) => {
+                        self.scripts[script_index].wait_until = *value;
+                    }
+                    EventAst::ForLoop(ForLoop { iterations, block }) => {
+                        let total = match iterations {
+                            Iterations::Finite(total) => *total,
+                            // Matches ScriptRunner which also only runs infinite loops once.
+                            Iterations::Infinite => 1,
+                        };
+                        if total > 0 {
+                            let mut call = Call::new(block, id);
+                            call.iterations = Some((1, total));
+                            self.scripts[script_index].calls.push(call);
+                        }
+                    }
+                    EventAst::IfStatement(IfStatement {
+                        test,
+                        then_branch,
+                        else_branch,
+                    }) => {
+                        let mut call = Call::new(then_branch, id);
+                        call.execute = self.evaluate(test).bool();
+                        call.else_branch = else_branch.as_deref();
+                        call.if_statement = true;
+                        self.scripts[script_index].calls.push(call);
+                    }
+                    EventAst::IfStatementAnd(test) => {
+                        if !self.evaluate(test).bool() {
+                            let call = self.scripts[script_index].calls.last_mut().unwrap();
+                            if call.if_statement {
+                                call.execute = false;
+                            }
+                        }
+                    }
+                    EventAst::FrameSpeedModifier { multiplier, .. } => {
+                        self.frame_speed_modifier = *multiplier;
+                    }
+                    EventAst::IntVariableSet { value, variable } => {
+                        self.set(variable, Value::Int(*value))
+                    }
+                    EventAst::IntVariableAdd { value, variable } => {
+                        let current = self.get(variable).int();
+                        self.set(variable, Value::Int(current.wrapping_add(*value)))
+                    }
+                    EventAst::IntVariableSubtract { value, variable } => {
+                        let current = self.get(variable).int();
+                        self.set(variable, Value::Int(current.wrapping_sub(*value)))
+                    }
+                    EventAst::IntVariableIncrement { variable } => {
+                        let current = self.get(variable).int();
+                        self.set(variable, Value::Int(current.wrapping_add(1)))
+                    }
+                    EventAst::IntVariableDecrement { variable } => {
+                        let current = self.get(variable).int();
+                        self.set(variable, Value::Int(current.wrapping_sub(1)))
+                    }
+                    EventAst::FloatVariableSet { value, variable } => {
+                        let value = self.float_value(value);
+                        self.set(variable, Value::Float(value))
+                    }
+                    EventAst::FloatVariableAdd { value, variable } => {
+                        let value = self.get(variable).float() + self.float_value(value);
+                        self.set(variable, Value::Float(value))
+                    }
+                    EventAst::FloatVariableSubtract { value, variable } => {
+                        let value = self.get(variable).float() - self.float_value(value);
+                        self.set(variable, Value::Float(value))
+                    }
+                    EventAst::FloatVariableMultiply { value, variable } => {
+                        let value = self.get(variable).float() * self.float_value(value);
+                        self.set(variable, Value::Float(value))
+                    }
+                    EventAst::FloatVariableDivide { value, variable } => {
+                        let value = self.get(variable).float() / self.float_value(value);
+                        self.set(variable, Value::Float(value))
+                    }
+                    EventAst::BoolVariableSetTrue { variable } => {
+                        self.set(variable, Value::Bool(true))
+                    }
+                    EventAst::BoolVariableSetFalse { variable } => {
+                        self.set(variable, Value::Bool(false))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        executed
+    }
+
+    fn get(&self, variable: &VariableAst) -> Value {
+        self.variables
+            .get(&VariableKey::new(variable))
+            .copied()
+            .unwrap_or(Value::Int(0))
+    }
+
+    /// Stores the value converted to the type of the variable, variables of unknown type are ignored.
+    fn set(&mut self, variable: &VariableAst, value: Value) {
+        let value = match variable.data_type() {
+            VariableDataType::Int => Value::Int(value.int()),
+            VariableDataType::Float => Value::Float(value.float()),
+            VariableDataType::Bool => Value::Bool(value.bool()),
+            VariableDataType::Unknown(_) => return,
+        };
+        self.variables.insert(VariableKey::new(variable), value);
+    }
+
+    fn float_value(&self, value: &FloatValue) -> f32 {
+        match value {
+            FloatValue::Constant(value) => *value,
+            FloatValue::Variable(variable) => self.get(variable).float(),
+        }
+    }
+
+    fn evaluate(&self, expression: &Expression) -> Value {
+        match expression {
+            Expression::Nullary(requirement) => Value::Bool(requirement_default(requirement)),
+            Expression::Unary(unary) => Value::Bool(match unary.requirement {
+                Requirement::BoolIsTrue => self.evaluate(&unary.value).bool(),
+                ref requirement => requirement_default(requirement),
+            }),
+            Expression::Binary(binary) => {
+                let left = self.evaluate(&binary.left);
+                let right = self.evaluate(&binary.right);
+                let compare = |op: fn(f32, f32) -> bool| match (left, right) {
+                    (Value::Int(left), Value::Int(right)) => op(left as f32, right as f32),
+                    _ => op(left.float(), right.float()),
+                };
+                Value::Bool(match binary.operator {
+                    ComparisonOperator::LessThan => compare(|a, b| a < b),
+                    ComparisonOperator::LessThanOrEqual => compare(|a, b| a <= b),
+                    ComparisonOperator::Equal => compare(|a, b| a == b),
+                    ComparisonOperator::NotEqual => compare(|a, b| a != b),
+                    ComparisonOperator::GreaterThanOrEqual => compare(|a, b| a >= b),
+                    ComparisonOperator::GreaterThan => compare(|a, b| a > b),
+                    ComparisonOperator::Or => left.bool() || right.bool(),
+                    ComparisonOperator::And => left.bool() && right.bool(),
+                    ComparisonOperator::UnknownArg(_) => false,
+                })
+            }
+            Expression::Not(expression) => Value::Bool(!self.evaluate(expression).bool()),
+            Expression::Variable(variable) => match variable.data_type() {
+                VariableDataType::Int => Value::Int(self.get(variable).int()),
+                VariableDataType::Float => Value::Float(self.get(variable).float()),
+                VariableDataType::Bool => Value::Bool(self.get(variable).bool()),
+                VariableDataType::Unknown(_) => Value::Bool(false),
+            },
+            Expression::Value(value) => Value::Int(*value),
+            Expression::Scalar(value) => Value::Float(*value),
+        }
+    }
+
+    fn state_html(&self, frame: usize) -> String {
+        let mut html = format!(
+            "<p>Frame {} (script frame {})</p><table class=\"script-state-table\"><tr><th>Script</th><th>State</th></tr>",
+            frame + 1,
+            self.frame_index
+        );
+        for script in &self.scripts {
+            let mut state = if script.calls.is_empty() {
+                String::from("Finished")
+            } else if self.frame_index < script.wait_until {
+                format!("Waiting until frame {}", script.wait_until)
+            } else {
+                String::from("Running")
+            };
+            for call in &script.calls {
+                if let Some((iteration, total)) = call.iterations {
+                    state.push_str(&format!(", loop iteration {iteration} of {total}"));
+                }
+            }
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td></tr>",
+                script.name, state
+            ));
+        }
+        html.push_str("</table>");
+
+        if !self.variables.is_empty() {
+            html.push_str(
+                "<table class=\"script-state-table\"><tr><th>Variable</th><th>Value</th></tr>",
+            );
+            for (key, value) in &self.variables {
+                let key = key.to_string();
+                let name = self.variable_names.get(&key).unwrap_or(&key);
+                let value = match value {
+                    Value::Int(value) => value.to_string(),
+                    Value::Float(value) => value.to_string(),
+                    Value::Bool(value) => value.to_string(),
+                };
+                html.push_str(&format!("<tr><td>{name}</td><td>{value}</td></tr>"));
+            }
+            html.push_str("</table>");
+        }
+        html
+    }
+}
+
+/// Game state requirements can't be known outside of the game, these match the values ScriptRunner uses.
+fn requirement_default(requirement: &Requirement) -> bool {
+    matches!(
+        requirement,
+        Requirement::CharacterExists
+            | Requirement::OnGround
+            | Requirement::FacingRight
+            | Requirement::HasntTethered3Times
+            | Requirement::IsNotInDamagingLens
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use brawllib_rs::script::{Variable, VariableMemoryType};
+
+    fn variable(address: u32) -> VariableAst {
+        VariableAst::new(&Variable {
+            memory_type: VariableMemoryType::RandomAccess,
+            data_type: VariableDataType::Int,
+            address,
+        })
+    }
+
+    #[test]
+    fn trace_waits_and_variables() {
+        let main = Block {
+            events: vec![
+                EventAst::IntVariableSet {
+                    value: 3,
+                    variable: variable(100),
+                },
+                EventAst::SyncWait(2.0),
+                EventAst::IntVariableAdd {
+                    value: 2,
+                    variable: variable(100),
+                },
+                EventAst::SyncWait(1.0),
+                EventAst::IntVariableIncrement {
+                    variable: variable(101),
+                },
+            ],
+        };
+        let gfx = Block {
+            events: vec![EventAst::AsyncWait(1.0), EventAst::Nop],
+        };
+        let empty = Block { events: vec![] };
+        let names = BTreeMap::from([("RA-Basic[100]".to_string(), "Charge".to_string())]);
+
+        let traces = trace_scripts([&main, &gfx, &empty, &empty], 5, &names);
+        let executed: Vec<_> = traces.iter().map(|x| x.executed.clone()).collect();
+        assert_eq!(
+            executed,
+            [
+                vec!["event-main-0", "event-main-1", "event-gfx-0"],
+                vec!["event-gfx-1"],
+                vec!["event-main-2", "event-main-3"],
+                vec!["event-main-4"],
+                vec![],
+            ]
+        );
+
+        assert!(
+            traces[0]
+                .state
+                .contains("<tr><td>Main</td><td>Waiting until frame 2</td></tr>")
+        );
+        assert!(
+            traces[0]
+                .state
+                .contains("<tr><td>Charge</td><td>3</td></tr>")
+        );
+        assert!(
+            traces[2]
+                .state
+                .contains("<tr><td>Charge</td><td>5</td></tr>")
+        );
+        assert!(
+            traces[3]
+                .state
+                .contains("<tr><td>Main</td><td>Finished</td></tr>")
+        );
+        // Variables without a name are shown by their address.
+        assert!(!traces[2].state.contains("RA-Basic[101]"));
+        assert!(
+            traces[3]
+                .state
+                .contains("<tr><td>RA-Basic[101]</td><td>1</td></tr>")
+        );
+    }
+
+    #[test]
+    fn trace_loop() {
+        let main = Block {
+            events: vec![EventAst::ForLoop(ForLoop {
+                iterations: Iterations::Finite(2),
+                block: Block {
+                    events: vec![EventAst::SyncWait(1.0)],
+                },
+            })],
+        };
+        let empty = Block { events: vec![] };
+
+        let traces = trace_scripts([&main, &empty, &empty, &empty], 3, &BTreeMap::new());
+        let executed: Vec<_> = traces.iter().map(|x| x.executed.clone()).collect();
+        assert_eq!(
+            executed,
+            [
+                vec!["event-main-0", "event-main-0-0"],
+                vec!["event-main-0-0"],
+                vec![],
+            ]
+        );
+        assert!(traces[0].state.contains("loop iteration 1 of 2"));
+        assert!(traces[1].state.contains("loop iteration 2 of 2"));
+    }
+}
@@ -0,0 +1,416 @@
+//! Builds the graph of which actions a fighter can transition into from each action.
+//!
+//! Edges come from:
+//! * `ChangeSubaction` events in action scripts, linking the action to the subactions it plays.
+//! * `CreateInterrupt` events in action scripts and the scripts of those subactions, linking to the action interrupted into.
+//! * `EnableInterruptGroup` events, linking to a node for the common interrupt group, which links to every action in the group.
+
+use crate::brawl_data::{BrawlFighter, BrawlMod};
+use crate::process_scripts;
+use crate::script_walker::ScriptWalker;
+use brawllib_rs::script_ast::{EventAst, Interrupt, InterruptType};
+use handlebars::html_escape;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+
+const NODE_WIDTH: usize = 230;
+const NODE_HEIGHT: usize = 24;
+const LAYER_GAP: usize = 120;
+const NODE_GAP: usize = 10;
+const MARGIN: usize = 10;
+
+pub struct ActionGraph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+pub struct Node {
+    pub name: String,
+    pub link: Option<String>,
+    pub kind: NodeKind,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum NodeKind {
+    Action,
+    Subaction,
+    InterruptGroup,
+}
+
+impl NodeKind {
+    fn class(&self) -> &'static str {
+        match self {
+            NodeKind::Action => "graph-node-action",
+            NodeKind::Subaction => "graph-node-subaction",
+            NodeKind::InterruptGroup => "graph-node-group",
+        }
+    }
+}
+
+#[derive(PartialEq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub kind: &'static str,
+    /// The requirement of an interrupt, empty for other kinds of edges
+    pub requirement: String,
+}
+
+pub struct Transition<'a> {
+    /// The subaction or interrupt group the transition is made through
+    pub via: Option<&'a Node>,
+    pub edge: &'a Edge,
+}
+
+impl ActionGraph {
+    pub fn new(brawl_mod: &BrawlMod, brawl_fighter: &BrawlFighter) -> ActionGraph {
+        let fighter = &brawl_fighter.fighter;
+        let mut graph = ActionGraph {
+            nodes: vec![],
+            edges: vec![],
+        };
+
+        // The first nodes are the actions so that action indexes are also node indexes.
+        for action in &fighter.actions {
+            graph.nodes.push(Node {
+                name: action.name.clone(),
+                link: Some(format!(
+                    "/{}/actions/{}.html",
+                    brawl_mod.fighter_path(&fighter.name),
+                    action.name
+                )),
+                kind: NodeKind::Action,
+            });
+        }
+        let mut subaction_nodes = HashMap::new();
+        let mut group_nodes = HashMap::new();
+
+        for (action_index, action) in fighter.actions.iter().enumerate() {
+            let mut subactions = vec![];
+            let mut walker = ScriptWalker::new(fighter);
+            for (script, common) in [
+                (&action.script_entry, action.script_entry_common),
+                (&action.script_exit, action.script_exit_common),
+            ] {
+                walker.walk(&script.block, common, &mut |event| match event {
+                    EventAst::ChangeSubaction(index)
+                    | EventAst::ChangeSubactionRestartFrame(index) => {
+                        if let Some(subaction) = usize::try_from(*index)
+                            .ok()
+                            .and_then(|x| fighter.subactions.get(x))
+                        {
+                            let node = *subaction_nodes.entry(*index).or_insert_with(|| {
+                                graph.nodes.push(Node {
+                                    name: subaction.name.clone(),
+                                    link: Some(format!(
+                                        "/{}/subactions/{}.html",
+                                        brawl_mod.fighter_path(&fighter.name),
+                                        subaction.name
+                                    )),
+                                    kind: NodeKind::Subaction,
+                                });
+                                graph.nodes.len() - 1
+                            });
+                            subactions.push((subaction, node));
+                            graph.add_edge(action_index, node, "ChangeSubaction", String::new());
+                        }
+                    }
+                    EventAst::EnableInterruptGroup(interrupt_type) => {
+                        let name = format!("{interrupt_type:?}");
+                        let node = match group_nodes.get(&name) {
+                            Some(node) => *node,
+                            None => {
+                                graph.nodes.push(Node {
+                                    name: format!("{name} Interrupts"),
+                                    link: None,
+                                    kind: NodeKind::InterruptGroup,
+                                });
+                                let node = graph.nodes.len() - 1;
+                                for action in group_actions(interrupt_type) {
+                                    if *action < fighter.actions.len() {
+                                        graph.add_edge(node, *action, "Group", String::new());
+                                    }
+                                }
+                                group_nodes.insert(name, node);
+                                node
+                            }
+                        };
+                        graph.add_edge(action_index, node, "EnableInterruptGroup", String::new());
+                    }
+                    _ => graph.add_interrupt(brawl_fighter, action_index, event),
+                });
+            }
+
+            // Interrupts created by the subactions of an action belong to the action
+            for (subaction, subaction_node) in subactions {
+                let mut walker = ScriptWalker::new(fighter);
+                walker.walk(&subaction.scripts.script_main.block, false, &mut |event| {
+                    graph.add_interrupt(brawl_fighter, subaction_node, event)
+                });
+            }
+        }
+
+        graph
+    }
+
+    fn add_interrupt(&mut self, brawl_fighter: &BrawlFighter, from: usize, event: &EventAst) {
+        let fighter = &brawl_fighter.fighter;
+        if let EventAst::CreateInterrupt(Interrupt { action, test, .. }) = event {
+            if let Some(to) = usize::try_from(*action)
+                .ok()
+                .filter(|x| *x < fighter.actions.len())
+            {
+                self.add_edge(
+                    from,
+                    to,
+                    "CreateInterrupt",
+                    process_scripts::process_expression(test, &brawl_fighter.variable_names),
+                );
+            }
+        }
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, kind: &'static str, requirement: String) {
+        let edge = Edge {
+            from,
+            to,
+            kind,
+            requirement,
+        };
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+    }
+
+    /// Nodes connected to the node, in either direction.
+    pub fn neighbours(&self, node: usize) -> (Vec<usize>, Vec<usize>) {
+        let mut incoming = vec![];
+        let mut outgoing = vec![];
+        for edge in &self.edges {
+            if edge.to == node && !incoming.contains(&edge.from) {
+                incoming.push(edge.from);
+            }
+            if edge.from == node && !outgoing.contains(&edge.to) {
+                outgoing.push(edge.to);
+            }
+        }
+        (incoming, outgoing)
+    }
+
+    /// Renders every node with an edge, laid out in columns by the number of transitions from the first action.
+    pub fn render_svg(&self) -> String {
+        let mut layers: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        let connected: Vec<bool> = (0..self.nodes.len())
+            .map(|node| self.edges.iter().any(|x| x.from == node || x.to == node))
+            .collect();
+
+        // Breadth first search from each node not yet reached, starting with the lowest action index.
+        for root in 0..self.nodes.len() {
+            if layers[root].is_some() || !connected[root] {
+                continue;
+            }
+            layers[root] = Some(0);
+            let mut queue = VecDeque::from([root]);
+            while let Some(node) = queue.pop_front() {
+                let layer = layers[node].unwrap();
+                for edge in self.edges.iter().filter(|x| x.from == node) {
+                    if layers[edge.to].is_none() {
+                        layers[edge.to] = Some(layer + 1);
+                        queue.push_back(edge.to);
+                    }
+                }
+            }
+        }
+
+        let mut columns: Vec<Vec<usize>> = vec![];
+        for (node, layer) in layers.iter().enumerate() {
+            if let Some(layer) = layer {
+                if columns.len() <= *layer {
+                    columns.resize(layer + 1, vec![]);
+                }
+                columns[*layer].push(node);
+            }
+        }
+        self.render_columns(&columns, None)
+    }
+
+    /// Renders the nodes leading into the node on the left and the nodes it leads into on the right.
+    /// Subactions and interrupt groups are followed one step further so that the actions they lead into are also shown.
+    pub fn render_neighbourhood_svg(&self, node: usize) -> String {
+        let (incoming, outgoing) = self.neighbours(node);
+        let outgoing: Vec<usize> = outgoing.into_iter().filter(|x| *x != node).collect();
+        let incoming: Vec<usize> = incoming
+            .into_iter()
+            .filter(|x| *x != node && !outgoing.contains(x))
+            .collect();
+        let mut second = vec![];
+        for via in &outgoing {
+            if self.nodes[*via].kind != NodeKind::Action {
+                for to in self.neighbours(*via).1 {
+                    if to != node
+                        && !outgoing.contains(&to)
+                        && !incoming.contains(&to)
+                        && !second.contains(&to)
+                    {
+                        second.push(to);
+                    }
+                }
+            }
+        }
+        let mut columns = vec![incoming, vec![node], outgoing, second];
+        columns.retain(|x| !x.is_empty());
+        self.render_columns(&columns, Some(node))
+    }
+
+    /// Every edge leading to an action from the node, directly or via one of its subactions or interrupt groups.
+    pub fn transitions(&self, node: usize) -> Vec<Transition<'_>> {
+        let mut transitions = vec![];
+        for edge in self.edges.iter().filter(|x| x.from == node) {
+            if self.nodes[edge.to].kind == NodeKind::Action {
+                transitions.push(Transition { via: None, edge });
+            } else {
+                for via_edge in self.edges.iter().filter(|x| x.from == edge.to) {
+                    transitions.push(Transition {
+                        via: Some(&self.nodes[edge.to]),
+                        edge: via_edge,
+                    });
+                }
+            }
+        }
+        transitions
+    }
+
+    fn render_columns(&self, columns: &[Vec<usize>], highlight: Option<usize>) -> String {
+        let mut positions: HashMap<usize, (usize, usize)> = HashMap::new();
+        for (column_index, column) in columns.iter().enumerate() {
+            for (row, node) in column.iter().enumerate() {
+                positions.insert(
+                    *node,
+                    (
+                        MARGIN + column_index * (NODE_WIDTH + LAYER_GAP),
+                        MARGIN + row * (NODE_HEIGHT + NODE_GAP),
+                    ),
+                );
+            }
+        }
+        let width =
+            MARGIN * 2 + (columns.len() * (NODE_WIDTH + LAYER_GAP)).saturating_sub(LAYER_GAP);
+        let rows = columns.iter().map(|x| x.len()).max().unwrap_or(0);
+        let height = MARGIN * 2 + rows * (NODE_HEIGHT + NODE_GAP);
+
+        let mut svg = format!(
+            r#"<svg class="action-graph" xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        );
+        svg.push_str(r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" /></marker></defs>"#);
+
+        for edge in &self.edges {
+            let (Some((x1, y1)), Some((x2, y2))) =
+                (positions.get(&edge.from), positions.get(&edge.to))
+            else {
+                continue;
+            };
+            let y1 = y1 + NODE_HEIGHT / 2;
+            let y2 = y2 + NODE_HEIGHT / 2;
+            let x1 = x1 + NODE_WIDTH;
+            // Edges going backwards or within a column loop around to the right side of the target.
+            let (x2, control) = if *x2 >= x1 {
+                (*x2 as isize, -60)
+            } else {
+                ((x2 + NODE_WIDTH) as isize, 60)
+            };
+            let mut title = edge.kind.to_owned();
+            if !edge.requirement.is_empty() {
+                write!(title, ": {}", edge.requirement).unwrap();
+            }
+            write!(
+                svg,
+                r#"<path class="graph-edge graph-edge-{}" d="M {x1} {y1} C {} {y1}, {} {y2}, {x2} {y2}" marker-end="url(#arrow)"><title>{}</title></path>"#,
+                edge.kind,
+                x1 + 60,
+                x2 + control,
+                html_escape(&title)
+            )
+            .unwrap();
+        }
+
+        for node_index in columns.iter().flatten() {
+            let (x, y) = positions[node_index];
+            let node_index = *node_index;
+            let node = &self.nodes[node_index];
+            let class = if highlight == Some(node_index) {
+                format!("{} graph-node-current", node.kind.class())
+            } else {
+                node.kind.class().to_owned()
+            };
+            let name = html_escape(&node.name);
+            let inner = format!(
+                r#"<rect x="{x}" y="{y}" width="{NODE_WIDTH}" height="{NODE_HEIGHT}" rx="4" /><text x="{}" y="{}">{name}</text>"#,
+                x + NODE_WIDTH / 2,
+                y + NODE_HEIGHT / 2,
+            );
+            match &node.link {
+                Some(link) => write!(
+                    svg,
+                    r#"<a class="{class}" href="{}">{inner}</a>"#,
+                    html_escape(link)
+                ),
+                None => write!(svg, r#"<g class="{class}">{inner}</g>"#),
+            }
+            .unwrap();
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Renders the graph in the graphviz format for use with external tools.
+    pub fn render_dot(&self) -> String {
+        let mut dot = String::from("digraph actions {\n");
+        for (index, node) in self.nodes.iter().enumerate() {
+            if self.edges.iter().any(|x| x.from == index || x.to == index) {
+                let shape = match node.kind {
+                    NodeKind::Action => "box",
+                    NodeKind::Subaction => "ellipse",
+                    NodeKind::InterruptGroup => "diamond",
+                };
+                writeln!(dot, "    n{index} [label={:?} shape={shape}]", node.name).unwrap();
+            }
+        }
+        for edge in &self.edges {
+            writeln!(
+                dot,
+                "    n{} -> n{} [label={:?}]",
+                edge.from, edge.to, edge.requirement
+            )
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// The actions that the game engine checks for when a common interrupt group is enabled.
+/// This is an approximation based on the action names, the engine handles these internally rather than via scripts.
+fn group_actions(interrupt_type: &InterruptType) -> &'static [usize] {
+    match interrupt_type {
+        InterruptType::GroundSpecial | InterruptType::AirSpecial => &[0x112, 0x113, 0x114, 0x115],
+        InterruptType::GroundItem => &[0x96, 0x97, 0x9b, 0x9e, 0x9f, 0xa0, 0xa3],
+        InterruptType::GroundCatch => &[0x34, 0x36],
+        InterruptType::GroundAttack => &[0x24, 0x26, 0x27, 0x28, 0x29, 0x2a, 0x2d, 0x30],
+        InterruptType::GroundEscape => &[0x1e, 0x1f, 0x20],
+        InterruptType::GroundGuard => &[0x1a],
+        InterruptType::GroundJump => &[0x0a],
+        InterruptType::GroundOther => &[0x01, 0x03, 0x06, 0x11, 0x10c],
+        InterruptType::AirLanding => &[0x16, 0x17, 0x18],
+        InterruptType::CliffCatch => &[0x73],
+        InterruptType::AirItemThrow => &[0x9b],
+        InterruptType::AirLasso => &[0x7f],
+        InterruptType::AirDodge => &[0x21],
+        InterruptType::AirAttack => &[0x33],
+        InterruptType::AirTreadjump => &[0x6d],
+        InterruptType::AirWalljump => &[0x67],
+        InterruptType::AirJump => &[0x0c],
+        InterruptType::PassThroughPlat => &[0x72],
+        InterruptType::Main | InterruptType::Unknown(_) => &[],
+    }
+}
@@ -0,0 +1,259 @@
+//! Read only JSON API served by `-s` and `--dev` from the loaded mods, so that tools don't need to scrape the html.
+//!
+//! * `/api/v1/mods` every loaded mod
+//! * `/api/v1/{mod}/fighters` the fighters of the mod
+//! * `/api/v1/{mod}/{fighter}/attributes` the fighter's attributes
+//! * `/api/v1/{mod}/{fighter}/subactions` the frame data of every subaction of the fighter
+//! * `/api/v1/{mod}/{fighter}/subactions/{subaction}` the frame data and scripts of a single subaction, and the names of the variables the scripts use
+//!
+//! The subaction routes take the hitbox filters in `HitFilter` as query parameters e.g. `?min_damage=12&angle=361`
+//! Mod, fighter and subaction names are case insensitive.
+
+use crate::brawl_data::{BrawlFighter, BrawlMod, BrawlMods};
+use crate::frame_data::{HitBox, SubactionFrameData};
+use crate::variable_key::VariableKey;
+use crate::variable_names::{self, NamedVariable};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use brawllib_rs::high_level_fighter::HighLevelScripts;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+pub fn router(brawl_mods: Arc<BrawlMods>) -> Router {
+    Router::new()
+        .route("/api/v1/mods", get(mods))
+        .route("/api/v1/{mod_name}/fighters", get(fighters))
+        .route(
+            "/api/v1/{mod_name}/{fighter_name}/attributes",
+            get(attributes),
+        )
+        .route(
+            "/api/v1/{mod_name}/{fighter_name}/subactions",
+            get(subactions),
+        )
+        .route(
+            "/api/v1/{mod_name}/{fighter_name}/subactions/{subaction_name}",
+            get(subaction),
+        )
+        .with_state(brawl_mods)
+}
+
+/// Hitboxes are only included when they match every specified filter.
+/// Subactions without any matching hitboxes are left out of subaction lists.
+#[derive(Deserialize, Default)]
+pub struct HitFilter {
+    pub min_damage: Option<f32>,
+    pub max_damage: Option<f32>,
+    pub angle: Option<i32>,
+    /// Only include hitboxes active on this frame, frames are numbered starting from 1.
+    pub frame: Option<usize>,
+}
+
+impl HitFilter {
+    fn is_empty(&self) -> bool {
+        self.min_damage.is_none()
+            && self.max_damage.is_none()
+            && self.angle.is_none()
+            && self.frame.is_none()
+    }
+
+    fn matches(&self, hitbox: &HitBox) -> bool {
+        match hitbox {
+            HitBox::Hit(hit) => {
+                self.min_damage.is_none_or(|x| hit.damage >= x)
+                    && self.max_damage.is_none_or(|x| hit.damage <= x)
+                    && self.angle.is_none_or(|x| hit.angle == x)
+            }
+            // Grabs have no damage or angle to filter by.
+            HitBox::Grab(_) => {
+                self.min_damage.is_none() && self.max_damage.is_none() && self.angle.is_none()
+            }
+        }
+    }
+
+    /// Removes every hitbox that doesn't match, returns false if there are no hitboxes left.
+    fn apply(&self, frame_data: &mut SubactionFrameData) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        frame_data.hitbox_groups.retain_mut(|group| {
+            if self
+                .frame
+                .is_some_and(|x| x < group.frames.start || x > group.frames.end)
+            {
+                return false;
+            }
+            group.hitboxes.retain(|x| self.matches(x));
+            !group.hitboxes.is_empty()
+        });
+        !frame_data.hitbox_groups.is_empty()
+    }
+}
+
+#[derive(Serialize)]
+struct ModSummary<'a> {
+    name: &'a str,
+    display_name: &'a str,
+    version: &'a Option<String>,
+    description: &'a Option<String>,
+}
+
+#[derive(Serialize)]
+struct FighterSummary<'a> {
+    name: &'a str,
+    attributes: String,
+    subactions: String,
+}
+
+#[derive(Serialize)]
+struct SubactionResponse<'a> {
+    frame_data: SubactionFrameData,
+    scripts: &'a HighLevelScripts,
+    variables: Vec<NamedVariable>,
+}
+
+fn not_found(message: String) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({ "error": message })),
+    )
+        .into_response()
+}
+
+fn find_mod<'a>(brawl_mods: &'a BrawlMods, mod_name: &str) -> Result<&'a BrawlMod, Response> {
+    brawl_mods
+        .mods
+        .iter()
+        .find(|x| x.name.eq_ignore_ascii_case(mod_name))
+        .ok_or_else(|| not_found(format!("No mod named {mod_name:?}")))
+}
+
+fn find_fighter<'a>(
+    brawl_mods: &'a BrawlMods,
+    mod_name: &str,
+    fighter_name: &str,
+) -> Result<(&'a BrawlMod, &'a BrawlFighter), Response> {
+    let brawl_mod = find_mod(brawl_mods, mod_name)?;
+    let fighter = brawl_mod
+        .fighters
+        .iter()
+        .find(|x| x.fighter.name.eq_ignore_ascii_case(fighter_name))
+        .ok_or_else(|| not_found(format!("No fighter named {fighter_name:?} in {mod_name:?}")))?;
+    Ok((brawl_mod, fighter))
+}
+
+async fn mods(State(brawl_mods): State<Arc<BrawlMods>>) -> Response {
+    let mods: Vec<_> = brawl_mods
+        .mods
+        .iter()
+        .map(|x| ModSummary {
+            name: &x.name,
+            display_name: &x.display_name,
+            version: &x.version,
+            description: &x.description,
+        })
+        .collect();
+    Json(mods).into_response()
+}
+
+async fn fighters(
+    State(brawl_mods): State<Arc<BrawlMods>>,
+    Path(mod_name): Path<String>,
+) -> Response {
+    let brawl_mod = match find_mod(&brawl_mods, &mod_name) {
+        Ok(x) => x,
+        Err(response) => return response,
+    };
+    let fighters: Vec<_> = brawl_mod
+        .fighters
+        .iter()
+        .map(|x| FighterSummary {
+            name: &x.fighter.name,
+            attributes: format!("/api/v1/{}/{}/attributes", brawl_mod.name, x.fighter.name),
+            subactions: format!("/api/v1/{}/{}/subactions", brawl_mod.name, x.fighter.name),
+        })
+        .collect();
+    Json(fighters).into_response()
+}
+
+async fn attributes(
+    State(brawl_mods): State<Arc<BrawlMods>>,
+    Path((mod_name, fighter_name)): Path<(String, String)>,
+) -> Response {
+    match find_fighter(&brawl_mods, &mod_name, &fighter_name) {
+        Ok((_, fighter)) => Json(&fighter.fighter.attributes).into_response(),
+        Err(response) => response,
+    }
+}
+
+async fn subactions(
+    State(brawl_mods): State<Arc<BrawlMods>>,
+    Path((mod_name, fighter_name)): Path<(String, String)>,
+    Query(filter): Query<HitFilter>,
+) -> Response {
+    let (brawl_mod, fighter) = match find_fighter(&brawl_mods, &mod_name, &fighter_name) {
+        Ok(x) => x,
+        Err(response) => return response,
+    };
+    let subactions: Vec<_> = fighter
+        .fighter
+        .subactions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, subaction)| {
+            let mut frame_data = SubactionFrameData::new(brawl_mod, fighter, index, subaction);
+            filter.apply(&mut frame_data).then_some(frame_data)
+        })
+        .collect();
+    Json(subactions).into_response()
+}
+
+async fn subaction(
+    State(brawl_mods): State<Arc<BrawlMods>>,
+    Path((mod_name, fighter_name, subaction_name)): Path<(String, String, String)>,
+    Query(filter): Query<HitFilter>,
+) -> Response {
+    let (brawl_mod, fighter) = match find_fighter(&brawl_mods, &mod_name, &fighter_name) {
+        Ok(x) => x,
+        Err(response) => return response,
+    };
+    let Some((index, subaction)) = fighter
+        .fighter
+        .subactions
+        .iter()
+        .enumerate()
+        .find(|(_, x)| x.name.eq_ignore_ascii_case(&subaction_name))
+    else {
+        return not_found(format!(
+            "No subaction named {subaction_name:?} for {fighter_name:?}"
+        ));
+    };
+
+    let mut frame_data = SubactionFrameData::new(brawl_mod, fighter, index, subaction);
+    // A single subaction is still returned when nothing matches, just without any hitboxes.
+    filter.apply(&mut frame_data);
+    let scripts = &subaction.scripts;
+    let mut variables = BTreeSet::new();
+    for script in [
+        &scripts.script_main,
+        &scripts.script_gfx,
+        &scripts.script_sfx,
+        &scripts.script_other,
+    ] {
+        variable_names::walk_variables(&script.block.events, &mut |variable, _| {
+            variables.insert(VariableKey::new(variable));
+        });
+    }
+    Json(SubactionResponse {
+        frame_data,
+        scripts,
+        variables: variables
+            .into_iter()
+            .map(|x| fighter.variable_names.get_key(x))
+            .collect(),
+    })
+    .into_response()
+}
@@ -1,4 +1,5 @@
-use crate::{config::Config, output::OutDir};
+use crate::{config::Config, output::OutDir, site::Site};
+use std::borrow::Cow;
 use std::fmt::Write;
 use std::fs;
 use std::path::Path;
@@ -25,7 +26,7 @@ fn run_command_in_dir(command: &str, args: &[&str], dir: &str) {
         )
     }
 }
-fn hash(value: &[u8]) -> String {
+pub fn hash(value: &[u8]) -> String {
     let mut hasher = Sha256::default();
     hasher.update(value);
     hasher.finalize().iter().fold(String::new(), |mut out, x| {
@@ -34,8 +35,29 @@ fn hash(value: &[u8]) -> String {
     })
 }
 
+/// Reads the asset from `src/assets` at runtime when `live` is true, so that the dev server picks up edits.
+/// Otherwise the asset is embedded in the binary.
+macro_rules! asset {
+    ($live:expr, $name:literal) => {
+        if $live {
+            Cow::Owned(fs::read(concat!("src/assets/", $name)).unwrap())
+        } else {
+            Cow::Borrowed(&include_bytes!($name)[..])
+        }
+    };
+}
+
 impl AssetPaths {
-    pub fn new(config: &Config) -> AssetPaths {
+    pub fn new(config: &Config, site: &Site) -> AssetPaths {
+        AssetPaths::build(config, site, false)
+    }
+
+    /// Used by the dev server, reads the assets from `src/assets` instead of the versions embedded in the binary.
+    pub fn new_live(config: &Config, site: &Site) -> AssetPaths {
+        AssetPaths::build(config, site, true)
+    }
+
+    fn build(config: &Config, site: &Site, live: bool) -> AssetPaths {
         let dir = OutDir::new_path(
             Path::new(&config.web_root)
                 .join("assets_static")
@@ -45,32 +67,53 @@ impl AssetPaths {
 
         let root_index = config.web_root.clone();
 
+        let search_index = Path::new(&config.web_root)
+            .join("search/index.json")
+            .into_os_string()
+            .into_string()
+            .unwrap();
+
         let style_css = {
-            let contents = include_str!("style.css");
+            let contents = asset!(live, "style.css");
+            let contents = std::str::from_utf8(&contents).unwrap();
 
             let minified = minifier::css::minify(contents).unwrap().to_string();
 
             let hash = hash(minified.as_bytes());
-            dir.create_compressed_file(&format!("{hash}.css"), minified.as_bytes())
+            site.create_compressed_file(&dir, &format!("{hash}.css"), minified.as_bytes())
         };
 
         let spritesheet_png = {
-            let contents = include_bytes!("spritesheet.png");
-            let hash = hash(contents);
-            dir.create_compressed_file(&format!("{hash}.png"), contents)
+            let contents = asset!(live, "spritesheet.png");
+            let hash = hash(&contents);
+            site.create_compressed_file(&dir, &format!("{hash}.png"), &contents)
         };
 
         let favicon_png = {
-            let contents = include_bytes!("favicon.png");
-            let hash = hash(contents);
-            dir.create_compressed_file(&format!("{hash}.png"), contents)
+            let contents = asset!(live, "favicon.png");
+            let hash = hash(&contents);
+            site.create_compressed_file(&dir, &format!("{hash}.png"), &contents)
+        };
+
+        let search_js = {
+            let contents = asset!(live, "search.js");
+
+            let hash = hash(&contents);
+            site.create_compressed_file(&dir, &format!("{hash}.js"), &contents)
+        };
+
+        let table_sort_js = {
+            let contents = asset!(live, "table_sort.js");
+
+            let hash = hash(&contents);
+            site.create_compressed_file(&dir, &format!("{hash}.js"), &contents)
         };
 
         let legacy_subaction_render_js = if config.legacy_renderer {
-            let contents = include_str!("subaction_render.js");
+            let contents = asset!(live, "subaction_render.js");
 
-            let hash = hash(contents.as_bytes());
-            dir.create_compressed_file(&format!("{hash}.js"), contents.as_bytes())
+            let hash = hash(&contents);
+            site.create_compressed_file(&dir, &format!("{hash}.js"), &contents)
         } else {
             String::new()
         };
@@ -119,7 +162,7 @@ impl AssetPaths {
                 ))
                 .unwrap();
                 let hash = hash(&contents);
-                dir.create_compressed_file(&format!("{hash}.wasm"), &contents)
+                site.create_compressed_file(&dir, &format!("{hash}.wasm"), &contents)
             }
         };
 
@@ -138,7 +181,7 @@ impl AssetPaths {
             contents = contents.replace(WASM_FILE_NAME, wasm_file_name);
 
             let hash = hash(contents.as_bytes());
-            dir.create_compressed_file(&format!("{hash}.js"), contents.as_bytes())
+            site.create_compressed_file(&dir, &format!("{hash}.js"), contents.as_bytes())
         };
 
         AssetPaths {
@@ -146,6 +189,9 @@ impl AssetPaths {
             favicon_png,
             spritesheet_png,
             style_css,
+            search_js,
+            search_index,
+            table_sort_js,
             legacy_subaction_render_js,
             fighter_renderer_wasm,
             fighter_renderer_js,
@@ -159,6 +205,9 @@ pub struct AssetPaths {
     pub favicon_png: String,
     pub spritesheet_png: String,
     pub style_css: String,
+    pub search_js: String,
+    pub search_index: String,
+    pub table_sort_js: String,
     pub legacy_subaction_render_js: String,
     pub fighter_renderer_wasm: String,
     pub fighter_renderer_js: String,
@@ -0,0 +1,244 @@
+"use strict";
+
+// Client side search over the index generated by `search.rs`.
+//
+// A query like "marth fair pm3.6" is split into tokens.
+// Tokens naming a mod select which shards are searched, when no mod is named the mod of the current page is searched,
+// falling back to every mod when the current page doesn't belong to one.
+// Every remaining token must match the start of a word in the fighter name, page name or their aliases.
+
+(function () {
+  const script = document.currentScript;
+  const input = document.getElementById("search-input");
+  const results = document.getElementById("search-results");
+  const MAX_RESULTS = 15;
+
+  let mods = null;
+  const shards = {};
+  let selected = 0;
+  let current_results = [];
+  let query_id = 0;
+  let shown_query_id = 0;
+  // Enter was pressed before the results of the latest query were ready.
+  let navigate_when_shown = false;
+
+  function normalize(token) {
+    return token.toLowerCase().replace(/[^a-z0-9]/g, "");
+  }
+
+  function tokenize(text) {
+    return text.split(/\s+/).map(normalize).filter(x => x.length > 0);
+  }
+
+  // "AttackAirF" => ["attack", "air", "f"]
+  function split_name(name) {
+    return name.replace(/([a-z])([A-Z0-9])/g, "$1 $2").replace(/([0-9])([A-Za-z])/g, "$1 $2");
+  }
+
+  function load_json(url) {
+    return fetch(url).then(response => response.json());
+  }
+
+  function load_mods() {
+    if (mods === null) {
+      mods = load_json(script.dataset.index);
+    }
+    return mods;
+  }
+
+  function load_shard(brawl_mod) {
+    if (!(brawl_mod.name in shards)) {
+      shards[brawl_mod.name] = load_json(brawl_mod.shard).then(shard => {
+        for (const fighter of shard.fighters) {
+          fighter.words = tokenize(fighter.name + " " + fighter.aliases);
+        }
+        shard.words = shard.entries.map(([, , name, , aliases]) => tokenize(split_name(name) + " " + name + " " + aliases));
+        return shard;
+      });
+    }
+    return shards[brawl_mod.name];
+  }
+
+  // Returns 0 if the token doesn't match any word, otherwise a higher score for a more exact match.
+  function match_token(token, words) {
+    let best = 0;
+    for (const word of words) {
+      if (word === token) {
+        return 3;
+      }
+      if (word.startsWith(token)) {
+        best = 1;
+      }
+    }
+    return best;
+  }
+
+  function search_shard(brawl_mod, shard, tokens, current_mod) {
+    const found = [];
+    for (let i = 0; i < shard.entries.length; i++) {
+      const [fighter_index, kind, name, link] = shard.entries[i];
+      const fighter = shard.fighters[fighter_index];
+      const words = shard.words[i];
+
+      let score = 0;
+      let fighter_matched = false;
+      let all_matched = true;
+      for (const token of tokens) {
+        const fighter_score = match_token(token, fighter.words);
+        const page_score = match_token(token, words);
+        if (fighter_score === 0 && page_score === 0) {
+          all_matched = false;
+          break;
+        }
+        fighter_matched = fighter_matched || fighter_score > 0;
+        score += Math.max(fighter_score, page_score);
+      }
+      if (!all_matched) {
+        continue;
+      }
+
+      // Only suggest the fighter page itself when nothing else was searched for.
+      if (kind === "Fighter" && tokens.some(token => match_token(token, fighter.words) === 0)) {
+        continue;
+      }
+      if (kind !== "Fighter" && !tokens.some(token => match_token(token, words) > 0)) {
+        continue;
+      }
+
+      if (fighter_matched) {
+        score += 1;
+      }
+      if (brawl_mod.name === current_mod) {
+        score += 1;
+      }
+
+      found.push({
+        score,
+        title: kind === "Fighter" ? fighter.name : fighter.name + " - " + name,
+        detail: brawl_mod.name + " " + kind,
+        link: kind === "Fighter" ? fighter.link : fighter.link + "/" + link,
+        length: name.length,
+      });
+    }
+    return found;
+  }
+
+  function search(query) {
+    const id = ++query_id;
+    load_mods().then(mods => {
+      const current_mod = decodeURIComponent(window.location.pathname.split("/")[1] || "");
+      const named_mods = [];
+      const tokens = [];
+      for (const token of tokenize(query)) {
+        const brawl_mod = mods.find(x => normalize(x.name) === token);
+        if (brawl_mod) {
+          named_mods.push(brawl_mod);
+        } else {
+          tokens.push(token);
+        }
+      }
+
+      let searched_mods = named_mods;
+      if (searched_mods.length === 0) {
+        searched_mods = mods.filter(x => x.name === current_mod);
+      }
+      if (searched_mods.length === 0) {
+        searched_mods = mods;
+      }
+
+      if (tokens.length === 0) {
+        show(named_mods.map(x => ({ title: x.name, detail: "Mod", link: x.link })), id);
+        return;
+      }
+
+      Promise.all(searched_mods.map(load_shard)).then(loaded => {
+        let found = [];
+        for (let i = 0; i < loaded.length; i++) {
+          found = found.concat(search_shard(searched_mods[i], loaded[i], tokens, current_mod));
+        }
+        found.sort((a, b) => b.score - a.score || a.length - b.length);
+        show(found.slice(0, MAX_RESULTS), id);
+      });
+    });
+  }
+
+  function show(found, id) {
+    // A slower search for an older query finished after a newer one
+    if (id !== query_id) {
+      return;
+    }
+
+    shown_query_id = id;
+    if (navigate_when_shown && found.length > 0) {
+      window.location.href = found[0].link;
+      return;
+    }
+    navigate_when_shown = false;
+
+    current_results = found;
+    selected = 0;
+    results.innerHTML = "";
+    for (let i = 0; i < found.length; i++) {
+      const item = document.createElement("a");
+      item.className = "dropdown-item";
+      item.href = found[i].link;
+      item.textContent = found[i].title;
+      const detail = document.createElement("small");
+      detail.className = "search-result-detail";
+      detail.textContent = found[i].detail;
+      item.appendChild(detail);
+      results.appendChild(item);
+    }
+    update_selected();
+    results.classList.toggle("show", found.length > 0);
+  }
+
+  function update_selected() {
+    for (let i = 0; i < results.children.length; i++) {
+      results.children[i].classList.toggle("active", i === selected);
+    }
+  }
+
+  input.addEventListener("input", () => {
+    if (input.value.trim() === "") {
+      show([], ++query_id);
+    } else {
+      search(input.value);
+    }
+  });
+
+  input.addEventListener("keydown", event => {
+    if (event.key === "ArrowDown" && current_results.length > 0) {
+      selected = (selected + 1) % current_results.length;
+      update_selected();
+      event.preventDefault();
+    } else if (event.key === "ArrowUp" && current_results.length > 0) {
+      selected = (selected + current_results.length - 1) % current_results.length;
+      update_selected();
+      event.preventDefault();
+    } else if (event.key === "Enter") {
+      if (shown_query_id !== query_id) {
+        navigate_when_shown = true;
+      } else if (current_results.length > 0) {
+        window.location.href = current_results[selected].link;
+      }
+      event.preventDefault();
+    } else if (event.key === "Escape") {
+      results.classList.remove("show");
+    }
+  });
+
+  input.addEventListener("focus", () => {
+    // Start downloading the index before the user finishes typing
+    load_mods();
+    if (current_results.length > 0) {
+      results.classList.add("show");
+    }
+  });
+
+  document.addEventListener("click", event => {
+    if (!results.contains(event.target) && event.target !== input) {
+      results.classList.remove("show");
+    }
+  });
+})();
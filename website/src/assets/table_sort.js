@@ -0,0 +1,42 @@
+"use strict";
+
+// Sorts the rows of every `.sortable-table` when a `.sortable` header is clicked.
+// Cells are compared by their `data-value` attribute, numerically when both values are numbers.
+// Clicking the same header again reverses the order.
+
+for (const table of document.querySelectorAll(".sortable-table")) {
+  const headers = table.querySelectorAll("th.sortable");
+  const body = table.querySelector("tbody");
+  let sorted_column = null;
+  let descending = false;
+
+  headers.forEach((header, column) => {
+    header.addEventListener("click", () => {
+      descending = sorted_column === column ? !descending : column !== 0;
+      sorted_column = column;
+
+      const rows = Array.from(body.rows);
+      rows.sort((a, b) => {
+        const a_value = a.cells[column].dataset.value;
+        const b_value = b.cells[column].dataset.value;
+        const a_number = parseFloat(a_value);
+        const b_number = parseFloat(b_value);
+        let order;
+        if (!isNaN(a_number) && !isNaN(b_number)) {
+          order = a_number - b_number;
+        } else {
+          order = a_value.localeCompare(b_value);
+        }
+        return descending ? -order : order;
+      });
+      for (const row of rows) {
+        body.appendChild(row);
+      }
+
+      for (const other of headers) {
+        other.classList.remove("sorted-ascending", "sorted-descending");
+      }
+      header.classList.add(descending ? "sorted-descending" : "sorted-ascending");
+    });
+  });
+}
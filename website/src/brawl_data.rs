@@ -1,28 +1,73 @@
+use crate::assets;
 use crate::cli::Args;
-use crate::config::Config;
+use crate::config::{Config, ModConfig};
+use crate::event_definitions::{EventDefinitions, EventKey};
+use crate::mechanics::Mechanics;
 use crate::page::NavLink;
+use crate::script_callers::{ScriptCallers, ScriptKey};
+use crate::subaction_category::{self, SubactionCategory, SubactionCategoryOverrides};
+use crate::variable_names::VariableNames;
 use brawllib_rs::brawl_mod::BrawlMod as BrawllibMod;
 use brawllib_rs::fighter::ModType;
 use brawllib_rs::high_level_fighter::HighLevelFighter;
-use std::collections::HashMap;
+use brawllib_rs::script_ast::ScriptAst;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::fs::DirEntry;
 
+/// The canonical form of a name in urls and output paths e.g. `Captain Falcon` -> `captain-falcon`, `Game & Watch` -> `game-and-watch` and `R.O.B` -> `rob`
+pub fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.replace('&', " and ").chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if c == '.' || c == '\'' {
+            // Abbreviations and possessives read better without a separator.
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_owned()
+}
+
+/// The path of the fighter's pages relative to the output root e.g. `P+/captain-falcon`
+pub fn fighter_path(mod_slug: &str, fighter_name: &str) -> String {
+    format!("{mod_slug}/{}", slug(fighter_name))
+}
+
 pub struct BrawlMods {
     pub mods: Vec<BrawlMod>,
     pub mod_links: Vec<NavLink>,
 }
 
 pub struct BrawlMod {
+    /// The mod's slug, used in urls and output paths
     pub name: String,
+    /// Shown in the nav and on the index page
+    pub display_name: String,
+    pub description: Option<String>,
+    pub version: Option<String>,
     pub fighters: Vec<BrawlFighter>,
-    pub is_mod: bool,
+    /// Used to derive frame data such as hitlag and shieldstun
+    pub mechanics: Mechanics,
+    /// Including the definitions overridden by the mod
+    pub event_definitions: EventDefinitions,
+    /// Hash of every fighter in the mod, an input of pages listing data from every fighter
+    pub hash: String,
 }
 
 pub struct BrawlFighter {
     pub fighter: HighLevelFighter,
     pub script_lookup: HashMap<i32, ScriptInfo>,
     pub script_lookup_common: HashMap<i32, ScriptInfo>,
+    /// The navigation category of each subaction, in the same order as `fighter.subactions`
+    pub subaction_categories: Vec<SubactionCategory>,
+    /// The scripts calling each script, used for the "Called by" lists
+    pub script_callers: ScriptCallers,
+    /// Including the names overridden by the mod and the fighter
+    pub variable_names: VariableNames,
+    /// Hash of the fighter's data, an input of the fighter's pages and gifs
+    pub hash: String,
 }
 
 pub struct ScriptInfo {
@@ -35,24 +80,44 @@ impl BrawlMods {
         match fs::read_dir("../data") {
             Ok(dir) => {
                 let mut mod_links = vec![];
-                for name in &config.mods {
+                for mod_config in &config.mods {
                     mod_links.push(NavLink {
-                        name: name.to_string(),
-                        link: format!("/{}", name),
+                        name: mod_config.name().to_string(),
+                        link: format!("/{}", mod_config.slug()),
                         current: false,
                     });
                 }
 
+                let overrides = SubactionCategoryOverrides::load().unwrap();
+                let variable_names = VariableNames::load().unwrap();
+                let event_definitions = EventDefinitions::load().unwrap();
                 let mods: Vec<_> = dir
                     .filter(|x| x.as_ref().unwrap().path().is_dir())
-                    .filter_map(|x| BrawlMod::new(x.unwrap(), args))
+                    .filter_map(|x| {
+                        let data = x.unwrap();
+                        let directory = data.file_name().into_string().unwrap();
+                        let mod_config = config
+                            .mods
+                            .iter()
+                            .find(|x| x.directory.eq_ignore_ascii_case(&directory))
+                            .cloned()
+                            .unwrap_or_else(|| ModConfig::new(directory));
+                        BrawlMod::new(
+                            data,
+                            &mod_config,
+                            args,
+                            &overrides,
+                            &variable_names,
+                            &event_definitions,
+                        )
+                    })
                     .collect();
 
                 // If nav links are not manually specified, automatically generate them.
                 if mod_links.is_empty() {
                     for brawl_mod in &mods {
                         mod_links.push(NavLink {
-                            name: brawl_mod.name.clone(),
+                            name: brawl_mod.display_name.clone(),
                             link: format!("/{}", brawl_mod.name),
                             current: false,
                         });
@@ -68,13 +133,15 @@ impl BrawlMods {
         }
     }
 
+    /// `current_mod` is the slug of the mod being viewed
     pub fn gen_mod_links(&self, current_mod: String) -> Vec<NavLink> {
+        let current_link = format!("/{}", current_mod);
         let mut links = vec![];
         for link in &self.mod_links {
             links.push(NavLink {
                 name: link.name.clone(),
                 link: link.link.clone(),
-                current: link.name == current_mod,
+                current: link.link == current_link,
             });
         }
         links
@@ -82,17 +149,32 @@ impl BrawlMods {
 }
 
 impl BrawlMod {
-    pub fn new(data: DirEntry, args: &Args) -> Option<BrawlMod> {
+    pub fn new(
+        data: DirEntry,
+        config: &ModConfig,
+        args: &Args,
+        overrides: &SubactionCategoryOverrides,
+        variable_names: &VariableNames,
+        event_definitions: &EventDefinitions,
+    ) -> Option<BrawlMod> {
         let mod_name = data.file_name().into_string().unwrap();
         let lower_mod_name = mod_name.to_lowercase();
         if args.mod_names.is_empty() || args.mod_names.iter().any(|x| x == &lower_mod_name) {
-            let is_mod = lower_mod_name != "brawl";
+            let slug = config.slug();
+            let is_mod = config.base().is_some();
             let mod_path = if is_mod { Some(data.path()) } else { None };
-            let brawl_path = if is_mod {
-                data.path().parent().unwrap().join("Brawl")
-            } else {
-                data.path()
+            let brawl_path = match config.base() {
+                Some(base) => data.path().parent().unwrap().join(base),
+                None => data.path(),
             };
+            // brawllib only layers a single mod over a brawl dump, so the base can't be another mod.
+            if is_mod && !brawl_path.join("fighter").is_dir() {
+                println!(
+                    "Failed to load brawl mod '{}': its base {:?} is not a brawl dump, it has no fighter directory. To layer over another mod, the base must be a brawl dump with the other mod's files already applied.",
+                    mod_name, brawl_path
+                );
+                return None;
+            }
             let brawllib_mod = BrawllibMod::new(&brawl_path, mod_path.as_deref());
 
             let fighters = match brawllib_mod.load_fighters(true) {
@@ -103,6 +185,13 @@ impl BrawlMod {
                 }
             };
 
+            let mod_variable_names = variable_names
+                .with_overrides(&data.path().join("variable_names.json"))
+                .unwrap();
+            let event_definitions = event_definitions
+                .with_overrides(&data.path().join("event_definitions.json"))
+                .unwrap();
+
             let mut brawl_fighters = vec![];
             for fighter in fighters {
                 let lower_fighter_name = fighter.cased_name.to_lowercase();
@@ -116,10 +205,14 @@ impl BrawlMod {
 
                 if (args.fighter_names.is_empty()
                     || args.fighter_names.iter().any(|x| x == &lower_fighter_name))
-                    && lower_fighter_name != "poketrainer"
+                    && !config
+                        .hidden_fighters
+                        .iter()
+                        .any(|x| x.eq_ignore_ascii_case(&lower_fighter_name))
                     && !unmodified_fighter_in_mod
                 {
                     let fighter = HighLevelFighter::new(&fighter);
+                    let fighter_path = fighter_path(slug, &fighter.name);
 
                     let mut script_lookup = HashMap::new();
                     let mut script_lookup_common = HashMap::new();
@@ -132,8 +225,8 @@ impl BrawlMod {
                                     action.name, action.script_entry.offset
                                 );
                                 let address = format!(
-                                    "/{}/{}/actions/{}.html#script-entry",
-                                    mod_name, fighter.name, action.name
+                                    "/{}/actions/{}.html#script-entry",
+                                    fighter_path, action.name
                                 );
                                 // These sorts of scripts may be from the same offset, as multiple actions refer to the same script.
                                 // It shouldnt matter too much as the scripts are going to be identical anyway.
@@ -146,8 +239,8 @@ impl BrawlMod {
                             let name =
                                 format!("{} Entry 0x{:x}", action.name, action.script_entry.offset);
                             let address = format!(
-                                "/{}/{}/actions/{}.html#script-entry",
-                                mod_name, fighter.name, action.name
+                                "/{}/actions/{}.html#script-entry",
+                                fighter_path, action.name
                             );
                             script_lookup
                                 .insert(action.script_entry.offset, ScriptInfo { name, address });
@@ -160,8 +253,8 @@ impl BrawlMod {
                                     action.name, action.script_exit.offset
                                 );
                                 let address = format!(
-                                    "/{}/{}/actions/{}.html#script-exit",
-                                    mod_name, fighter.name, action.name
+                                    "/{}/actions/{}.html#script-exit",
+                                    fighter_path, action.name
                                 );
                                 script_lookup_common.insert(
                                     action.script_exit.offset,
@@ -172,8 +265,8 @@ impl BrawlMod {
                             let name =
                                 format!("{} Exit 0x{:x}", action.name, action.script_exit.offset);
                             let address = format!(
-                                "/{}/{}/actions/{}.html#script-exit",
-                                mod_name, fighter.name, action.name
+                                "/{}/actions/{}.html#script-exit",
+                                fighter_path, action.name
                             );
                             script_lookup
                                 .insert(action.script_exit.offset, ScriptInfo { name, address });
@@ -186,8 +279,8 @@ impl BrawlMod {
                         let name =
                             format!("{} Main 0x{:x}", subaction.name, scripts.script_main.offset);
                         let address = format!(
-                            "/{}/{}/subactions/{}.html#script-main",
-                            mod_name, fighter.name, subaction.name
+                            "/{}/subactions/{}.html#script-main",
+                            fighter_path, subaction.name
                         );
                         script_lookup
                             .insert(scripts.script_main.offset, ScriptInfo { name, address });
@@ -195,8 +288,8 @@ impl BrawlMod {
                         let name =
                             format!("{} GFX 0x{:x}", subaction.name, scripts.script_gfx.offset);
                         let address = format!(
-                            "/{}/{}/subactions/{}.html#script-gfx",
-                            mod_name, fighter.name, subaction.name
+                            "/{}/subactions/{}.html#script-gfx",
+                            fighter_path, subaction.name
                         );
                         script_lookup
                             .insert(scripts.script_gfx.offset, ScriptInfo { name, address });
@@ -204,8 +297,8 @@ impl BrawlMod {
                         let name =
                             format!("{} SFX 0x{:x}", subaction.name, scripts.script_sfx.offset);
                         let address = format!(
-                            "/{}/{}/subactions/{}.html#script-sfx",
-                            mod_name, fighter.name, subaction.name
+                            "/{}/subactions/{}.html#script-sfx",
+                            fighter_path, subaction.name
                         );
                         script_lookup
                             .insert(scripts.script_sfx.offset, ScriptInfo { name, address });
@@ -215,8 +308,8 @@ impl BrawlMod {
                             subaction.name, scripts.script_other.offset
                         );
                         let address = format!(
-                            "/{}/{}/subactions/{}.html#script-other",
-                            mod_name, fighter.name, subaction.name
+                            "/{}/subactions/{}.html#script-other",
+                            fighter_path, subaction.name
                         );
                         script_lookup
                             .insert(scripts.script_other.offset, ScriptInfo { name, address });
@@ -224,8 +317,7 @@ impl BrawlMod {
 
                     for script in &fighter.scripts_fragment_fighter {
                         let name = format!("0x{:x}", script.offset);
-                        let address =
-                            format!("/{}/{}/scripts/{}.html", mod_name, fighter.name, name);
+                        let address = format!("/{}/scripts/{}.html", fighter_path, name);
                         // fragment scripts should not have duplicate offsets, they are
                         // guaranteed unique by the way they are generated.
                         assert!(
@@ -237,10 +329,7 @@ impl BrawlMod {
 
                     for script in &fighter.scripts_fragment_common {
                         let name = format!("0x{:x}", script.offset);
-                        let address = format!(
-                            "/{}/{}/scripts_common/{}.html",
-                            mod_name, fighter.name, name
-                        );
+                        let address = format!("/{}/scripts_common/{}.html", fighter_path, name);
                         assert!(
                             script_lookup_common
                                 .insert(script.offset, ScriptInfo { name, address })
@@ -248,32 +337,89 @@ impl BrawlMod {
                         );
                     }
 
+                    let subaction_categories =
+                        subaction_category::categorize(overrides, &mod_name, &fighter);
+                    let script_callers =
+                        ScriptCallers::new(&fighter, &script_lookup, &script_lookup_common);
+                    let variable_names = mod_variable_names
+                        .with_overrides(
+                            &data
+                                .path()
+                                .join("variable_names")
+                                .join(format!("{}.json", self::slug(&fighter.name))),
+                        )
+                        .unwrap();
+
+                    let hash = assets::hash(
+                        &bincode::serde::encode_to_vec(&fighter, bincode::config::standard())
+                            .unwrap(),
+                    );
+
                     brawl_fighters.push(BrawlFighter {
+                        hash,
                         fighter,
                         script_lookup,
                         script_lookup_common,
+                        subaction_categories,
+                        script_callers,
+                        variable_names,
                     });
                 }
             }
 
             brawl_fighters.sort_by_key(|x| x.fighter.name.clone());
 
+            let mut undefined_events: BTreeMap<EventKey, Vec<&str>> = BTreeMap::new();
+            for fighter in &brawl_fighters {
+                for key in event_definitions.undefined(&fighter.fighter) {
+                    undefined_events
+                        .entry(key)
+                        .or_default()
+                        .push(&fighter.fighter.name);
+                }
+            }
+            for (key, fighters) in &undefined_events {
+                println!(
+                    "Brawl mod '{}' uses event {} without a definition in: {}",
+                    mod_name,
+                    key,
+                    fighters.join(", ")
+                );
+            }
+
+            let hash = assets::hash(
+                brawl_fighters
+                    .iter()
+                    .map(|x| x.hash.as_str())
+                    .collect::<String>()
+                    .as_bytes(),
+            );
+
             Some(BrawlMod {
-                name: mod_name,
+                hash,
+                name: slug.to_owned(),
+                display_name: config.name().to_owned(),
+                description: config.description.clone(),
+                version: config.version.clone(),
+                mechanics: config.mechanics.resolve(&mod_name),
                 fighters: brawl_fighters,
-                is_mod,
+                event_definitions,
             })
         } else {
             None
         }
     }
 
+    pub fn fighter_path(&self, fighter_name: &str) -> String {
+        fighter_path(&self.name, fighter_name)
+    }
+
     pub fn gen_fighter_links(&self, current_fighter: &str) -> Vec<NavLink> {
         let mut links = vec![];
         for fighter in &self.fighters {
             links.push(NavLink {
                 name: fighter.fighter.name.clone(),
-                link: format!("/{}/{}", self.name, fighter.fighter.name),
+                link: format!("/{}", self.fighter_path(&fighter.fighter.name)),
                 current: current_fighter == fighter.fighter.name,
             });
         }
@@ -282,7 +428,7 @@ impl BrawlMod {
 
     pub fn gen_subaction_links(
         &self,
-        fighter: &HighLevelFighter,
+        fighter: &BrawlFighter,
         current_subaction: String,
     ) -> SubactionLinks {
         let mut attacks_jab = vec![];
@@ -311,118 +457,43 @@ impl BrawlMod {
         let mut misc = vec![];
         let mut none = vec![];
 
-        for subaction in &fighter.subactions {
+        for (index, subaction) in fighter.fighter.subactions.iter().enumerate() {
             let link = NavLink {
                 name: subaction.name.clone(),
                 link: format!(
-                    "/{}/{}/subactions/{}.html",
-                    self.name, fighter.name, subaction.name
+                    "/{}/subactions/{}.html",
+                    self.fighter_path(&fighter.fighter.name),
+                    subaction.name
                 ),
                 current: current_subaction == subaction.name,
             };
 
-            // This heuristic is kind of dumb.
-            // alernatively I could try and read which actions/subactions call each other to
-            // determine where to add each subaction
-            //
-            // NOTE: Be careful that sometimes the check uses link.name.contains(..) and other times it uses link.name.starts_with(..)
-            if link.name.contains("Cliff") {
-                ledge_options.push(link);
-            } else if link.name.contains("Item") || link.name.contains("Gekikara") {
-                item.push(link);
-            } else if link.name.contains("Ganon")
-                || link.name.contains("Snake")
-                || link.name.contains("Bitten")
-                || link.name.contains("Stick")
-                || link.name.contains("Rope")
-                || link.name.contains("Ladder")
-                || link.name.contains("Egg")
-                || link.name.contains("Capture")
-                || link.name.contains("Zitabata")
-                || link.name.contains("Swing")
-            {
-                misc.push(link);
-            } else if link.name.contains("FuraFura") {
-                stun.push(link);
-            } else if link.name.contains("FuraSleep") {
-                sleep.push(link);
-            } else if link.name.contains("Final") {
-                finals.push(link);
-            } else if link.name.contains("Swim") {
-                swim.push(link);
-            } else if link.name.contains("Slip") {
-                trips.push(link);
-            } else if link.name.contains("Glide") {
-                glide.push(link);
-            } else if link.name.contains("Shank")
-                || link.name.contains("AttackSquat")
-                || link.name.contains("SquatF")
-                || link.name.contains("SquatB")
-            {
-                crawl.push(link);
-            } else if link.name.contains("Down") {
-                knockdowns.push(link);
-            } else if link.name.contains("AirCatch") {
-                misc.push(link);
-            } else if link.name.contains("Step") {
-                footstool.push(link);
-            } else if link.name.contains("Fall") || link.name.contains("Landing") {
-                movements.push(link);
-            } else if link.name.contains("Special") {
-                specials.push(link);
-            } else if link.name.contains("Catch")
-                || link.name.starts_with("Throw") && !link.name.contains("Thrown")
-            {
-                grabs.push(link);
-            } else if !link.name.starts_with("Throw")
-                && link.name.contains("Throw")
-                && !link.name.contains("Thrown")
-            {
-                item_throw.push(link);
-            } else if link.name.contains("AttackEnd") {
-                misc.push(link);
-            } else if link.name.contains("Attack") {
-                let number: String = link.name.chars().filter(char::is_ascii_digit).collect();
-                if link.name.contains("Air") {
-                    attacks_aerial.push(link);
-                } else if link.name.contains("Attack") && number.starts_with('1') {
-                    attacks_jab.push(link);
-                } else if link.name.contains("Attack") && number.starts_with('3') {
-                    attacks_tilt.push(link);
-                } else if link.name.contains("Attack") && number.starts_with('4') {
-                    attacks_smash.push(link);
-                } else if link.name.contains("Attack") {
-                    attacks_dash.push(link);
-                } else {
-                    error!(
-                        "Missed an attack for {} in the subaction navigation",
-                        fighter.name
-                    );
-                }
-            } else if link.name.contains("Appeal")
-                || link.name.contains("Win")
-                || link.name == "Lose"
-            {
-                taunts.push(link);
-            } else if link.name.contains("Wait")
-                || link.name.contains("Dash")
-                || link.name.contains("Run")
-                || link.name.contains("Turn")
-                || link.name.contains("Walk")
-                || link.name.contains("Jump")
-                || link.name.contains("MissFoot")
-                || link.name.contains("Ottotto")
-                || link.name.contains("Squat")
-            {
-                movements.push(link);
-            } else if link.name.contains("Passive") {
-                tech.push(link);
-            } else if link.name.contains("Escape") || link.name.contains("Guard") {
-                dodges.push(link);
-            } else if link.name.contains("NONE") || link.name.starts_with('_') {
-                none.push(link);
-            } else {
-                misc.push(link);
+            match fighter.subaction_categories[index] {
+                SubactionCategory::AttackJab => attacks_jab.push(link),
+                SubactionCategory::AttackTilt => attacks_tilt.push(link),
+                SubactionCategory::AttackSmash => attacks_smash.push(link),
+                SubactionCategory::AttackDash => attacks_dash.push(link),
+                SubactionCategory::AttackAerial => attacks_aerial.push(link),
+                SubactionCategory::Grab => grabs.push(link),
+                SubactionCategory::Special => specials.push(link),
+                SubactionCategory::Knockdown => knockdowns.push(link),
+                SubactionCategory::Trip => trips.push(link),
+                SubactionCategory::LedgeOption => ledge_options.push(link),
+                SubactionCategory::Dodge => dodges.push(link),
+                SubactionCategory::Tech => tech.push(link),
+                SubactionCategory::Footstool => footstool.push(link),
+                SubactionCategory::Glide => glide.push(link),
+                SubactionCategory::Crawl => crawl.push(link),
+                SubactionCategory::Movement => movements.push(link),
+                SubactionCategory::Final => finals.push(link),
+                SubactionCategory::Taunt => taunts.push(link),
+                SubactionCategory::Stun => stun.push(link),
+                SubactionCategory::Sleep => sleep.push(link),
+                SubactionCategory::Swim => swim.push(link),
+                SubactionCategory::Item => item.push(link),
+                SubactionCategory::ItemThrow => item_throw.push(link),
+                SubactionCategory::Misc => misc.push(link),
+                SubactionCategory::None => none.push(link),
             }
         }
         attacks_jab.sort_by_key(|x| x.name.clone());
@@ -490,18 +561,11 @@ impl BrawlMod {
         fighter: &HighLevelFighter,
         current_script: i32,
     ) -> Vec<NavLink> {
-        let mut links = vec![];
-        for script in &fighter.scripts_fragment_common {
-            links.push(NavLink {
-                name: format!("0x{:x}", script.offset),
-                link: format!(
-                    "/{}/{}/scripts_common/0x{:x}.html",
-                    self.name, fighter.name, script.offset
-                ),
-                current: current_script == script.offset,
-            });
-        }
-        links
+        script_fragment_links(
+            &format!("/{}/scripts_common", self.fighter_path(&fighter.name)),
+            &fighter.scripts_fragment_common,
+            current_script,
+        )
     }
 
     pub fn gen_script_fragment_fighter_links(
@@ -509,18 +573,11 @@ impl BrawlMod {
         fighter: &HighLevelFighter,
         current_script: i32,
     ) -> Vec<NavLink> {
-        let mut links = vec![];
-        for script in &fighter.scripts_fragment_fighter {
-            links.push(NavLink {
-                name: format!("0x{:x}", script.offset),
-                link: format!(
-                    "/{}/{}/scripts/0x{:x}.html",
-                    self.name, fighter.name, script.offset
-                ),
-                current: current_script == script.offset,
-            });
-        }
-        links
+        script_fragment_links(
+            &format!("/{}/scripts", self.fighter_path(&fighter.name)),
+            &fighter.scripts_fragment_fighter,
+            current_script,
+        )
     }
 
     pub fn gen_script_section_links(
@@ -533,8 +590,9 @@ impl BrawlMod {
             links.push(NavLink {
                 name: script.name.clone(),
                 link: format!(
-                    "/{}/{}/scripts_common/{}.html",
-                    self.name, fighter.name, script.name
+                    "/{}/scripts_common/{}.html",
+                    self.fighter_path(&fighter.name),
+                    script.name
                 ),
                 current: current_script == script.name,
             });
@@ -542,6 +600,65 @@ impl BrawlMod {
         links
     }
 
+    /// Links to every script that calls the script, directly and transitively.
+    pub fn gen_script_callers(
+        &self,
+        fighter: &BrawlFighter,
+        script: &ScriptKey,
+    ) -> ScriptCallerLinks {
+        let called_by = fighter
+            .script_callers
+            .callers(script)
+            .iter()
+            .map(|caller| {
+                let (name, link) = self.script_name_link(fighter, &caller.script);
+                CallerLink {
+                    name,
+                    link,
+                    kind: caller.kind,
+                }
+            })
+            .collect();
+        let reachable_from = fighter
+            .script_callers
+            .reachable_from(script)
+            .iter()
+            .map(|script| {
+                let (name, link) = self.script_name_link(fighter, script);
+                NavLink {
+                    name,
+                    link,
+                    current: false,
+                }
+            })
+            .collect();
+        ScriptCallerLinks {
+            called_by,
+            reachable_from,
+        }
+    }
+
+    fn script_name_link(&self, fighter: &BrawlFighter, script: &ScriptKey) -> (String, String) {
+        let info = match script {
+            ScriptKey::Fighter(offset) => fighter.script_lookup.get(offset),
+            ScriptKey::Common(offset) => fighter.script_lookup_common.get(offset),
+            ScriptKey::Section(name) => {
+                return (
+                    name.clone(),
+                    format!(
+                        "/{}/scripts_common/{}.html",
+                        self.fighter_path(&fighter.fighter.name),
+                        name
+                    ),
+                );
+            }
+        };
+        match info {
+            Some(info) => (info.name.clone(), info.address.clone()),
+            None => (format!("{script:x?}"), String::new()),
+        }
+    }
+
     pub fn gen_action_links(
         &self,
         fighter: &HighLevelFighter,
@@ -557,8 +674,9 @@ impl BrawlMod {
             links.push(NavLink {
                 name,
                 link: format!(
-                    "/{}/{}/actions/{}.html",
-                    self.name, fighter.name, action.name
+                    "/{}/actions/{}.html",
+                    self.fighter_path(&fighter.name),
+                    action.name
                 ),
                 current: current_action == action.name,
             });
@@ -568,6 +686,19 @@ impl BrawlMod {
     }
 }
 
+#[derive(Serialize)]
+pub struct ScriptCallerLinks {
+    pub called_by: Vec<CallerLink>,
+    pub reachable_from: Vec<NavLink>,
+}
+
+#[derive(Serialize)]
+pub struct CallerLink {
+    pub name: String,
+    pub link: String,
+    pub kind: &'static str,
+}
+
 #[derive(Serialize)]
 pub struct SubactionLinks {
     pub attacks_jab: Vec<NavLink>,
@@ -598,3 +729,53 @@ pub struct SubactionLinks {
     pub has_glide: bool,
     pub has_crawl: bool,
 }
+
+/// Links to the scripts in `dir` ordered by offset, so that e.g. 0x2 comes before 0x10.
+fn script_fragment_links(dir: &str, scripts: &[ScriptAst], current_script: i32) -> Vec<NavLink> {
+    let mut scripts: Vec<_> = scripts.iter().collect();
+    scripts.sort_by_key(|x| x.offset);
+    scripts
+        .into_iter()
+        .map(|script| NavLink {
+            name: format!("0x{:x}", script.offset),
+            link: format!("{dir}/0x{:x}.html", script.offset),
+            current: current_script == script.offset,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use brawllib_rs::script_ast::Block;
+
+    #[test]
+    fn slug_case_only() {
+        assert_eq!(slug("Mario"), "mario");
+        assert_eq!(slug("mario"), "mario");
+    }
+
+    #[test]
+    fn slug_punctuation() {
+        assert_eq!(slug("Captain Falcon"), "captain-falcon");
+        assert_eq!(slug("Game & Watch"), "game-and-watch");
+        assert_eq!(slug("R.O.B"), "rob");
+        assert_eq!(slug("King Dedede's  Hammer!"), "king-dededes-hammer");
+    }
+
+    #[test]
+    fn script_fragment_links_numeric_order() {
+        let scripts: Vec<_> = [0x10, 0x1a0, 0x2]
+            .into_iter()
+            .map(|offset| ScriptAst {
+                block: Block { events: vec![] },
+                offset,
+            })
+            .collect();
+        let links = script_fragment_links("/P+/mario/scripts", &scripts, 0x10);
+        let names: Vec<_> = links.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(names, ["0x2", "0x10", "0x1a0"]);
+        assert_eq!(links[0].link, "/P+/mario/scripts/0x2.html");
+        assert!(links[1].current);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::assets;
+use crate::cli::Args;
+use crate::output::{self, Encoding, OutDir};
+use handlebars::Handlebars;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Keeps track of the hash of the inputs used to generate each file in the output root.
+/// When the inputs of a file have not changed since the previous run, generating the file is skipped.
+///
+/// Each kind of output (pages, gifs) has its own cache, so that e.g. running with only `-w` doesn't prune all the gifs.
+pub struct BuildCache {
+    path: PathBuf,
+    /// Hash of everything every page depends on that is known when the cache is loaded:
+    /// the templates, the settings files and the binary, so that changes to the code generating the pages rebuild them.
+    base_hash: String,
+    /// Hash of the nav and asset urls, which every page includes.
+    shared_hash: OnceLock<String>,
+    rebuild: bool,
+    /// Files that were not generated by this run should only be removed if the run was not filtered.
+    prune: bool,
+    previous: BTreeMap<PathBuf, String>,
+    current: Mutex<BTreeMap<PathBuf, String>>,
+}
+
+impl BuildCache {
+    pub fn load(kind: &str, args: &Args) -> BuildCache {
+        let path = Path::new("../build_cache").join(format!("{kind}.json"));
+        let previous = match fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+                warn!("Ignoring invalid build cache {path:?}: {e}");
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+
+        let mut base = vec![];
+        hash_files(&mut base, Path::new("templates"), "");
+        // Settings such as mod mechanics, event definitions and variable names.
+        hash_files(&mut base, Path::new("."), ".json");
+        hash_files(&mut base, Path::new("../data"), ".json");
+        for entry in fs::read_dir("../data").into_iter().flatten() {
+            let dir = entry.unwrap().path();
+            hash_files(&mut base, &dir, ".json");
+            hash_files(&mut base, &dir.join("variable_names"), ".json");
+        }
+        // Hashing the whole binary would be slow, a new build is enough to rebuild every page.
+        if let Ok(metadata) = std::env::current_exe().and_then(fs::metadata) {
+            base.extend(format!("{:?}{:?}", metadata.len(), metadata.modified().ok()).bytes());
+        }
+
+        BuildCache {
+            path,
+            base_hash: assets::hash(&base),
+            shared_hash: OnceLock::new(),
+            rebuild: args.rebuild,
+            prune: args.mod_names.is_empty() && args.fighter_names.is_empty(),
+            previous,
+            current: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Sets the hash of the inputs shared by every page, must be called before any pages are rendered.
+    pub fn set_shared_hash(&self, hash: String) {
+        self.shared_hash.set(hash).unwrap();
+    }
+
+    /// False when the run was filtered to some mods or fighters, so files listing every page would be missing pages.
+    pub fn is_complete(&self) -> bool {
+        self.prune
+    }
+
+    /// Returns true if the file needs to be generated because it doesn't exist or was generated from different inputs.
+    /// Either way the file is marked as used by this run so it won't be pruned.
+    pub fn is_stale(&self, dir: &OutDir, file_name: &str, input_hash: &str) -> bool {
+        let relative_path = dir.relative_path(file_name);
+        // Changing the output encodings needs every file to be written again.
+        let input_hash = format!("{input_hash}{:?}", output::encodings());
+        let stale = self.rebuild
+            || self.previous.get(&relative_path) != Some(&input_hash)
+            || !Path::new("../root").join(&relative_path).exists();
+
+        self.current
+            .lock()
+            .unwrap()
+            .insert(relative_path, input_hash);
+        stale
+    }
+
+    /// Marks the file as used by this run without generating it, so it won't be pruned.
+    pub fn keep(&self, dir: &OutDir, file_name: &str) {
+        let relative_path = dir.relative_path(file_name);
+        let hash = self
+            .previous
+            .get(&relative_path)
+            .cloned()
+            .unwrap_or_default();
+        self.current.lock().unwrap().insert(relative_path, hash);
+    }
+
+    /// Renders the page to `file_name` in `dir` when its inputs changed since the previous run.
+    ///
+    /// `inputs` are the hashes of the data the page is generated from, e.g. the fighter, along with everything in `base_hash` and `shared_hash`.
+    /// `context` is only called when the page is stale, so an unchanged page skips all the work of generating it.
+    pub fn render_page(
+        &self,
+        handlebars: &Handlebars,
+        template: &str,
+        dir: &OutDir,
+        file_name: &str,
+        inputs: &[&str],
+        context: impl FnOnce() -> serde_json::Value,
+    ) {
+        if self.is_stale(dir, file_name, &self.inputs_hash(template, inputs)) {
+            let html = handlebars.render(template, &context()).unwrap();
+            dir.create_compressed_file(file_name, html.as_bytes());
+        }
+    }
+
+    /// Writes the data returned by `data` to `file_name` in `dir` when its inputs changed since the previous run.
+    /// Returns the url of the file.
+    pub fn create_file_from_inputs(
+        &self,
+        dir: &OutDir,
+        file_name: &str,
+        inputs: &[&str],
+        data: impl FnOnce() -> Vec<u8>,
+    ) -> String {
+        if self.is_stale(dir, file_name, &self.inputs_hash("", inputs)) {
+            dir.create_compressed_file(file_name, &data())
+        } else {
+            dir.url(file_name)
+        }
+    }
+
+    fn inputs_hash(&self, template: &str, inputs: &[&str]) -> String {
+        let shared = self.shared_hash.get().expect(
+            "BuildCache::set_shared_hash must be called before generating files from inputs",
+        );
+        let input = format!(
+            "{template}\n{}\n{shared}\n{}",
+            self.base_hash,
+            inputs.join("\n")
+        );
+        assets::hash(input.as_bytes())
+    }
+
+    /// Writes the data in every output encoding to `file_name` in `dir`, skipping the write if the data is identical to the previous run.
+    /// Returns the url of the file.
+    pub fn create_compressed_file(&self, dir: &OutDir, file_name: &str, data: &[u8]) -> String {
+        if self.is_stale(dir, file_name, &assets::hash(data)) {
+            dir.create_compressed_file(file_name, data)
+        } else {
+            dir.url(file_name)
+        }
+    }
+
+    /// Save the hashes for the next run.
+    /// When the run was not filtered, files generated by a previous run that were not generated by this run are deleted.
+    pub fn save(self) {
+        let mut current = self.current.into_inner().unwrap();
+
+        for (relative_path, hash) in self.previous {
+            if current.contains_key(&relative_path) {
+                continue;
+            }
+
+            if self.prune {
+                // Also remove the other encodings of the file, including any from previously configured encodings.
+                for encoding in [
+                    Encoding::Identity,
+                    Encoding::Gzip,
+                    Encoding::Br,
+                    Encoding::Zstd,
+                ] {
+                    let mut path = Path::new("../root").join(&relative_path).into_os_string();
+                    path.push(encoding.extension());
+                    let path = PathBuf::from(path);
+                    if path.exists() {
+                        info!("Pruning {path:?}");
+                        fs::remove_file(&path).unwrap();
+                    }
+                }
+            } else {
+                current.insert(relative_path, hash);
+            }
+        }
+
+        fs::create_dir_all(self.path.parent().unwrap()).unwrap();
+        fs::write(&self.path, serde_json::to_vec(&current).unwrap())
+            .map_err(|e| anyhow::anyhow!("Failed to write to {:?} {e}", self.path))
+            .unwrap();
+    }
+}
+
+/// Adds the name and contents of every file in the directory ending in `extension` to `out`, sorted by name.
+fn hash_files(out: &mut Vec<u8>, dir: &Path, extension: &str) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<_> = entries
+        .map(|x| x.unwrap().path())
+        .filter(|x| x.is_file() && x.to_str().is_some_and(|x| x.ends_with(extension)))
+        .collect();
+    paths.sort();
+    for path in paths {
+        out.extend(path.to_str().unwrap().as_bytes());
+        out.extend(fs::read(&path).unwrap());
+    }
+}
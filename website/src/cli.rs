@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 #[derive(Parser, Clone)]
 pub struct Args {
@@ -18,10 +18,59 @@ pub struct Args {
     #[clap(long, short = 'w', action)]
     pub generate_web: bool,
 
+    /// Generate a changelog between two mod folders in data/ e.g. `--changelog P+2.4,P+3.0`
+    #[clap(long, value_delimiter = ',', value_name = "OLD,NEW")]
+    pub changelog: Vec<String>,
+
+    /// Ignore the build cache and regenerate every page and gif
+    #[clap(long, short, action)]
+    pub rebuild: bool,
+
+    /// Check every link in the output root after generating it, exiting with an error if there are new broken links or script lookup failures
+    #[clap(long, action)]
+    pub check_links: bool,
+
     /// Serve the website at localhost:8000 after generating it
     #[clap(long, short)]
     #[clap(long, short, action)]
     pub serve: bool,
+
+    /// Serve a development server at localhost:8000 that renders pages from memory on request,
+    /// reloading open pages when `templates` or `src/assets` change
+    #[clap(long, action)]
+    pub dev: bool,
+
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum Command {
+    /// Upload the output root to an S3 compatible bucket, only uploading files that changed since the previous deploy
+    Deploy(DeployArgs),
+}
+
+#[derive(clap::Args, Clone)]
+pub struct DeployArgs {
+    /// Name of the bucket to upload to
+    #[clap(long, env = "OUTPUT_BUCKET_NAME")]
+    pub bucket: String,
+
+    /// Endpoint of the S3 compatible server e.g. `http://localhost:9000` for MinIO, defaults to AWS S3
+    #[clap(long, env = "S3_ENDPOINT")]
+    pub endpoint: Option<String>,
+
+    /// Region of the bucket
+    #[clap(long, env = "AWS_REGION", default_value = "us-east-1")]
+    pub region: String,
+
+    /// Address the bucket in the url path instead of the subdomain, as required by MinIO
+    #[clap(long, action)]
+    pub path_style: bool,
+
+    /// Print the files that would be uploaded and deleted without changing the bucket
+    #[clap(long, action)]
+    pub dry_run: bool,
 }
 
 pub fn args() -> Args {
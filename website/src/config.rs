@@ -1,5 +1,8 @@
-use anyhow::{Result, anyhow};
-use serde::{Deserialize, Serialize};
+use crate::knockback_formulas::BlastZone;
+use crate::mechanics::MechanicsConfig;
+use crate::output::{self, Encoding};
+use anyhow::{Result, bail};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::{
     env::current_dir,
     path::{Path, PathBuf},
@@ -8,24 +11,132 @@ use std::{
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Config {
     pub legacy_renderer: bool,
-    pub mods: Vec<String>,
+    /// The mods listed in the nav and on the index page, in order.
+    /// An entry can be just the name of the mod's folder in `data` to use the default settings.
+    /// Folders in `data` without an entry are still loaded with the default settings, but are only listed when `mods` is empty.
+    #[serde(deserialize_with = "deserialize_mods")]
+    pub mods: Vec<ModConfig>,
     /// Mod directories are still created in the true root outside of the web root.
     /// This is too maintain backwards compatibility with existing links to rukaidata.com
     /// We could very easily introduce a web_root_mods field so that mods
     /// could be also set to the same directory allowing other users to fully alter the web root.
     pub web_root: String,
+    /// Used by the knockback calculator and kill percent tables.
+    #[serde(default)]
+    pub blast_zone: BlastZone,
+    /// The first encoding is written to each file's own name, every other encoding is written alongside it with the encoding's extension.
+    /// Defaults to only gzip, which is what AWS S3 hosting needs.
+    #[serde(default = "output::default_encodings")]
+    pub output_encodings: Vec<Encoding>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModConfig {
+    /// The mod's folder in `data`
+    pub directory: String,
+    /// Shown in the nav and on the index page, defaults to `directory`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The mod's pages are generated in `/{slug}/`, defaults to `directory`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    /// The folder in `data` that the mod's files are layered over, defaults to `Brawl`.
+    /// Only a single mod can be layered over a folder, so layering over another mod requires a folder laid out like a brawl dump with the other mod's files already applied.
+    /// The mod fails to load when the folder is not a brawl dump.
+    /// The `Brawl` folder is the brawl dump itself and is never layered over anything.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// Fighters that are never loaded, case insensitive.
+    #[serde(default = "default_hidden_fighters")]
+    pub hidden_fighters: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The release of the mod that is in `data` e.g. `3.0.5`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Used to derive frame data such as hitlag and shieldstun, defaults to the preset guessed from `directory`
+    #[serde(default)]
+    pub mechanics: MechanicsConfig,
+}
+
+impl ModConfig {
+    /// The default settings for the mod in this folder.
+    pub fn new(directory: String) -> Self {
+        ModConfig {
+            directory,
+            name: None,
+            slug: None,
+            base: None,
+            hidden_fighters: default_hidden_fighters(),
+            description: None,
+            version: None,
+            mechanics: MechanicsConfig::default(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.directory)
+    }
+
+    pub fn slug(&self) -> &str {
+        self.slug.as_deref().unwrap_or(&self.directory)
+    }
+
+    /// None when the mod is a brawl dump rather than a mod.
+    pub fn base(&self) -> Option<&str> {
+        if self.directory.eq_ignore_ascii_case("brawl") {
+            None
+        } else {
+            Some(self.base.as_deref().unwrap_or("Brawl"))
+        }
+    }
+}
+
+/// Pokemon trainer is just a wrapper around the three pokemon, which are loaded as their own fighters.
+fn default_hidden_fighters() -> Vec<String> {
+    vec!["poketrainer".to_owned()]
+}
+
+/// Allows `mods` entries to be either a folder name or a full `ModConfig`.
+fn deserialize_mods<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<ModConfig>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Directory(String),
+        Config(ModConfig),
+    }
+
+    Ok(Vec::<Entry>::deserialize(deserializer)?
+        .into_iter()
+        .map(|entry| match entry {
+            Entry::Directory(directory) => ModConfig::new(directory),
+            Entry::Config(config) => config,
+        })
+        .collect())
 }
 
 impl Config {
     pub fn load() -> Result<Self> {
         let path = default_config_path();
         if path.exists() {
-            serde_json::from_slice(&std::fs::read(path)?).map_err(|e| anyhow!(e))
+            let value: serde_json::Value = serde_json::from_slice(&std::fs::read(&path)?)?;
+            if value.get("mechanics").is_some() {
+                bail!(
+                    "{path:?} has a top level `mechanics` map, which is no longer used. Move each mod's mechanics to the `mechanics` field of its entry in `mods`."
+                );
+            }
+            let config: Config = serde_json::from_value(value)?;
+            output::validate_encodings(&config.output_encodings)?;
+            Ok(config)
         } else {
             let config = Config {
                 legacy_renderer: false,
-                mods: vec!["Brawl".to_owned()],
+                mods: vec![ModConfig::new("Brawl".to_owned())],
                 web_root: "/".to_owned(),
+                blast_zone: BlastZone::default(),
+                output_encodings: output::default_encodings(),
             };
             config.save(&path);
             Ok(config)
@@ -0,0 +1,211 @@
+//! Uploads the output root to an S3 compatible bucket, started with the `deploy` subcommand.
+//!
+//! The bucket holds a manifest of the hash and headers of every file uploaded by the previous deploy.
+//! Only files that are new or differ from the manifest are uploaded and files in the manifest that are no longer in the output root are deleted.
+//! Objects in the bucket that were never uploaded by a deploy are left alone.
+//!
+//! `assets_static` is uploaded before the pages that reference it and nothing is deleted until every upload succeeded,
+//! so the site keeps working while a deploy is in progress.
+
+use crate::assets;
+use crate::cli::DeployArgs;
+use crate::output::Encoding;
+use anyhow::{Context, Result, bail};
+use axum::http::header::{CACHE_CONTROL, CONTENT_ENCODING};
+use axum::http::{HeaderMap, HeaderValue};
+use futures::{StreamExt, TryStreamExt};
+use s3::creds::Credentials;
+use s3::request::ResponseData;
+use s3::{Bucket, Region};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// The key of the manifest in the bucket.
+const MANIFEST_KEY: &str = "deploy_manifest.json";
+
+/// How many requests are made to the bucket at once.
+const CONCURRENT_REQUESTS: usize = 16;
+
+/// Keyed by the object key.
+type Manifest = BTreeMap<String, ManifestEntry>;
+
+/// The headers are included so that changing e.g. the cache policy uploads the files again.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct ManifestEntry {
+    hash: String,
+    content_type: String,
+    content_encoding: Option<String>,
+    cache_control: String,
+}
+
+pub fn deploy(args: &DeployArgs, encodings: &[Encoding]) -> Result<()> {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(run(args, encodings))
+}
+
+async fn run(args: &DeployArgs, encodings: &[Encoding]) -> Result<()> {
+    let region = match &args.endpoint {
+        Some(endpoint) => Region::Custom {
+            region: args.region.clone(),
+            endpoint: endpoint.clone(),
+        },
+        None => args.region.parse()?,
+    };
+    let credentials = Credentials::default().context("Failed to load the S3 credentials")?;
+    let mut bucket = Bucket::new(&args.bucket, region, credentials)?;
+    if args.path_style {
+        bucket = bucket.with_path_style();
+    }
+
+    let mut local = Manifest::new();
+    walk(Path::new("../root"), "", encodings, &mut local)?;
+    let remote = fetch_manifest(&bucket).await?;
+
+    // Assets first so that new pages never reference assets that aren't uploaded yet.
+    let mut uploads: Vec<&String> = local
+        .iter()
+        .filter(|(key, entry)| remote.get(*key) != Some(*entry))
+        .map(|(key, _)| key)
+        .collect();
+    uploads.sort_by_key(|key| !key.starts_with("assets_static/"));
+    let deletes: Vec<&String> = remote.keys().filter(|x| !local.contains_key(*x)).collect();
+
+    if args.dry_run {
+        for key in &uploads {
+            let status = if remote.contains_key(*key) {
+                "changed"
+            } else {
+                "new"
+            };
+            println!("upload {key} ({status})");
+        }
+        for key in &deletes {
+            println!("delete {key}");
+        }
+        println!(
+            "dry run: would upload {} files, delete {} files and leave {} files unchanged",
+            uploads.len(),
+            deletes.len(),
+            local.len() - uploads.len()
+        );
+        return Ok(());
+    }
+
+    futures::stream::iter(uploads.iter().map(|key| upload(&bucket, key, &local[*key])))
+        .buffer_unordered(CONCURRENT_REQUESTS)
+        .try_collect::<Vec<_>>()
+        .await?;
+    info!("uploaded {} files", uploads.len());
+
+    futures::stream::iter(deletes.iter().map(|key| delete(&bucket, key)))
+        .buffer_unordered(CONCURRENT_REQUESTS)
+        .try_collect::<Vec<_>>()
+        .await?;
+    info!("deleted {} files", deletes.len());
+
+    // Uploaded last, so that an interrupted deploy uploads the remaining changes next time.
+    let manifest = serde_json::to_vec(&local)?;
+    let mut headers = HeaderMap::new();
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    let response = bucket
+        .with_extra_headers(headers)?
+        .put_object_with_content_type(MANIFEST_KEY, &manifest, "application/json")
+        .await?;
+    check(response, "upload", MANIFEST_KEY)?;
+    info!("deployed to {}", args.bucket);
+    Ok(())
+}
+
+/// Adds every file in the directory to the manifest, keyed by its path relative to the output root.
+fn walk(dir: &Path, prefix: &str, encodings: &[Encoding], manifest: &mut Manifest) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {dir:?}"))? {
+        let entry = entry?;
+        let name = entry
+            .file_name()
+            .into_string()
+            .map_err(|x| anyhow::anyhow!("Non utf-8 file name {x:?}"))?;
+        let key = format!("{prefix}{name}");
+        if entry.file_type()?.is_dir() {
+            walk(&entry.path(), &format!("{key}/"), encodings, manifest)?;
+        } else {
+            let data = fs::read(entry.path())?;
+            manifest.insert(key.clone(), manifest_entry(&key, &data, encodings));
+        }
+    }
+    Ok(())
+}
+
+fn manifest_entry(key: &str, data: &[u8], encodings: &[Encoding]) -> ManifestEntry {
+    // Files in a secondary encoding are served with the type of the file they were encoded from.
+    let (served_key, encoding) = encodings[1..]
+        .iter()
+        .find_map(|encoding| {
+            key.strip_suffix(encoding.extension())
+                .map(|served_key| (served_key, *encoding))
+        })
+        .unwrap_or((key, encodings[0]));
+
+    // The file names in `assets_static` contain the hash of their contents, so can be cached forever.
+    // Everything else is revalidated on every request, with the browser deciding how long to rely on its cache first.
+    let cache_control = if key.starts_with("assets_static/") {
+        "max-age=31536000"
+    } else {
+        "no-cache"
+    };
+
+    ManifestEntry {
+        hash: assets::hash(data),
+        content_type: mime_guess::from_path(served_key)
+            .first_or_octet_stream()
+            .to_string(),
+        content_encoding: encoding.content_encoding().map(|x| x.to_owned()),
+        cache_control: cache_control.to_owned(),
+    }
+}
+
+/// The manifest of the previous deploy, empty if the bucket has never been deployed to.
+async fn fetch_manifest(bucket: &Bucket) -> Result<Manifest> {
+    let response = bucket.get_object(MANIFEST_KEY).await?;
+    match response.status_code() {
+        200 => serde_json::from_slice(response.as_slice())
+            .with_context(|| format!("Invalid {MANIFEST_KEY} in the bucket")),
+        404 => {
+            info!("No {MANIFEST_KEY} in the bucket, uploading every file");
+            Ok(Manifest::new())
+        }
+        status => bail!(
+            "Failed to download {MANIFEST_KEY}, status {status}: {}",
+            String::from_utf8_lossy(response.as_slice())
+        ),
+    }
+}
+
+async fn upload(bucket: &Bucket, key: &str, entry: &ManifestEntry) -> Result<()> {
+    let data = tokio::fs::read(Path::new("../root").join(key)).await?;
+    let mut headers = HeaderMap::new();
+    headers.insert(CACHE_CONTROL, entry.cache_control.parse()?);
+    if let Some(content_encoding) = &entry.content_encoding {
+        headers.insert(CONTENT_ENCODING, content_encoding.parse()?);
+    }
+    let response = bucket
+        .with_extra_headers(headers)?
+        .put_object_with_content_type(key, &data, &entry.content_type)
+        .await?;
+    check(response, "upload", key)
+}
+
+async fn delete(bucket: &Bucket, key: &str) -> Result<()> {
+    check(bucket.delete_object(key).await?, "delete", key)
+}
+
+fn check(response: ResponseData, action: &str, key: &str) -> Result<()> {
+    let status = response.status_code();
+    if !(200..300).contains(&status) {
+        bail!(
+            "Failed to {action} {key}, status {status}: {}",
+            String::from_utf8_lossy(response.as_slice())
+        );
+    }
+    Ok(())
+}
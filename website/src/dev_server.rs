@@ -0,0 +1,232 @@
+//! A server for working on the website, started with `--dev`.
+//!
+//! The mods are loaded once at startup and each page is generated into memory the first time it is requested,
+//! by the one generator that writes it, keeping only the context the page is rendered from.
+//! Pages are then rendered through handlebars on each request, so edits to `templates` show up without generating anything again.
+//! Edits to `src/assets` regenerate the assets in memory.
+//! Either way, open browser tabs are told to reload over a server-sent event stream.
+//!
+//! Changes to the rust code still need a restart, as a page's context is only generated once.
+
+use crate::api;
+use crate::assets::AssetPaths;
+use crate::brawl_data::BrawlMods;
+use crate::config::Config;
+use crate::page;
+use crate::site::{MemoryFile, Site};
+use axum::Router;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{StatusCode, Uri, header};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use futures::Stream;
+use handlebars::Handlebars;
+use std::convert::Infallible;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::sync::broadcast;
+
+/// The event stream `RELOAD_SCRIPT` listens to.
+const RELOAD_URL: &str = "/__dev/reload";
+
+/// Inserted into every page so that it reloads when the server says so.
+const RELOAD_SCRIPT: &str =
+    r#"<script>new EventSource("/__dev/reload").onmessage = () => location.reload();</script>"#;
+
+struct DevState {
+    config: Config,
+    brawl_mods: Arc<BrawlMods>,
+    site: Site,
+    handlebars: RwLock<Handlebars<'static>>,
+    assets: RwLock<AssetPaths>,
+    reload: broadcast::Sender<()>,
+}
+
+pub fn serve(config: Config, brawl_mods: BrawlMods) {
+    let handlebars = load_templates().unwrap();
+    let site = Site::in_memory();
+    let assets = AssetPaths::new_live(&config, &site);
+    let brawl_mods = Arc::new(brawl_mods);
+
+    let state = Arc::new(DevState {
+        config,
+        brawl_mods: brawl_mods.clone(),
+        site,
+        handlebars: RwLock::new(handlebars),
+        assets: RwLock::new(assets),
+        reload: broadcast::channel(16).0,
+    });
+
+    let watch_state = state.clone();
+    std::thread::spawn(move || watch(&watch_state));
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let app = api::router(brawl_mods).merge(
+            Router::new()
+                .route(RELOAD_URL, get(reload_events))
+                .fallback(serve_file)
+                .with_state(state),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:8000")
+            .await
+            .unwrap();
+        println!(
+            "dev server listening on http://{}",
+            listener.local_addr().unwrap()
+        );
+        axum::serve(listener, app).await.unwrap();
+    })
+}
+
+fn load_templates() -> anyhow::Result<Handlebars<'static>> {
+    let mut handlebars = Handlebars::new();
+    handlebars.register_templates_directory(".html.hbs", "templates")?;
+    Ok(handlebars)
+}
+
+/// Polls `templates` and `src/assets` for changes, reloading them and then every open page.
+fn watch(state: &DevState) {
+    let mut templates = modified_times("templates");
+    let mut assets = modified_times("src/assets");
+    loop {
+        std::thread::sleep(Duration::from_millis(500));
+
+        let new_templates = modified_times("templates");
+        if new_templates != templates {
+            templates = new_templates;
+            match load_templates() {
+                Ok(handlebars) => {
+                    info!("templates reloaded");
+                    *state.handlebars.write().unwrap() = handlebars;
+                    state.reload.send(()).ok();
+                }
+                // Keep the previous templates until the error is fixed.
+                Err(err) => error!("Failed to reload templates: {err}"),
+            }
+        }
+
+        let new_assets = modified_times("src/assets");
+        if new_assets != assets {
+            assets = new_assets;
+            *state.assets.write().unwrap() = AssetPaths::new_live(&state.config, &state.site);
+            info!("assets reloaded");
+            state.reload.send(()).ok();
+        }
+    }
+}
+
+fn modified_times(dir: &str) -> Vec<(String, SystemTime)> {
+    let mut times: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((entry.file_name().into_string().ok()?, modified))
+        })
+        .collect();
+    times.sort();
+    times
+}
+
+async fn reload_events(
+    State(state): State<Arc<DevState>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = futures::stream::unfold(state.reload.subscribe(), |mut rx| async move {
+        match rx.recv().await {
+            Err(broadcast::error::RecvError::Closed) => None,
+            // Missing some reloads doesn't matter, the page is reloaded either way.
+            Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) => {
+                Some((Ok(Event::default().data("reload")), rx))
+            }
+        }
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn serve_file(State(state): State<Arc<DevState>>, uri: Uri) -> Response {
+    let Ok(url) = percent_encoding::percent_decode_str(uri.path()).decode_utf8() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let url = if url.ends_with('/') {
+        format!("{url}index.html")
+    } else {
+        url.into_owned()
+    };
+
+    match memory_file(&state, &url).await {
+        Some(MemoryFile::Page { template, context }) => render_page(&state, &template, &context),
+        Some(MemoryFile::Data(data)) => {
+            let mime = mime_guess::from_path(Path::new(&url)).first_or_octet_stream();
+            ([(header::CONTENT_TYPE, mime.to_string())], data).into_response()
+        }
+        // Pages link relative to their directory so need the trailing slash.
+        None if memory_file(&state, &format!("{url}/index.html"))
+            .await
+            .is_some() =>
+        {
+            Response::builder()
+                .status(StatusCode::PERMANENT_REDIRECT)
+                .header(header::LOCATION, format!("{}/", uri.path()))
+                .body(Body::empty())
+                .unwrap()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// The file at the url, generating it if this is the first request for it.
+async fn memory_file(state: &Arc<DevState>, url: &str) -> Option<MemoryFile> {
+    if let Some(file) = state.site.memory_file(url) {
+        return Some(file);
+    }
+    let state = state.clone();
+    let url = url.to_owned();
+    tokio::task::spawn_blocking(move || {
+        let site = Site::in_memory_only(&url);
+        page::generate_url(
+            &state.handlebars.read().unwrap(),
+            &state.brawl_mods,
+            &state.assets.read().unwrap(),
+            &site,
+            &state.config,
+            &url,
+        );
+        let file = site.memory_file(&url)?;
+        info!("generated {url}");
+        state.site.insert_memory_file(url, file.clone());
+        Some(file)
+    })
+    .await
+    .unwrap()
+}
+
+/// Renders the page with the current templates and assets, the rest of the context is from when the page was generated.
+fn render_page(state: &DevState, template: &str, context: &[u8]) -> Response {
+    let mut context: serde_json::Value = serde_json::from_slice(context).unwrap();
+    if let Some(assets) = context.get_mut("assets") {
+        *assets = serde_json::to_value(&*state.assets.read().unwrap()).unwrap();
+    }
+
+    match state.handlebars.read().unwrap().render(template, &context) {
+        Ok(mut html) => {
+            match html.rfind("</body>") {
+                Some(index) => html.insert_str(index, RELOAD_SCRIPT),
+                None => html.push_str(RELOAD_SCRIPT),
+            }
+            ([(header::CONTENT_TYPE, "text/html")], html).into_response()
+        }
+        // Still reload once the template is fixed.
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            [(header::CONTENT_TYPE, "text/html")],
+            format!("<pre>Failed to render {template}: {err}</pre>{RELOAD_SCRIPT}"),
+        )
+            .into_response(),
+    }
+}
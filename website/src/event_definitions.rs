@@ -0,0 +1,169 @@
+//! Names and arguments of the events brawllib doesn't know, e.g. the custom events that mods add through code hacks.
+//! Keys are the namespace and code of the event in hex, the first half of the event id used by PSA e.g. `4A00` for `4A000100`.
+//!
+//! `website/event_definitions.json` holds the definitions shared by every mod.
+//! A mod overrides and adds to them in `data/{mod}/event_definitions.json`.
+//! Every file has the same format:
+//!
+//! ```json
+//! {
+//!     "4A00": {
+//!         "name": "Set Custom Flag",
+//!         "description": "Shown when hovering over the event.",
+//!         "arguments": [
+//!             { "name": "flag", "type": "hex" },
+//!             { "name": "enabled", "type": "bool", "description": "Shown when hovering over the argument." }
+//!         ]
+//!     }
+//! }
+//! ```
+//!
+//! Argument types are `value`, `hex` and `bool` and only change how a value argument is displayed.
+//! Any other argument, e.g. a scalar or a variable, is displayed as stored.
+
+use anyhow::{Result, anyhow};
+use brawllib_rs::high_level_fighter::HighLevelFighter;
+use brawllib_rs::script::Event;
+use brawllib_rs::script_ast::{Block, EventAst};
+use std::collections::{BTreeSet, HashMap};
+use std::env::current_dir;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::script_walker::ScriptWalker;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct EventKey {
+    pub namespace: u8,
+    pub code: u8,
+}
+
+impl EventKey {
+    pub fn new(event: &Event) -> EventKey {
+        EventKey {
+            namespace: event.namespace,
+            code: event.code,
+        }
+    }
+}
+
+impl fmt::Display for EventKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02X}{:02X}", self.namespace, self.code)
+    }
+}
+
+impl FromStr for EventKey {
+    type Err = anyhow::Error;
+
+    fn from_str(key: &str) -> Result<Self> {
+        let invalid = || anyhow!("{key:?} is not an event e.g. 4A00");
+        if key.len() != 4 || !key.is_ascii() {
+            return Err(invalid());
+        }
+        Ok(EventKey {
+            namespace: u8::from_str_radix(&key[..2], 16).map_err(|_| invalid())?,
+            code: u8::from_str_radix(&key[2..], 16).map_err(|_| invalid())?,
+        })
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct EventDefinition {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<ArgumentDefinition>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ArgumentDefinition {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: Option<ArgumentType>,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgumentType {
+    Value,
+    /// A value displayed in hex e.g. flags and ids
+    Hex,
+    /// A value displayed as `true` when it isn't 0
+    Bool,
+}
+
+#[derive(Clone, Default)]
+pub struct EventDefinitions(HashMap<EventKey, EventDefinition>);
+
+impl EventDefinitions {
+    /// Loads `website/event_definitions.json`
+    pub fn load() -> Result<Self> {
+        EventDefinitions::default().with_overrides(&current_dir()?.join("event_definitions.json"))
+    }
+
+    /// Returns the definitions with the definitions in the file at `path` taking priority, the definitions are unchanged if the file does not exist.
+    pub fn with_overrides(&self, path: &Path) -> Result<Self> {
+        let mut definitions = self.clone();
+        if path.exists() {
+            let file: HashMap<String, EventDefinition> =
+                serde_json::from_slice(&std::fs::read(path)?)
+                    .map_err(|e| anyhow!("Failed to parse {path:?} {e}"))?;
+            for (key, definition) in file {
+                let key = key
+                    .parse()
+                    .map_err(|e| anyhow!("Failed to parse {path:?} {e}"))?;
+                definitions.0.insert(key, definition);
+            }
+        }
+        Ok(definitions)
+    }
+
+    pub fn get(&self, event: &Event) -> Option<&EventDefinition> {
+        self.0.get(&EventKey::new(event))
+    }
+
+    /// Every event used by the fighter's scripts that brawllib doesn't know and has no definition.
+    pub fn undefined(&self, fighter: &HighLevelFighter) -> BTreeSet<EventKey> {
+        let mut blocks: Vec<(&Block, bool)> = vec![];
+        for action in &fighter.actions {
+            blocks.push((&action.script_entry.block, action.script_entry_common));
+            blocks.push((&action.script_exit.block, action.script_exit_common));
+        }
+        for subaction in &fighter.subactions {
+            let scripts = &subaction.scripts;
+            for script in [
+                &scripts.script_main,
+                &scripts.script_gfx,
+                &scripts.script_sfx,
+                &scripts.script_other,
+            ] {
+                blocks.push((&script.block, false));
+            }
+        }
+        for script in &fighter.scripts_fragment_fighter {
+            blocks.push((&script.block, false));
+        }
+        for script in &fighter.scripts_fragment_common {
+            blocks.push((&script.block, true));
+        }
+        for script in &fighter.scripts_section {
+            blocks.push((&script.script.block, true));
+        }
+
+        let mut undefined = BTreeSet::new();
+        let mut walker = ScriptWalker::new(fighter);
+        for (block, common) in blocks {
+            walker.walk(block, common, &mut |event| {
+                if let EventAst::Unknown(event) = event {
+                    if self.get(event).is_none() {
+                        undefined.insert(EventKey::new(event));
+                    }
+                }
+            });
+        }
+        undefined
+    }
+}
@@ -0,0 +1,822 @@
+//! A typed model of all the frame data we derive from a subaction.
+//!
+//! The subaction pages are rendered from this model and it is also exported as JSON and CSV
+//! so that other tools can use the data without scraping the html.
+
+use crate::brawl_data::{BrawlFighter, BrawlMod, BrawlMods};
+use crate::output::OutDir;
+use crate::site::Site;
+use brawllib_rs::high_level_fighter::{CollisionBoxValues, HighLevelSubaction};
+use brawllib_rs::script_ast::{AngleFlip, GrabTarget, HitBoxEffect, HitBoxSound};
+use rayon::prelude::*;
+use std::fmt;
+use std::sync::OnceLock;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SubactionFrameData {
+    pub name: String,
+    /// Internal subaction index
+    pub index: usize,
+    pub frames: usize,
+    /// The first frame the subaction can be interrupted with another subaction.
+    pub iasa: Option<usize>,
+    pub auto_cancel: Vec<FrameRange>,
+    /// The amount of lag taken when auto cancelling, only present when the subaction can auto cancel.
+    pub auto_cancel_lag: Option<i32>,
+    pub landing_lag: Option<f32>,
+    /// Only present when the mod has L-cancelling.
+    pub landing_lag_l_cancel: Option<u32>,
+    pub fully_invincible: Vec<FrameRange>,
+    pub fully_intangible: Vec<FrameRange>,
+    pub partially_invincible: Vec<FrameRange>,
+    pub partially_intangible: Vec<FrameRange>,
+    pub reverse_direction: Vec<usize>,
+    pub hitboxes_active: Vec<FrameRange>,
+    pub hitbox_set_rehits: Vec<HitBoxSetRehit>,
+    pub hitbox_groups: Vec<HitBoxGroup>,
+    pub throw: Option<Throw>,
+}
+
+/// An inclusive range of frames, frames are numbered starting from 1.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct FrameRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl fmt::Display for FrameRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+/// All hitboxes within the hitbox set can hit the same enemy again on these frames.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HitBoxSetRehit {
+    pub set_id: usize,
+    pub frames: Vec<usize>,
+}
+
+/// A range of frames over which the hitboxes do not change.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HitBoxGroup {
+    pub frames: FrameRange,
+    pub hitboxes: Vec<HitBox>,
+}
+
+impl HitBoxGroup {
+    pub fn hits(&self) -> impl Iterator<Item = &Hit> {
+        self.hitboxes.iter().filter_map(|x| match x {
+            HitBox::Hit(hit) => Some(hit),
+            HitBox::Grab(_) => None,
+        })
+    }
+
+    pub fn frames_string(&self) -> String {
+        if self.frames.start == self.frames.end {
+            format!("Frame:{}", self.frames.start)
+        } else {
+            format!("Frames:{}-{}", self.frames.start, self.frames.end)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type")]
+pub enum HitBox {
+    Hit(Hit),
+    Grab(Grab),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Hit {
+    pub set_id: u8,
+    pub hitbox_id: u8,
+    pub damage: f32,
+    pub wdsk: i16,
+    pub bkb: i16,
+    pub kbg: i16,
+    pub angle: i32,
+    pub effect: HitBoxEffect,
+    pub sound: HitBoxSound,
+    pub angle_flipping: AngleFlip,
+    pub clang: bool,
+    pub direct: bool,
+    pub hitlag_mult: f32,
+    pub sdi_mult: f32,
+    pub shield_damage: i16,
+    pub tripping_rate: f32,
+    pub rehit_rate: i32,
+    pub can_be_shielded: bool,
+    pub can_be_reflected: bool,
+    pub can_be_absorbed: bool,
+    pub remain_grabbed: bool,
+    pub ignore_invincibility: bool,
+    pub freeze_frame_disable: bool,
+    pub flinchless: bool,
+    pub shieldstun: f32,
+    pub hitlag: f32,
+    /// None when the hit can't be shielded.
+    pub shield_advantage: Option<ShieldAdvantage>,
+    pub targets: HitTargets,
+}
+
+/// Frame advantage of the attacker when the hit is shielded, negative when the defender can act first.
+/// Both fighters experience the same hitlag, so it doesn't change the advantage.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type")]
+pub enum ShieldAdvantage {
+    /// The attacker stays grounded and waits until IASA or the end of the subaction.
+    /// Hitting later in the active frames leaves less of the subaction remaining, so the advantage is given for both the first and last active frame.
+    Grounded { first_frame: i32, last_frame: i32 },
+    /// The attacker lands on the frame after the hit, with and without L-cancelling.
+    /// Landing within an auto cancel window uses the auto cancel lag for both.
+    /// `l_cancel` is None when the mod doesn't have L-cancelling.
+    Aerial { landing: i32, l_cancel: Option<i32> },
+}
+
+impl ShieldAdvantage {
+    /// The advantage when the attack is used as well as possible.
+    pub fn best(&self) -> i32 {
+        match self {
+            ShieldAdvantage::Grounded { last_frame, .. } => *last_frame,
+            ShieldAdvantage::Aerial { landing, l_cancel } => l_cancel.unwrap_or(*landing),
+        }
+    }
+}
+
+impl fmt::Display for ShieldAdvantage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShieldAdvantage::Grounded {
+                first_frame,
+                last_frame,
+            } => {
+                if first_frame == last_frame {
+                    write!(f, "{first_frame}")
+                } else {
+                    write!(f, "{first_frame} to {last_frame}")
+                }
+            }
+            ShieldAdvantage::Aerial {
+                landing,
+                l_cancel: Some(l_cancel),
+            } => write!(f, "{landing} ({l_cancel} L-cancelled)"),
+            ShieldAdvantage::Aerial {
+                landing,
+                l_cancel: None,
+            } => write!(f, "{landing}"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HitTargets {
+    pub fighter_ground: bool,
+    pub fighter_air: bool,
+    pub waddle_dee_doo: bool,
+    pub pikmin: bool,
+    pub gyro: bool,
+    pub snake_grenade: bool,
+    pub mr_saturn: bool,
+    pub stage_non_wall_ceiling_floor: bool,
+    pub wall_ceiling_floor: bool,
+    pub link_bomb: bool,
+    pub bobomb: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Grab {
+    pub hitbox_id: u8,
+    pub set_action: i32,
+    pub target: GrabTarget,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Throw {
+    pub frame: usize,
+    pub damage: i32,
+    pub wdsk: i32,
+    pub bkb: i32,
+    pub kbg: i32,
+    pub angle: i32,
+    pub effect: HitBoxEffect,
+    pub sound: HitBoxSound,
+    pub grab_target: GrabTarget,
+    pub i_frames: i32,
+    pub weight_dependent_speed: bool,
+}
+
+impl SubactionFrameData {
+    pub fn new(
+        brawl_mod: &BrawlMod,
+        fighter: &BrawlFighter,
+        index: usize,
+        subaction: &HighLevelSubaction,
+    ) -> SubactionFrameData {
+        // generate auto cancel ranges
+        let mut auto_cancel = vec![];
+        let mut landing_lag_prev = true;
+        let mut last_frame_change = 0;
+        for (index, frame) in subaction.frames.iter().enumerate() {
+            if frame.landing_lag && !landing_lag_prev {
+                auto_cancel.push(FrameRange {
+                    start: last_frame_change + 1,
+                    end: index,
+                });
+            }
+            if landing_lag_prev != frame.landing_lag {
+                last_frame_change = index;
+                landing_lag_prev = frame.landing_lag;
+            }
+        }
+        if !landing_lag_prev && last_frame_change != 0 {
+            auto_cancel.push(FrameRange {
+                start: last_frame_change + 1,
+                end: subaction.frames.len(),
+            });
+        }
+
+        let auto_cancel_lag = if auto_cancel.is_empty() {
+            None
+        } else {
+            Some(
+                brawl_mod
+                    .mechanics
+                    .auto_cancel_lag(fighter.fighter.attributes.light_landing_lag),
+            )
+        };
+
+        let mut fully_invincible = RangeBuilder::default();
+        let mut partially_invincible = RangeBuilder::default();
+        let mut fully_intangible = RangeBuilder::default();
+        let mut partially_intangible = RangeBuilder::default();
+        let mut hitboxes_active = RangeBuilder::default();
+        let mut reverse_direction = vec![];
+        for (i, frame) in subaction.frames.iter().enumerate() {
+            let all_invincible = frame.hurt_boxes.iter().all(|x| x.state.is_invincible());
+            let any_invincible = frame.hurt_boxes.iter().any(|x| x.state.is_invincible());
+            let all_intangible = frame.hurt_boxes.iter().all(|x| x.state.is_intangible());
+            let any_intangible = frame.hurt_boxes.iter().any(|x| x.state.is_intangible());
+
+            fully_invincible.frame(i, all_invincible, !all_invincible);
+            partially_invincible.frame(i, !all_invincible && any_invincible, !any_invincible);
+            fully_intangible.frame(i, all_intangible, !all_intangible);
+            partially_intangible.frame(i, !all_intangible && any_intangible, !any_intangible);
+
+            let has_hitboxes = !frame.hit_boxes.is_empty();
+            hitboxes_active.frame(i, has_hitboxes, !has_hitboxes);
+
+            if frame.reverse_direction {
+                reverse_direction.push(i + 1);
+            }
+        }
+
+        let mut hitbox_set_rehits = vec![];
+        for set_id in 0..10 {
+            if subaction.frames.iter().any(|x| x.hitbox_sets_rehit[set_id]) {
+                let frames = subaction
+                    .frames
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, frame)| frame.hitbox_sets_rehit[set_id])
+                    .map(|(i, _)| i + 1)
+                    .collect();
+                hitbox_set_rehits.push(HitBoxSetRehit { set_id, frames });
+            }
+        }
+
+        // Frame numbers here start from 1, the same as the ranges in the hitbox groups.
+        let first_actionable = subaction
+            .iasa
+            .map(|x| x + 1)
+            .unwrap_or(subaction.frames.len() + 1) as i32;
+        let mechanics = &brawl_mod.mechanics;
+        let shield_advantage = |frames: FrameRange, shieldstun: f32| {
+            let shieldstun = shieldstun as i32;
+            match subaction.landing_lag {
+                Some(landing_lag) => {
+                    let landing_frame = frames.start + 1;
+                    let auto_cancelled = auto_cancel
+                        .iter()
+                        .any(|x| x.start <= landing_frame && landing_frame <= x.end);
+                    let (landing_lag, l_cancel_lag) = match auto_cancel_lag {
+                        Some(lag) if auto_cancelled => (lag, mechanics.l_cancel.then_some(lag)),
+                        _ => (
+                            landing_lag as i32,
+                            mechanics.l_cancel_lag(landing_lag).map(|x| x as i32),
+                        ),
+                    };
+                    ShieldAdvantage::Aerial {
+                        landing: shieldstun - landing_lag,
+                        l_cancel: l_cancel_lag.map(|x| shieldstun - x),
+                    }
+                }
+                None => ShieldAdvantage::Grounded {
+                    first_frame: frames.start as i32 + shieldstun + 1 - first_actionable,
+                    last_frame: frames.end as i32 + shieldstun + 1 - first_actionable,
+                },
+            }
+        };
+
+        let mut hitbox_groups = vec![];
+        let mut throw = None;
+        let mut last_change_frame = None;
+        for i in 0..subaction.frames.len() {
+            let prev_frame = if i == 0 {
+                None
+            } else {
+                Some(&subaction.frames[i - 1])
+            };
+            let frame = &subaction.frames[i];
+
+            // get the values of the previous and next hitboxes
+            let prev_values = if let Some(prev_frame) = prev_frame {
+                prev_frame
+                    .hit_boxes
+                    .iter()
+                    .map(|x| &x.next_values)
+                    .collect()
+            } else {
+                vec![]
+            };
+            let next_values: Vec<_> = frame.hit_boxes.iter().map(|x| &x.next_values).collect();
+
+            if let Some(frame_throw) = &frame.throw {
+                throw = Some(Throw {
+                    frame: i,
+                    damage: frame_throw.damage,
+                    wdsk: frame_throw.wdsk,
+                    bkb: frame_throw.bkb,
+                    kbg: frame_throw.kbg,
+                    angle: frame_throw.trajectory,
+                    effect: frame_throw.effect.clone(),
+                    sound: frame_throw.sfx.clone(),
+                    grab_target: frame_throw.grab_target.clone(),
+                    i_frames: frame_throw.i_frames,
+                    weight_dependent_speed: frame_throw.weight_dependent_speed,
+                });
+            }
+
+            // start a new group when ((the hitbox values or number of hitboxes change) and there are hitboxes) or it is the last frame
+            // TODO: This comparison ignores hitbox_id, is this acceptable?
+            if prev_values != next_values || i + 1 == subaction.frames.len() {
+                if let Some(first_frame) = last_change_frame {
+                    last_change_frame = Some(i);
+                    let frames = FrameRange {
+                        start: first_frame + 1,
+                        end: i,
+                    };
+
+                    let mut hitboxes = vec![];
+                    for colbox in prev_frame.map(|x| &x.hit_boxes).unwrap_or(&frame.hit_boxes) {
+                        match &colbox.next_values {
+                            CollisionBoxValues::Hit(hit) => {
+                                if !hit.enabled {
+                                    continue;
+                                }
+                                let shieldstun = mechanics.shieldstun(hit.damage);
+                                hitboxes.push(HitBox::Hit(Hit {
+                                    set_id: hit.set_id,
+                                    hitbox_id: colbox.hitbox_id,
+                                    damage: hit.damage,
+                                    wdsk: hit.wdsk,
+                                    bkb: hit.bkb,
+                                    kbg: hit.kbg,
+                                    angle: hit.trajectory,
+                                    effect: hit.effect.clone(),
+                                    sound: hit.sound.clone(),
+                                    angle_flipping: hit.angle_flipping.clone(),
+                                    clang: hit.clang,
+                                    direct: hit.direct,
+                                    hitlag_mult: hit.hitlag_mult,
+                                    sdi_mult: hit.sdi_mult,
+                                    shield_damage: hit.shield_damage,
+                                    tripping_rate: hit.tripping_rate,
+                                    rehit_rate: hit.rehit_rate,
+                                    can_be_shielded: hit.can_be_shielded,
+                                    can_be_reflected: hit.can_be_reflected,
+                                    can_be_absorbed: hit.can_be_absorbed,
+                                    remain_grabbed: hit.remain_grabbed,
+                                    ignore_invincibility: hit.ignore_invincibility,
+                                    freeze_frame_disable: hit.freeze_frame_disable,
+                                    flinchless: hit.flinchless,
+                                    shieldstun,
+                                    hitlag: mechanics.hitlag(hit.damage, hit.hitlag_mult),
+                                    shield_advantage: hit
+                                        .can_be_shielded
+                                        .then(|| shield_advantage(frames, shieldstun)),
+                                    targets: HitTargets {
+                                        fighter_ground: hit.can_hit_fighter() && hit.ground,
+                                        fighter_air: hit.can_hit_fighter() && hit.aerial,
+                                        waddle_dee_doo: hit.can_hit_waddle_dee_doo(),
+                                        pikmin: hit.can_hit_pikmin(),
+                                        gyro: hit.can_hit_gyro(),
+                                        snake_grenade: hit.can_hit_snake_grenade(),
+                                        mr_saturn: hit.can_hit_mr_saturn(),
+                                        stage_non_wall_ceiling_floor: hit
+                                            .can_hit_stage_non_wall_ceiling_floor(),
+                                        wall_ceiling_floor: hit.can_hit_wall_ceiling_floor(),
+                                        link_bomb: hit.can_hit_link_bomb(),
+                                        bobomb: hit.can_hit_bobomb(),
+                                    },
+                                }))
+                            }
+                            CollisionBoxValues::Grab(grab) => hitboxes.push(HitBox::Grab(Grab {
+                                hitbox_id: colbox.hitbox_id,
+                                set_action: grab.set_action,
+                                target: grab.target.clone(),
+                            })),
+                        }
+                    }
+
+                    if !hitboxes.is_empty() {
+                        hitbox_groups.push(HitBoxGroup { frames, hitboxes });
+                    }
+                }
+            }
+
+            // set initial last_change_frame
+            if prev_values != next_values
+                && !frame.hit_boxes.is_empty()
+                && last_change_frame.is_none()
+            {
+                last_change_frame = Some(i);
+            }
+
+            // no more hitboxes, clear last_change_frame
+            if prev_values != next_values && frame.hit_boxes.is_empty() {
+                last_change_frame = None;
+            }
+        }
+
+        let frames = subaction.frames.len();
+        SubactionFrameData {
+            name: subaction.name.clone(),
+            index,
+            frames,
+            iasa: subaction.iasa.map(|x| x + 1),
+            auto_cancel,
+            auto_cancel_lag,
+            landing_lag: subaction.landing_lag,
+            landing_lag_l_cancel: subaction
+                .landing_lag
+                .and_then(|landing_lag| mechanics.l_cancel_lag(landing_lag)),
+            fully_invincible: fully_invincible.finish(frames),
+            fully_intangible: fully_intangible.finish(frames),
+            partially_invincible: partially_invincible.finish(frames),
+            partially_intangible: partially_intangible.finish(frames),
+            reverse_direction,
+            hitboxes_active: hitboxes_active.finish(frames),
+            hitbox_set_rehits,
+            hitbox_groups,
+            throw,
+        }
+    }
+}
+
+/// Builds a list of frame ranges over which some state is active.
+#[derive(Default)]
+struct RangeBuilder {
+    ranges: Vec<FrameRange>,
+    start: Option<usize>,
+}
+
+impl RangeBuilder {
+    fn frame(&mut self, i: usize, start: bool, end: bool) {
+        if start && self.start.is_none() {
+            self.start = Some(i);
+        }
+        if end {
+            if let Some(start) = self.start.take() {
+                self.ranges.push(FrameRange {
+                    start: start + 1,
+                    end: i,
+                });
+            }
+        }
+    }
+
+    /// handle states that were not turned off
+    fn finish(mut self, frames: usize) -> Vec<FrameRange> {
+        if let Some(start) = self.start.take() {
+            self.ranges.push(FrameRange {
+                start: start + 1,
+                end: frames,
+            });
+        }
+        self.ranges
+    }
+}
+
+pub fn ranges_string(ranges: &[FrameRange]) -> String {
+    ranges
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn frames_string(frames: &[usize]) -> String {
+    frames
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Writes the frame data of every subaction to `/{mod}/{fighter}/subactions/{name}.json`
+/// and a spreadsheet of every hitbox of the fighter to `/{mod}/{fighter}/frame_data.csv`
+pub fn generate(brawl_mods: &BrawlMods, site: &Site) {
+    for brawl_mod in &brawl_mods.mods {
+        brawl_mod.fighters.par_iter().for_each(|fighter| {
+            let fighter_name = &fighter.fighter.name;
+            let fighter_dir = OutDir::new(&brawl_mod.fighter_path(fighter_name));
+            if !site.wants(&fighter_dir) {
+                return;
+            }
+            let subactions_dir = OutDir::new(&format!(
+                "{}/subactions",
+                brawl_mod.fighter_path(fighter_name)
+            ));
+            // Only calculated when a file exporting it is stale.
+            let subactions = &fighter.fighter.subactions;
+            let frame_data: Vec<OnceLock<SubactionFrameData>> =
+                subactions.iter().map(|_| OnceLock::new()).collect();
+            let frame_data = |index: usize| {
+                frame_data[index].get_or_init(|| {
+                    SubactionFrameData::new(brawl_mod, fighter, index, &subactions[index])
+                })
+            };
+
+            for (index, subaction) in subactions.iter().enumerate() {
+                site.file(
+                    &subactions_dir,
+                    &format!("{}.json", subaction.name),
+                    &[&fighter.hash],
+                    || serde_json::to_vec(frame_data(index)).unwrap(),
+                );
+            }
+
+            site.file(&fighter_dir, "frame_data.csv", &[&fighter.hash], || {
+                let mut csv = String::new();
+                csv_row(&mut csv, CSV_HEADER.iter().map(|x| x.to_string()));
+                for index in 0..subactions.len() {
+                    csv_subaction(&mut csv, frame_data(index));
+                }
+                csv.into_bytes()
+            });
+            info!("{} {} frame data", brawl_mod.name, fighter_name);
+        });
+    }
+}
+
+const CSV_HEADER: &[&str] = &[
+    "Subaction",
+    "Index",
+    "Frames",
+    "IASA",
+    "Auto Cancel",
+    "Auto Cancel Lag",
+    "Landing Lag",
+    "Landing Lag (L-Cancel)",
+    "Fully Invincible",
+    "Fully Intangible",
+    "Partially Invincible",
+    "Partially Intangible",
+    "Hitboxes Active",
+    "Hitbox Frames",
+    "Type",
+    "Set",
+    "ID",
+    "Damage",
+    "WDSK",
+    "BKB",
+    "KBG",
+    "Angle",
+    "Effect",
+    "Angle Flip",
+    "Shieldstun",
+    "Hitlag",
+    "Shield Advantage",
+    "Shield Damage",
+    "Hitlag Mult",
+    "SDI Mult",
+    "Clang",
+    "Ground",
+    "Air",
+];
+
+/// One row per hitbox, subactions without hitboxes still get a single row.
+fn csv_subaction(csv: &mut String, data: &SubactionFrameData) {
+    let subaction = [
+        data.name.clone(),
+        data.index.to_string(),
+        data.frames.to_string(),
+        option_string(data.iasa),
+        ranges_string(&data.auto_cancel),
+        option_string(data.auto_cancel_lag),
+        option_string(data.landing_lag),
+        option_string(data.landing_lag_l_cancel),
+        ranges_string(&data.fully_invincible),
+        ranges_string(&data.fully_intangible),
+        ranges_string(&data.partially_invincible),
+        ranges_string(&data.partially_intangible),
+        ranges_string(&data.hitboxes_active),
+    ];
+
+    let mut has_rows = false;
+    for group in &data.hitbox_groups {
+        for hitbox in &group.hitboxes {
+            let hitbox = match hitbox {
+                HitBox::Hit(hit) => vec![
+                    group.frames.to_string(),
+                    "Hit".into(),
+                    hit.set_id.to_string(),
+                    hit.hitbox_id.to_string(),
+                    hit.damage.to_string(),
+                    hit.wdsk.to_string(),
+                    hit.bkb.to_string(),
+                    hit.kbg.to_string(),
+                    hit.angle.to_string(),
+                    format!("{:?}", hit.effect),
+                    format!("{:?}", hit.angle_flipping),
+                    hit.shieldstun.to_string(),
+                    hit.hitlag.to_string(),
+                    option_string(hit.shield_advantage.as_ref()),
+                    hit.shield_damage.to_string(),
+                    hit.hitlag_mult.to_string(),
+                    hit.sdi_mult.to_string(),
+                    hit.clang.to_string(),
+                    hit.targets.fighter_ground.to_string(),
+                    hit.targets.fighter_air.to_string(),
+                ],
+                HitBox::Grab(grab) => vec![
+                    group.frames.to_string(),
+                    "Grab".into(),
+                    String::new(),
+                    grab.hitbox_id.to_string(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    grab.target.grounded().to_string(),
+                    grab.target.aerial().to_string(),
+                ],
+            };
+            csv_row(csv, subaction.iter().cloned().chain(hitbox));
+            has_rows = true;
+        }
+    }
+
+    if let Some(throw) = &data.throw {
+        let throw = vec![
+            throw.frame.to_string(),
+            "Throw".into(),
+            String::new(),
+            String::new(),
+            throw.damage.to_string(),
+            throw.wdsk.to_string(),
+            throw.bkb.to_string(),
+            throw.kbg.to_string(),
+            throw.angle.to_string(),
+            format!("{:?}", throw.effect),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            throw.grab_target.grounded().to_string(),
+            throw.grab_target.aerial().to_string(),
+        ];
+        csv_row(csv, subaction.iter().cloned().chain(throw));
+        has_rows = true;
+    }
+
+    if !has_rows {
+        let empty = std::iter::repeat_n(String::new(), CSV_HEADER.len() - subaction.len());
+        csv_row(csv, subaction.iter().cloned().chain(empty));
+    }
+}
+
+fn csv_row(csv: &mut String, cells: impl Iterator<Item = String>) {
+    let cells: Vec<_> = cells
+        .map(|cell| {
+            if cell.contains([',', '"', '\n']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell
+            }
+        })
+        .collect();
+    assert_eq!(
+        cells.len(),
+        CSV_HEADER.len(),
+        "A csv row has a different number of cells to the header!"
+    );
+    csv.push_str(&cells.join(","));
+    csv.push_str("\r\n");
+}
+
+/// An empty string when there is no value, for cells that are left blank.
+pub fn option_string<T: ToString>(value: Option<T>) -> String {
+    value.map(|x| x.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The ranges of frames that are active, in the way `SubactionFrameData::new` steps through the frames.
+    fn ranges(active: &[bool]) -> Vec<FrameRange> {
+        let mut builder = RangeBuilder::default();
+        for (i, active) in active.iter().enumerate() {
+            builder.frame(i, *active, !*active);
+        }
+        builder.finish(active.len())
+    }
+
+    #[test]
+    fn range_merges_consecutive_frames() {
+        let ranges = ranges(&[false, true, true, true, false, true, true, false]);
+        assert_eq!(
+            ranges,
+            [
+                FrameRange { start: 2, end: 4 },
+                FrameRange { start: 6, end: 7 }
+            ]
+        );
+        assert_eq!(ranges_string(&ranges), "2-4, 6-7");
+    }
+
+    #[test]
+    fn range_single_frame() {
+        let ranges = ranges(&[false, false, true, false]);
+        assert_eq!(ranges, [FrameRange { start: 3, end: 3 }]);
+        assert_eq!(ranges_string(&ranges), "3");
+    }
+
+    #[test]
+    fn range_empty() {
+        assert_eq!(ranges(&[false, false, false]), []);
+        assert_eq!(ranges(&[]), []);
+        assert_eq!(ranges_string(&[]), "");
+    }
+
+    #[test]
+    fn range_active_until_the_end() {
+        assert_eq!(
+            ranges(&[true, true, false, true]),
+            [
+                FrameRange { start: 1, end: 2 },
+                FrameRange { start: 4, end: 4 }
+            ]
+        );
+    }
+
+    /// A csv row with the given cells followed by blank cells.
+    fn row(cells: &[&str]) -> String {
+        let blank = std::iter::repeat_n(String::new(), CSV_HEADER.len() - cells.len());
+        let mut csv = String::new();
+        csv_row(&mut csv, cells.iter().map(|x| x.to_string()).chain(blank));
+        csv
+    }
+
+    #[test]
+    fn csv_plain_fields() {
+        let csv = row(&["AttackS3S", "5"]);
+        assert!(csv.starts_with("AttackS3S,5,,"));
+        assert!(csv.ends_with(",\r\n"));
+        assert_eq!(csv.matches(',').count(), CSV_HEADER.len() - 1);
+    }
+
+    #[test]
+    fn csv_escapes_commas_and_quotes() {
+        let csv = row(&["2-4, 6-7", r#"the "sweetspot""#, "a\nb"]);
+        assert!(csv.starts_with("\"2-4, 6-7\",\"the \"\"sweetspot\"\"\",\"a\nb\",,"));
+    }
+
+    #[test]
+    fn csv_header() {
+        let mut csv = String::new();
+        csv_row(&mut csv, CSV_HEADER.iter().map(|x| x.to_string()));
+        assert_eq!(csv, format!("{}\r\n", CSV_HEADER.join(",")));
+    }
+}
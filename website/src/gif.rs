@@ -1,4 +1,5 @@
 use crate::brawl_data::BrawlMods;
+use crate::build_cache::BuildCache;
 use crate::output::OutDir;
 use brawllib_rs::renderer;
 use brawllib_rs::renderer::WgpuState;
@@ -17,19 +18,25 @@ impl GifWait {
     }
 }
 
-pub fn generate(brawl_mods: &BrawlMods) {
+pub fn generate(brawl_mods: &BrawlMods, cache: &BuildCache) {
     let mut state = futures::executor::block_on(WgpuState::new_for_gif());
     let mut gif_waits = vec![];
     for brawl_mod in &brawl_mods.mods {
         for fighter in &brawl_mod.fighters {
             let fighter_name = &fighter.fighter.name;
-            let dir = OutDir::new(&format!("{}/{}/subactions", brawl_mod.name, fighter_name));
-
+            let dir = OutDir::new(&format!(
+                "{}/subactions",
+                brawl_mod.fighter_path(fighter_name)
+            ));
             for (index, subaction) in fighter.fighter.subactions.iter().enumerate() {
-                if !subaction.frames.is_empty() {
+                let file_name = format!("{}.gif", subaction.name);
+                if !subaction.frames.is_empty()
+                    // render_gif is given the whole fighter, so any change to the fighter renders its gifs again.
+                    && cache.is_stale(&dir, &file_name, &format!("{}{index}", fighter.hash))
+                {
                     let rx = renderer::render_gif(&mut state, &fighter.fighter, index);
                     gif_waits.push(GifWait {
-                        file_name: format!("{}.gif", subaction.name),
+                        file_name,
                         rx,
                         dir: dir.clone(),
                     });
@@ -0,0 +1,56 @@
+//! The website side of the knockback calculator and kill percents, the formulas themselves are in `knockback_formulas.rs`.
+
+use crate::frame_data::{Hit, HitBoxGroup};
+use crate::knockback_formulas::{BlastZone, KnockbackHit, KnockbackTarget};
+use brawllib_rs::high_level_fighter::HighLevelFighter;
+
+impl KnockbackTarget {
+    pub fn new(fighter: &HighLevelFighter) -> Self {
+        KnockbackTarget {
+            name: fighter.name.clone(),
+            weight: fighter.attributes.weight,
+            gravity: fighter.attributes.gravity,
+            term_vel: fighter.attributes.term_vel,
+        }
+    }
+}
+
+impl KnockbackHit {
+    pub fn new(hit: &Hit) -> Self {
+        KnockbackHit {
+            damage: hit.damage,
+            wdsk: hit.wdsk,
+            bkb: hit.bkb,
+            kbg: hit.kbg,
+            angle: hit.angle,
+        }
+    }
+}
+
+/// Everything the knockback calculator on a subaction page needs, deserialized by `fighter_renderer`.
+#[derive(Serialize)]
+pub struct KnockbackCalculatorData<'a> {
+    pub targets: &'a [KnockbackTarget],
+    pub blast_zone: BlastZone,
+    pub hits: Vec<KnockbackCalculatorHit>,
+}
+
+#[derive(Serialize)]
+pub struct KnockbackCalculatorHit {
+    pub frames: String,
+    pub set_id: u8,
+    pub hitbox_id: u8,
+    #[serde(flatten)]
+    pub knockback: KnockbackHit,
+}
+
+impl KnockbackCalculatorHit {
+    pub fn new(group: &HitBoxGroup, hit: &Hit) -> Self {
+        KnockbackCalculatorHit {
+            frames: group.frames.to_string(),
+            set_id: hit.set_id,
+            hitbox_id: hit.hitbox_id,
+            knockback: KnockbackHit::new(hit),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Brawl's knockback formula and a rough simulation of the launch, used to estimate kill percents.
+//!
+//! `fighter_renderer` includes this file with `#[path]` to run the knockback calculator in the browser,
+//! so it can only use crates that both the website and `fighter_renderer` depend on.
+//!
+//! The launch simulation ignores DI, SDI, stage collision and the attacker's momentum,
+//! so kill percents are only an estimate of when the target is launched past the blast zone from the centre of the stage.
+
+use serde::{Deserialize, Serialize};
+
+/// Launch speed decays by this amount every frame.
+const LAUNCH_SPEED_DECAY: f32 = 0.051;
+
+/// Distance from the centre of the stage to the blast zones.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct BlastZone {
+    pub horizontal: f32,
+    pub vertical: f32,
+}
+
+impl Default for BlastZone {
+    fn default() -> Self {
+        BlastZone {
+            horizontal: 200.0,
+            vertical: 180.0,
+        }
+    }
+}
+
+/// The attributes of the fighter being hit that affect knockback.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KnockbackTarget {
+    pub name: String,
+    pub weight: f32,
+    pub gravity: f32,
+    pub term_vel: f32,
+}
+
+/// The values of a hit that affect knockback.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct KnockbackHit {
+    pub damage: f32,
+    pub wdsk: i16,
+    pub bkb: i16,
+    pub kbg: i16,
+    pub angle: i32,
+}
+
+/// `percent` is the target's percent before the hit.
+pub fn knockback(hit: &KnockbackHit, percent: f32, weight: f32) -> f32 {
+    // Weight dependent set knockback ignores the target's percent and the damage of the hit.
+    let (percent, damage) = if hit.wdsk != 0 {
+        (10.0, hit.wdsk as f32)
+    } else {
+        (percent + hit.damage, hit.damage)
+    };
+    ((percent / 10.0 + percent * damage / 20.0) * (200.0 / (weight + 100.0)) * 1.4 + 18.0)
+        * (hit.kbg as f32 / 100.0)
+        + hit.bkb as f32
+}
+
+pub fn hitstun(knockback: f32) -> u32 {
+    (knockback * 0.4).floor() as u32
+}
+
+/// The speed the target is launched at on the first frame, before it decays.
+pub fn launch_speed(knockback: f32) -> f32 {
+    knockback * 0.03
+}
+
+/// The angle in degrees the target is launched at, handling the Sakurai angle (361).
+pub fn launch_angle(angle: i32, knockback: f32, aerial: bool) -> f32 {
+    if angle == 361 {
+        if aerial {
+            45.0
+        } else if knockback < 32.0 {
+            0.0
+        } else {
+            44.0
+        }
+    } else {
+        angle as f32
+    }
+}
+
+/// Returns true if the launch carries the target past a blast zone before hitstun ends and the launch speed has decayed.
+pub fn launch_kills(
+    knockback: f32,
+    angle: f32,
+    target: &KnockbackTarget,
+    blast_zone: &BlastZone,
+) -> bool {
+    let hitstun = hitstun(knockback);
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let mut launch_speed = launch_speed(knockback);
+    let mut fall_speed = 0.0;
+    let mut x = 0.0;
+    let mut y = 0.0;
+    let mut frame = 0;
+    while launch_speed > 0.0 || frame < hitstun {
+        x += launch_speed * cos;
+        y += launch_speed * sin + fall_speed;
+        if x.abs() > blast_zone.horizontal || y.abs() > blast_zone.vertical {
+            return true;
+        }
+        launch_speed = (launch_speed - LAUNCH_SPEED_DECAY).max(0.0);
+        fall_speed = (fall_speed - target.gravity).max(-target.term_vel);
+        frame += 1;
+    }
+    false
+}
+
+/// The lowest percent, before the hit, at which the hit kills the target.
+/// None if it doesn't kill below 999%.
+pub fn kill_percent(
+    hit: &KnockbackHit,
+    target: &KnockbackTarget,
+    aerial: bool,
+    blast_zone: &BlastZone,
+) -> Option<u32> {
+    let kills = |percent: u32| {
+        let knockback = knockback(hit, percent as f32, target.weight);
+        let angle = launch_angle(hit.angle, knockback, aerial);
+        launch_kills(knockback, angle, target, blast_zone)
+    };
+
+    // Knockback only increases with percent, so binary search for the first percent that kills.
+    if !kills(999) {
+        return None;
+    }
+    let mut low = 0;
+    let mut high = 999;
+    while low < high {
+        let mid = (low + high) / 2;
+        if kills(mid) {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    Some(low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 0.001,
+            "expected {expected} but was {actual}"
+        );
+    }
+
+    /// 12% damage, 30 base knockback and 100 knockback growth.
+    const HIT: KnockbackHit = KnockbackHit {
+        damage: 12.0,
+        wdsk: 0,
+        bkb: 30,
+        kbg: 100,
+        angle: 361,
+    };
+
+    #[test]
+    fn knockback_at_percent() {
+        // ((62 / 10 + 62 * 12 / 20) * (200 / 200) * 1.4 + 18) * 1 + 30
+        let knockback = knockback(&HIT, 50.0, 100.0);
+        assert_close(knockback, 108.76);
+        assert_eq!(hitstun(knockback), 43);
+        assert_close(launch_speed(knockback), 3.2628);
+    }
+
+    #[test]
+    fn knockback_weight() {
+        // ((62 / 10 + 62 * 12 / 20) * (200 / 250) * 1.4 + 18) * 1 + 30
+        let knockback = knockback(&HIT, 50.0, 150.0);
+        assert_close(knockback, 96.608);
+        assert_eq!(hitstun(knockback), 38);
+        assert_close(launch_speed(knockback), 2.89824);
+    }
+
+    #[test]
+    fn knockback_weight_dependent_set() {
+        let hit = KnockbackHit {
+            damage: 3.0,
+            wdsk: 50,
+            bkb: 10,
+            kbg: 50,
+            angle: 90,
+        };
+        // ((10 / 10 + 10 * 50 / 20) * (200 / 200) * 1.4 + 18) * 0.5 + 10, whatever the percent.
+        assert_close(knockback(&hit, 0.0, 100.0), 37.2);
+        assert_close(knockback(&hit, 150.0, 100.0), 37.2);
+        assert_eq!(hitstun(37.2), 14);
+    }
+
+    #[test]
+    fn sakurai_angle() {
+        assert_eq!(launch_angle(361, 31.9, false), 0.0);
+        assert_eq!(launch_angle(361, 32.0, false), 44.0);
+        assert_eq!(launch_angle(361, 10.0, true), 45.0);
+        assert_eq!(launch_angle(80, 10.0, false), 80.0);
+    }
+
+    #[test]
+    fn kill_percent_increases_with_weight() {
+        let target = |weight| KnockbackTarget {
+            name: String::new(),
+            weight,
+            gravity: 0.09,
+            term_vel: 1.6,
+        };
+        let blast_zone = BlastZone::default();
+        let light = kill_percent(&HIT, &target(80.0), false, &blast_zone).unwrap();
+        let heavy = kill_percent(&HIT, &target(120.0), false, &blast_zone).unwrap();
+        assert!(light < heavy, "{light} should be lower than {heavy}");
+
+        let weak = KnockbackHit { kbg: 0, ..HIT };
+        assert_eq!(
+            kill_percent(&weak, &target(100.0), false, &blast_zone),
+            None
+        );
+    }
+}
@@ -0,0 +1,353 @@
+//! `--check-links` walks every html file in the output root and reports internal `href` and `src` attributes that don't resolve to a file.
+//! The report also includes the script lookups that failed while generating pages, which are rendered as unlinked text.
+//!
+//! The report of the previous check is kept in `build_cache/links.json`.
+//! Problems that aren't in the previous report are regressions and fail the run, leaving the previous report in place.
+//! Otherwise the report replaces the previous report, so fixed problems can't come back unnoticed.
+//! Delete `build_cache/links.json` to accept the current problems.
+
+use crate::brawl_data::{BrawlFighter, BrawlMod};
+use crate::cli::Args;
+use crate::output;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+static LOOKUP_FAILURES: Mutex<BTreeSet<Problem>> = Mutex::new(BTreeSet::new());
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Problem {
+    /// The mod and fighter the problem is in e.g. `P+/marth`, just the mod for pages that aren't about a single fighter.
+    group: String,
+    description: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Report {
+    broken_links: BTreeSet<Problem>,
+    lookup_failures: BTreeSet<Problem>,
+}
+
+/// Logs the failure and includes it in the `--check-links` report.
+pub fn lookup_failed(brawl_mod: &BrawlMod, fighter: &BrawlFighter, message: String) {
+    error!("{message} in {} {}", brawl_mod.name, fighter.fighter.name);
+    LOOKUP_FAILURES.lock().unwrap().insert(Problem {
+        group: brawl_mod.fighter_path(&fighter.fighter.name),
+        description: message,
+    });
+}
+
+/// Prints the report, returning false if there are any regressions.
+pub fn check(args: &Args) -> bool {
+    let root = Path::new("../root");
+    let mut pages = vec![];
+    find_pages(root, &mut pages);
+    let broken_links = pages
+        .par_iter()
+        .flat_map_iter(|page| check_page(root, page))
+        .collect();
+
+    let report_path = Path::new("../build_cache/links.json");
+    let previous: Option<Report> = fs::read(report_path)
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok());
+
+    // Lookup failures are only found while generating pages, otherwise assume they are unchanged.
+    let generated = args.generate_web;
+    let lookup_failures = if generated {
+        LOOKUP_FAILURES.lock().unwrap().clone()
+    } else {
+        previous
+            .as_ref()
+            .map(|x| x.lookup_failures.clone())
+            .unwrap_or_default()
+    };
+    let report = Report {
+        broken_links,
+        lookup_failures,
+    };
+
+    let mut groups: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    let mut regressions = 0;
+    for (problems, previous_problems, kind) in [
+        (
+            &report.broken_links,
+            previous.as_ref().map(|x| &x.broken_links),
+            "broken link",
+        ),
+        (
+            &report.lookup_failures,
+            previous.as_ref().map(|x| &x.lookup_failures),
+            "lookup failure",
+        ),
+    ] {
+        for problem in problems {
+            let new = previous_problems.is_some_and(|x| !x.contains(problem));
+            if new {
+                regressions += 1;
+            }
+            groups.entry(&problem.group).or_default().push(format!(
+                "    {}{kind}: {}",
+                if new { "NEW " } else { "" },
+                problem.description
+            ));
+        }
+    }
+
+    for (group, lines) in &groups {
+        println!("{group}");
+        for line in lines {
+            println!("{line}");
+        }
+    }
+    println!(
+        "Checked {} pages: {} broken links, {} lookup failures, {} new",
+        pages.len(),
+        report.broken_links.len(),
+        report.lookup_failures.len(),
+        regressions
+    );
+
+    if regressions > 0 {
+        return false;
+    }
+    // A filtered run only finds the lookup failures of some fighters, which would look like the rest were fixed.
+    let filtered = !args.mod_names.is_empty() || !args.fighter_names.is_empty();
+    if !(generated && filtered) {
+        fs::create_dir_all(report_path.parent().unwrap()).unwrap();
+        fs::write(report_path, serde_json::to_vec(&report).unwrap()).unwrap();
+    }
+    true
+}
+
+fn find_pages(dir: &Path, pages: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            find_pages(&path, pages);
+        } else if path.extension().is_some_and(|x| x == "html") {
+            pages.push(path);
+        }
+    }
+}
+
+fn check_page(root: &Path, page: &Path) -> Vec<Problem> {
+    let relative = page.strip_prefix(root).unwrap();
+    let url = format!("/{}", relative.to_str().unwrap());
+    let html = match fs::read(page).and_then(|x| output::encodings()[0].decode(&x)) {
+        Ok(html) => String::from_utf8_lossy(&html).into_owned(),
+        Err(err) => {
+            return vec![Problem {
+                group: group(relative),
+                description: format!("{url} could not be read: {err}"),
+            }];
+        }
+    };
+
+    let mut links = BTreeSet::new();
+    for attribute in ["href=", "src="] {
+        for (index, _) in html.match_indices(attribute) {
+            // Skip e.g. `data-src=`
+            if !html[..index].ends_with(|c: char| c.is_ascii_whitespace()) {
+                continue;
+            }
+            if let Some(link) = attribute_value(&html[index + attribute.len()..]) {
+                links.insert(link);
+            }
+        }
+    }
+
+    links
+        .into_iter()
+        .filter(|link| !resolves(root, relative, link))
+        .map(|link| Problem {
+            group: group(relative),
+            description: format!("{url} links to {link}"),
+        })
+        .collect()
+}
+
+/// The quoted or unquoted value at the start of `html` with html escapes undone.
+fn attribute_value(html: &str) -> Option<String> {
+    let value = match html.chars().next()? {
+        quote @ ('"' | '\'') => html[1..].split(quote).next()?,
+        _ => html
+            .split(|c: char| c.is_ascii_whitespace() || c == '>')
+            .next()?,
+    };
+    Some(
+        value
+            .replace("&quot;", "\"")
+            .replace("&#x27;", "'")
+            .replace("&#x60;", "`")
+            .replace("&#x3D;", "=")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&"),
+    )
+}
+
+/// Returns true if the link is external or points to a file in the output root.
+fn resolves(root: &Path, page: &Path, link: &str) -> bool {
+    let external = ["http:", "https:", "//", "mailto:", "data:", "javascript:"];
+    if external.iter().any(|x| link.starts_with(x)) {
+        return true;
+    }
+    // Fragments and queries aren't checked, only the file they are part of.
+    let path = link.split(['#', '?']).next().unwrap();
+    if path.is_empty() {
+        return true;
+    }
+    let Ok(path) = percent_encoding::percent_decode_str(path).decode_utf8() else {
+        return false;
+    };
+
+    let mut target = if path.starts_with('/') {
+        PathBuf::new()
+    } else {
+        page.parent().unwrap().to_path_buf()
+    };
+    for component in Path::new(path.as_ref()).components() {
+        match component {
+            Component::Normal(x) => target.push(x),
+            Component::ParentDir => {
+                if !target.pop() {
+                    return false;
+                }
+            }
+            Component::RootDir | Component::CurDir | Component::Prefix(_) => {}
+        }
+    }
+    let target = root.join(target);
+    target.is_file() || target.join("index.html").is_file()
+}
+
+/// The mod and fighter directories the page is in, e.g. `P+/marth` or `compare/PM3.6/P+/marth`
+fn group(page: &Path) -> String {
+    let dirs: Vec<_> = page
+        .parent()
+        .unwrap()
+        .components()
+        .map(|x| x.as_os_str().to_string_lossy())
+        .collect();
+    let len = if dirs.first().is_some_and(|x| x == "compare") {
+        4
+    } else {
+        2
+    };
+    let group = dirs[..dirs.len().min(len)].join("/");
+    if group.is_empty() {
+        String::from("/")
+    } else {
+        group
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An output root containing `/P+/marth/index.html`, `/P+/marth/subactions/index.html` and `/P+/marth/frame_data.csv`
+    fn root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("link_check_{}_{name}", std::process::id()));
+        fs::create_dir_all(root.join("P+/marth/subactions")).unwrap();
+        fs::write(root.join("P+/marth/index.html"), "").unwrap();
+        fs::write(root.join("P+/marth/subactions/index.html"), "").unwrap();
+        fs::write(root.join("P+/marth/frame_data.csv"), "").unwrap();
+        root
+    }
+
+    #[test]
+    fn resolves_relative() {
+        let root = root("relative");
+        let page = Path::new("P+/marth/index.html");
+        assert!(resolves(&root, page, "frame_data.csv"));
+        assert!(resolves(&root, page, "./frame_data.csv"));
+        assert!(!resolves(&root, page, "frame_data.json"));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn resolves_parent() {
+        let root = root("parent");
+        let page = Path::new("P+/marth/subactions/index.html");
+        assert!(resolves(&root, page, "../frame_data.csv"));
+        assert!(resolves(&root, page, "../../marth/"));
+        assert!(!resolves(&root, page, "../../../../P+/marth/"));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn resolves_fragment_and_query() {
+        let root = root("fragment");
+        let page = Path::new("P+/marth/index.html");
+        assert!(resolves(&root, page, "#hitboxes"));
+        assert!(resolves(&root, page, "?frame=3"));
+        assert!(resolves(&root, page, "/P+/marth/#hitboxes"));
+        assert!(resolves(&root, page, "subactions/?frame=3#hitboxes"));
+        assert!(!resolves(&root, page, "/P+/roy/#hitboxes"));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn resolves_directory_index() {
+        let root = root("directory");
+        let page = Path::new("P+/marth/index.html");
+        assert!(resolves(&root, page, "/P+/marth/"));
+        assert!(resolves(&root, page, "/P+/marth"));
+        assert!(resolves(&root, page, "subactions/"));
+        assert!(!resolves(&root, page, "/P+/"));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn resolves_external() {
+        let page = Path::new("P+/marth/index.html");
+        let root = Path::new("does_not_exist");
+        assert!(resolves(root, page, "https://github.com/rukai/brawllib_rs"));
+        assert!(resolves(root, page, "//example.com/"));
+        assert!(!resolves(root, page, "/P+/marth/"));
+    }
+
+    #[test]
+    fn attribute_value_double_quotes() {
+        assert_eq!(
+            attribute_value(r#""/P+/marth/" class="nav">"#).as_deref(),
+            Some("/P+/marth/")
+        );
+        assert_eq!(
+            attribute_value(r#""/a?b=1&amp;c=2">"#).as_deref(),
+            Some("/a?b=1&c=2")
+        );
+    }
+
+    #[test]
+    fn attribute_value_single_quotes() {
+        assert_eq!(
+            attribute_value(r#"'/P+/marth/' class='nav'>"#).as_deref(),
+            Some("/P+/marth/")
+        );
+        assert_eq!(
+            attribute_value(r#"'say "hi"'>"#).as_deref(),
+            Some(r#"say "hi""#)
+        );
+    }
+
+    #[test]
+    fn attribute_value_unquoted() {
+        assert_eq!(
+            attribute_value("/P+/marth/ class=nav>").as_deref(),
+            Some("/P+/marth/")
+        );
+        assert_eq!(
+            attribute_value("/P+/marth/>").as_deref(),
+            Some("/P+/marth/")
+        );
+        assert_eq!(attribute_value(""), None);
+    }
+}
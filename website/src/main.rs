@@ -10,56 +10,105 @@ extern crate log;
 use config::Config;
 use handlebars::Handlebars;
 
+pub mod action_graph;
+mod api;
 pub mod assets;
 pub mod brawl_data;
+pub mod build_cache;
 pub mod cli;
 pub mod config;
+mod deploy;
+mod dev_server;
+pub mod event_definitions;
+pub mod frame_data;
 pub mod gif;
+pub mod knockback;
+pub mod knockback_formulas;
+pub mod link_check;
 pub mod logger;
+pub mod mechanics;
 pub mod output;
 pub mod page;
 pub mod process_scripts;
+pub mod redirects;
+pub mod script_callers;
+pub mod script_walker;
+pub mod search;
 mod serve;
+pub mod site;
+pub mod sitemap;
+pub mod subaction_category;
+pub mod variable_key;
+pub mod variable_names;
+
+// fighter_renderer only builds for wasm, so its script stepper is tested with the website.
+#[cfg(test)]
+#[allow(dead_code)]
+#[path = "../../fighter_renderer/src/script_stepper.rs"]
+mod script_stepper;
 
 use assets::AssetPaths;
 use brawl_data::BrawlMods;
+use build_cache::BuildCache;
+use site::Site;
 
 fn main() {
     logger::init();
     let args = cli::args();
     let config = Config::load().unwrap();
+    output::init(&config.output_encodings);
+
+    // Deploys what is already in the output root, so there is no need to load any mods.
+    if let Some(cli::Command::Deploy(deploy_args)) = &args.command {
+        if let Err(err) = deploy::deploy(deploy_args, &config.output_encodings) {
+            error!("Failed to deploy: {err:?}");
+            std::process::exit(1);
+        }
+        return;
+    }
 
     if let Some(brawl_mods) = BrawlMods::new(&config, &args) {
         info!("brawl files loaded");
 
-        if args.generate_web {
+        if args.generate_web || !args.changelog.is_empty() {
             let mut handlebars = Handlebars::new();
             handlebars
                 .register_templates_directory(".html.hbs", "templates")
                 .unwrap();
             info!("handlebars templates loaded");
 
-            let assets = AssetPaths::new(&config);
-            page::index::generate(&handlebars, &brawl_mods, &assets);
-            page::error::generate(&handlebars, &brawl_mods, &assets);
-            page::brawl_mod::generate(&handlebars, &brawl_mods, &assets);
-            page::fighter::generate(&handlebars, &brawl_mods, &assets);
-            page::attributes::generate(&handlebars, &brawl_mods, &assets);
-            page::actions::generate(&handlebars, &brawl_mods, &assets);
-            page::action::generate(&handlebars, &brawl_mods, &assets);
-            page::subactions::generate(&handlebars, &brawl_mods, &assets);
-            page::subaction::generate(&handlebars, &brawl_mods, &assets, config.legacy_renderer);
-            page::script::generate(&handlebars, &brawl_mods, &assets);
-            page::scripts::generate(&handlebars, &brawl_mods, &assets);
-            page::variables::generate(&handlebars, &brawl_mods, &assets);
+            let site = Site::new(BuildCache::load("pages", &args));
+            let assets = AssetPaths::new(&config, &site);
+            if args.generate_web {
+                page::generate_all(&handlebars, &brawl_mods, &assets, &site, &config);
+                // Only saved when generating the website, otherwise every page would be pruned.
+                site.save();
+            }
+
+            match args.changelog.as_slice() {
+                [] => {}
+                [old, new] => {
+                    page::changelog::generate(&handlebars, &brawl_mods, &assets, old, new)
+                }
+                _ => error!("--changelog takes exactly two mods e.g. --changelog P+2.4,P+3.0"),
+            }
         }
 
         if args.generate_gifs {
-            gif::generate(&brawl_mods);
+            let cache = BuildCache::load("gifs", &args);
+            gif::generate(&brawl_mods, &cache);
+            cache.save();
+        }
+
+        if args.check_links && !link_check::check(&args) {
+            error!("--check-links found new broken links or lookup failures");
+            std::process::exit(1);
         }
 
-        if args.serve {
-            serve::serve();
+        if args.dev {
+            dev_server::serve(config, brawl_mods);
+        } else if args.serve {
+            serve::serve(&config.output_encodings, brawl_mods);
         }
     }
 }
@@ -0,0 +1,128 @@
+//! Game mechanics constants that differ between mods, used to derive frame data such as hitlag and shieldstun.
+
+/// The constants used to derive frame data for a mod.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Mechanics {
+    /// Hitlag is `floor((damage * hitlag_mult + 3) * hitbox_hitlag_mult)`
+    pub hitlag_mult: f32,
+    /// Shieldstun is `floor((damage + shieldstun_damage_offset) * shieldstun_mult)`
+    pub shieldstun_damage_offset: f32,
+    pub shieldstun_mult: f32,
+    /// Auto cancel lag is the fighter's light landing lag plus this amount.
+    pub auto_cancel_extra_lag: i32,
+    /// Whether aerials can be L-cancelled at all.
+    pub l_cancel: bool,
+    /// L-cancelled landing lag is `floor(landing_lag / l_cancel_divisor)`
+    pub l_cancel_divisor: f32,
+}
+
+/// The built in mechanics of each supported mod.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum MechanicsPreset {
+    Brawl,
+    PM,
+    #[serde(rename = "P+")]
+    PPlus,
+    LXP,
+}
+
+impl MechanicsPreset {
+    pub fn mechanics(self) -> Mechanics {
+        match self {
+            MechanicsPreset::Brawl => Mechanics {
+                hitlag_mult: 0.3865,
+                shieldstun_damage_offset: 4.45,
+                shieldstun_mult: 0.447,
+                auto_cancel_extra_lag: 1,
+                l_cancel: false,
+                l_cancel_divisor: 1.0,
+            },
+            // LXP is built on PM 3.02 and recent versions of P+ kept PM's hitlag and L-cancelling.
+            MechanicsPreset::PM | MechanicsPreset::PPlus | MechanicsPreset::LXP => Mechanics {
+                hitlag_mult: 0.33333,
+                shieldstun_damage_offset: 4.45,
+                shieldstun_mult: 0.447,
+                auto_cancel_extra_lag: 1,
+                l_cancel: true,
+                l_cancel_divisor: 2.0,
+            },
+        }
+    }
+
+    /// Guesses the preset from the name of the mod's directory in `data`.
+    /// Unknown mods are assumed to be PM based.
+    pub fn from_mod_name(mod_name: &str) -> Self {
+        let lower = mod_name.to_lowercase();
+        if lower == "brawl" {
+            MechanicsPreset::Brawl
+        } else if lower.contains("lxp") {
+            MechanicsPreset::LXP
+        } else if lower.contains("p+") || lower.contains("pplus") || lower.contains("projectplus") {
+            MechanicsPreset::PPlus
+        } else {
+            MechanicsPreset::PM
+        }
+    }
+}
+
+/// The `mechanics` of a mod entry in `config.json`.
+/// Starts from `preset` and replaces any constant that is specified.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct MechanicsConfig {
+    /// Defaults to guessing the preset from the mod name.
+    #[serde(default)]
+    pub preset: Option<MechanicsPreset>,
+    #[serde(default)]
+    pub hitlag_mult: Option<f32>,
+    #[serde(default)]
+    pub shieldstun_damage_offset: Option<f32>,
+    #[serde(default)]
+    pub shieldstun_mult: Option<f32>,
+    #[serde(default)]
+    pub auto_cancel_extra_lag: Option<i32>,
+    #[serde(default)]
+    pub l_cancel: Option<bool>,
+    #[serde(default)]
+    pub l_cancel_divisor: Option<f32>,
+}
+
+impl MechanicsConfig {
+    pub fn resolve(&self, mod_name: &str) -> Mechanics {
+        let preset = self
+            .preset
+            .unwrap_or_else(|| MechanicsPreset::from_mod_name(mod_name))
+            .mechanics();
+        Mechanics {
+            hitlag_mult: self.hitlag_mult.unwrap_or(preset.hitlag_mult),
+            shieldstun_damage_offset: self
+                .shieldstun_damage_offset
+                .unwrap_or(preset.shieldstun_damage_offset),
+            shieldstun_mult: self.shieldstun_mult.unwrap_or(preset.shieldstun_mult),
+            auto_cancel_extra_lag: self
+                .auto_cancel_extra_lag
+                .unwrap_or(preset.auto_cancel_extra_lag),
+            l_cancel: self.l_cancel.unwrap_or(preset.l_cancel),
+            l_cancel_divisor: self.l_cancel_divisor.unwrap_or(preset.l_cancel_divisor),
+        }
+    }
+}
+
+impl Mechanics {
+    pub fn hitlag(&self, damage: f32, hitbox_hitlag_mult: f32) -> f32 {
+        ((damage * self.hitlag_mult + 3.0) * hitbox_hitlag_mult).floor()
+    }
+
+    pub fn shieldstun(&self, damage: f32) -> f32 {
+        ((damage + self.shieldstun_damage_offset) * self.shieldstun_mult).floor()
+    }
+
+    pub fn auto_cancel_lag(&self, light_landing_lag: f32) -> i32 {
+        light_landing_lag as i32 + self.auto_cancel_extra_lag
+    }
+
+    /// None when the mod doesn't have L-cancelling.
+    pub fn l_cancel_lag(&self, landing_lag: f32) -> Option<u32> {
+        self.l_cancel
+            .then(|| (landing_lag / self.l_cancel_divisor) as u32)
+    }
+}
@@ -1,9 +1,118 @@
+use anyhow::{Result, bail};
 use flate2::Compression;
+use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use std::fs;
-use std::fs::File;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static ENCODINGS: OnceLock<Vec<Encoding>> = OnceLock::new();
+
+/// How files in the output root are encoded, named after their `content-encoding`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    Identity,
+    Gzip,
+    Br,
+    Zstd,
+}
+
+impl Encoding {
+    /// The value of the `content-encoding` header, None for uncompressed files.
+    pub fn content_encoding(self) -> Option<&'static str> {
+        match self {
+            Encoding::Identity => None,
+            Encoding::Gzip => Some("gzip"),
+            Encoding::Br => Some("br"),
+            Encoding::Zstd => Some("zstd"),
+        }
+    }
+
+    /// The name used in `accept-encoding` headers.
+    pub fn name(self) -> &'static str {
+        self.content_encoding().unwrap_or("identity")
+    }
+
+    /// Appended to the file name when the encoding is not the primary encoding.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Encoding::Identity => "",
+            Encoding::Gzip => ".gz",
+            Encoding::Br => ".br",
+            Encoding::Zstd => ".zst",
+        }
+    }
+
+    pub fn encode(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Encoding::Identity => data.to_vec(),
+            Encoding::Gzip => {
+                let mut writer = GzEncoder::new(vec![], Compression::best());
+                writer.write_all(data).unwrap();
+                writer.finish().unwrap()
+            }
+            Encoding::Br => {
+                let mut out = vec![];
+                let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 11, 22);
+                writer.write_all(data).unwrap();
+                drop(writer);
+                out
+            }
+            Encoding::Zstd => zstd::encode_all(data, 19).unwrap(),
+        }
+    }
+
+    pub fn decode(self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut out = vec![];
+        match self {
+            Encoding::Identity => out.extend_from_slice(data),
+            Encoding::Gzip => {
+                GzDecoder::new(data).read_to_end(&mut out)?;
+            }
+            Encoding::Br => {
+                brotli::Decompressor::new(data, 4096).read_to_end(&mut out)?;
+            }
+            Encoding::Zstd => out = zstd::decode_all(data)?,
+        }
+        Ok(out)
+    }
+}
+
+/// Every file is written in gzip to the name it is served at, as required for hosting on AWS S3.
+pub fn default_encodings() -> Vec<Encoding> {
+    vec![Encoding::Gzip]
+}
+
+/// The first encoding is written to the file's own name and every other encoding is written alongside it with the encoding's extension.
+/// e.g. `["identity", "gzip", "br"]` writes `index.html`, `index.html.gz` and `index.html.br`
+pub fn validate_encodings(encodings: &[Encoding]) -> Result<()> {
+    if encodings.is_empty() {
+        bail!("output_encodings must contain at least one encoding");
+    }
+    for (i, encoding) in encodings.iter().enumerate() {
+        if encodings[..i].contains(encoding) {
+            bail!(
+                "output_encodings contains {:?} more than once",
+                encoding.name()
+            );
+        }
+        if i > 0 && *encoding == Encoding::Identity {
+            bail!("identity can only be the first of the output_encodings");
+        }
+    }
+    Ok(())
+}
+
+/// Sets the encodings used by every `OutDir`, must be called before any files are written.
+pub fn init(encodings: &[Encoding]) {
+    ENCODINGS.set(encodings.to_vec()).unwrap();
+}
+
+pub fn encodings() -> &'static [Encoding] {
+    ENCODINGS.get_or_init(default_encodings)
+}
 
 #[derive(Clone)]
 pub struct OutDir {
@@ -11,42 +120,94 @@ pub struct OutDir {
 }
 
 impl OutDir {
+    /// The directory is only created when a file is written to it, so that the dev server doesn't touch the output root.
     pub fn new(path: &str) -> Self {
         let path = Path::new("../root").join(path);
-        fs::create_dir_all(&path).unwrap();
         OutDir { path }
     }
 
     pub fn new_path(path: &Path) -> Self {
         let path = Path::new("../root").join(path);
-        fs::create_dir_all(&path).unwrap();
         OutDir { path }
     }
 
-    pub fn compressed_file_writer(&self, file_name: &str) -> GzEncoder<File> {
-        let file = File::create(self.path.join(file_name)).unwrap();
-        GzEncoder::new(file, Compression::best())
+    /// Writes the data in every configured output encoding.
+    pub fn create_compressed_file(&self, file_name: &str, data: &[u8]) -> String {
+        fs::create_dir_all(&self.path).unwrap();
+        for (i, encoding) in encodings().iter().enumerate() {
+            let extension = if i == 0 { "" } else { encoding.extension() };
+            let path = self.path.join(format!("{file_name}{extension}"));
+            fs::write(path, encoding.encode(data)).unwrap();
+        }
+        self.url(file_name)
     }
 
-    pub fn create_compressed_file(&self, file_name: &str, data: &[u8]) -> String {
-        let path = self.path.join(file_name);
-        let file = File::create(&path).unwrap();
-        let mut writer = GzEncoder::new(file, Compression::best());
-        writer.write_all(data).unwrap();
-        Path::new("/")
-            .join(path.strip_prefix("../root").unwrap())
-            .into_os_string()
-            .into_string()
+    pub fn create_file(&self, file_name: &str, data: &[u8]) -> String {
+        fs::create_dir_all(&self.path).unwrap();
+        std::fs::write(self.path.join(file_name), data).unwrap();
+        self.url(file_name)
+    }
+
+    /// The path of the file relative to the output root, this is used as the key in the build cache.
+    pub fn relative_path(&self, file_name: &str) -> PathBuf {
+        self.path
+            .join(file_name)
+            .strip_prefix("../root")
             .unwrap()
+            .to_path_buf()
     }
 
-    pub fn create_file(&self, file_name: &str, data: &[u8]) -> String {
-        let path = self.path.join(file_name);
-        std::fs::write(&path, data).unwrap();
+    /// The url pages link to, which for `index.html` is the url of the directory.
+    pub fn page_url(&self, file_name: &str) -> String {
+        let url = self.url(file_name);
+        match url.strip_suffix("index.html") {
+            Some(dir) => dir.to_owned(),
+            None => url,
+        }
+    }
+
+    /// The absolute url the file will be served at.
+    pub fn url(&self, file_name: &str) -> String {
         Path::new("/")
-            .join(path.strip_prefix("../root").unwrap())
+            .join(self.relative_path(file_name))
             .into_os_string()
             .into_string()
             .unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_encodings_valid() {
+        assert!(validate_encodings(&default_encodings()).is_ok());
+        assert!(
+            validate_encodings(&[
+                Encoding::Identity,
+                Encoding::Gzip,
+                Encoding::Br,
+                Encoding::Zstd
+            ])
+            .is_ok()
+        );
+        assert!(validate_encodings(&[Encoding::Br, Encoding::Gzip]).is_ok());
+    }
+
+    #[test]
+    fn validate_encodings_empty() {
+        assert!(validate_encodings(&[]).is_err());
+    }
+
+    #[test]
+    fn validate_encodings_duplicate() {
+        assert!(validate_encodings(&[Encoding::Gzip, Encoding::Gzip]).is_err());
+        assert!(validate_encodings(&[Encoding::Identity, Encoding::Identity]).is_err());
+    }
+
+    #[test]
+    fn validate_encodings_identity_not_first() {
+        assert!(validate_encodings(&[Encoding::Gzip, Encoding::Identity]).is_err());
+    }
+}
@@ -1,12 +1,16 @@
+use crate::action_graph::ActionGraph;
 use crate::assets::AssetPaths;
-use crate::brawl_data::BrawlMods;
+use crate::brawl_data::{BrawlMods, ScriptCallerLinks};
 use crate::output::OutDir;
 use crate::page::NavLink;
-use crate::process_scripts;
+use crate::process_scripts::{self, ScriptBlock};
+use crate::script_callers::ScriptKey;
+use crate::site::Site;
 use handlebars::Handlebars;
 use rayon::prelude::*;
+use std::sync::OnceLock;
 
-pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths) {
+pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths, site: &Site) {
     for brawl_mod in &brawl_mods.mods {
         let mod_links = brawl_mods.gen_mod_links(brawl_mod.name.clone());
 
@@ -16,58 +20,114 @@ pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetP
                 let other_name = &other_fighter.fighter.name;
                 fighter_links.push(NavLink {
                     name: other_name.clone(),
-                    link: format!("/{}/{}/actions", brawl_mod.name, other_fighter.fighter.name),
+                    link: format!(
+                        "/{}/actions",
+                        brawl_mod.fighter_path(&other_fighter.fighter.name)
+                    ),
                     current: other_name == &fighter.fighter.name,
                 });
             }
             let dir = OutDir::new(&format!(
-                "{}/{}/actions/",
-                brawl_mod.name, fighter.fighter.name
+                "{}/actions/",
+                brawl_mod.fighter_path(&fighter.fighter.name)
             ));
+            if !site.wants(&dir) {
+                continue;
+            }
+            // Only built when one of the fighter's action pages is stale.
+            let graph = OnceLock::new();
             fighter
                 .fighter
                 .actions
                 .par_iter()
                 .enumerate()
                 .for_each(|(index, action)| {
-                    let title = if action.name.starts_with("0x") {
-                        format!(
-                            "{} - {} - Action - {}",
-                            brawl_mod.name, fighter.fighter.name, action.name
-                        )
-                    } else {
-                        format!(
-                            "{} - {} - Action - 0x{:x} {}",
-                            brawl_mod.name, fighter.fighter.name, index, action.name
-                        )
-                    };
-                    let page = ActionPage {
-                        assets,
-                        title,
-                        mod_links: &mod_links,
-                        action_links: brawl_mod.gen_action_links(&fighter.fighter, &action.name),
-                        script_entry: process_scripts::process_events(
-                            &action.script_entry.block.events,
-                            action.script_entry_common,
-                            brawl_mod,
-                            fighter,
-                        ),
-                        script_exit: process_scripts::process_events(
-                            &action.script_exit.block.events,
-                            action.script_exit_common,
-                            brawl_mod,
-                            fighter,
-                        ),
-                        script_entry_common: action.script_entry_common,
-                        script_exit_common: action.script_exit_common,
-                        fighter_links: &fighter_links,
-                    };
-
-                    let file = dir.compressed_file_writer(&format!("{}.html", action.name));
-                    handlebars.render_to_write("action", &page, file).unwrap();
-                    info!(
-                        "{} {} action {}",
-                        brawl_mod.name, fighter.fighter.name, action.name
+                    let file_name = format!("{}.html", action.name);
+                    site.page(
+                        handlebars,
+                        "action",
+                        &dir,
+                        &file_name,
+                        &[&fighter.hash],
+                        || {
+                            let graph = graph.get_or_init(|| ActionGraph::new(brawl_mod, fighter));
+                            let title = if action.name.starts_with("0x") {
+                                format!(
+                                    "{} - {} - Action - {}",
+                                    brawl_mod.name, fighter.fighter.name, action.name
+                                )
+                            } else {
+                                format!(
+                                    "{} - {} - Action - 0x{:x} {}",
+                                    brawl_mod.name, fighter.fighter.name, index, action.name
+                                )
+                            };
+                            let transitions = graph
+                                .transitions(index)
+                                .into_iter()
+                                .map(|transition| {
+                                    let to = &graph.nodes[transition.edge.to];
+                                    TransitionRow {
+                                        to: GraphLink {
+                                            name: to.name.clone(),
+                                            link: to.link.clone(),
+                                        },
+                                        via: transition.via.map(|via| GraphLink {
+                                            name: via.name.clone(),
+                                            link: via.link.clone(),
+                                        }),
+                                        kind: transition.edge.kind,
+                                        requirement: transition.edge.requirement.clone(),
+                                    }
+                                })
+                                .collect();
+                            info!(
+                                "{} {} action {}",
+                                brawl_mod.name, fighter.fighter.name, action.name
+                            );
+                            ActionPage {
+                                assets,
+                                graph_svg: graph.render_neighbourhood_svg(index),
+                                graph_link: format!(
+                                    "/{}/actions/graph.html",
+                                    brawl_mod.fighter_path(&fighter.fighter.name)
+                                ),
+                                transitions,
+                                title,
+                                mod_links: &mod_links,
+                                action_links: brawl_mod
+                                    .gen_action_links(&fighter.fighter, &action.name),
+                                script_entry: process_scripts::process_events(
+                                    &action.script_entry.block.events,
+                                    action.script_entry_common,
+                                    brawl_mod,
+                                    fighter,
+                                ),
+                                script_exit: process_scripts::process_events(
+                                    &action.script_exit.block.events,
+                                    action.script_exit_common,
+                                    brawl_mod,
+                                    fighter,
+                                ),
+                                script_entry_common: action.script_entry_common,
+                                script_exit_common: action.script_exit_common,
+                                callers_entry: brawl_mod.gen_script_callers(
+                                    fighter,
+                                    &ScriptKey::new(
+                                        action.script_entry_common,
+                                        action.script_entry.offset,
+                                    ),
+                                ),
+                                callers_exit: brawl_mod.gen_script_callers(
+                                    fighter,
+                                    &ScriptKey::new(
+                                        action.script_exit_common,
+                                        action.script_exit.offset,
+                                    ),
+                                ),
+                                fighter_links: &fighter_links,
+                            }
+                        },
                     );
                 });
         }
@@ -81,8 +141,28 @@ pub struct ActionPage<'a> {
     fighter_links: &'a [NavLink],
     action_links: Vec<NavLink>,
     title: String,
-    script_entry: String,
-    script_exit: String,
+    script_entry: ScriptBlock,
+    script_exit: ScriptBlock,
     script_entry_common: bool,
     script_exit_common: bool,
+    callers_entry: ScriptCallerLinks,
+    callers_exit: ScriptCallerLinks,
+    graph_svg: String,
+    graph_link: String,
+    transitions: Vec<TransitionRow>,
+}
+
+#[derive(Serialize)]
+struct TransitionRow {
+    to: GraphLink,
+    via: Option<GraphLink>,
+    kind: &'static str,
+    requirement: String,
+}
+
+#[derive(Serialize)]
+struct GraphLink {
+    name: String,
+    /// Interrupt groups have no page to link to
+    link: Option<String>,
 }
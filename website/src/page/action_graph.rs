@@ -0,0 +1,72 @@
+use crate::action_graph::ActionGraph;
+use crate::assets::AssetPaths;
+use crate::brawl_data::BrawlMods;
+use crate::output::OutDir;
+use crate::page::NavLink;
+use crate::site::Site;
+use handlebars::Handlebars;
+use rayon::prelude::*;
+use std::sync::OnceLock;
+
+/// Generates `/{mod}/{fighter}/actions/graph.html` and the same graph in graphviz format at `graph.dot`.
+pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths, site: &Site) {
+    for brawl_mod in &brawl_mods.mods {
+        let mod_links = brawl_mods.gen_mod_links(brawl_mod.name.clone());
+        brawl_mod.fighters.par_iter().for_each(|brawl_fighter| {
+            let fighter = &brawl_fighter.fighter;
+            let dir = OutDir::new(&format!(
+                "{}/actions",
+                brawl_mod.fighter_path(&fighter.name)
+            ));
+            // Only built when the graph is stale.
+            let graph = OnceLock::new();
+            let graph = || graph.get_or_init(|| ActionGraph::new(brawl_mod, brawl_fighter));
+            let dot_link = site.file(&dir, "graph.dot", &[&brawl_fighter.hash], || {
+                graph().render_dot().into_bytes()
+            });
+
+            site.page(
+                handlebars,
+                "action_graph",
+                &dir,
+                "graph.html",
+                &[&brawl_fighter.hash],
+                || {
+                    let mut fighter_links = vec![];
+                    for other_fighter in &brawl_mod.fighters {
+                        let other_name = &other_fighter.fighter.name;
+                        fighter_links.push(NavLink {
+                            name: other_name.clone(),
+                            link: format!(
+                                "/{}/actions/graph.html",
+                                brawl_mod.fighter_path(&other_fighter.fighter.name)
+                            ),
+                            current: other_name == &fighter.name,
+                        });
+                    }
+
+                    ActionGraphPage {
+                        assets,
+                        mod_links: &mod_links,
+                        title: format!("{} - {} - Action Graph", brawl_mod.name, fighter.name),
+                        fighter_links,
+                        actions_link: format!("/{}/actions", brawl_mod.fighter_path(&fighter.name)),
+                        dot_link,
+                        svg: graph().render_svg(),
+                    }
+                },
+            );
+        });
+    }
+}
+
+#[derive(Serialize)]
+struct ActionGraphPage<'a> {
+    assets: &'a AssetPaths,
+    mod_links: &'a [NavLink],
+    title: String,
+    fighter_links: Vec<NavLink>,
+    actions_link: String,
+    dot_link: String,
+    svg: String,
+}
@@ -2,36 +2,47 @@ use crate::assets::AssetPaths;
 use crate::brawl_data::BrawlMods;
 use crate::output::OutDir;
 use crate::page::NavLink;
+use crate::site::Site;
 use handlebars::Handlebars;
 use rayon::prelude::*;
 
-pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths) {
+pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths, site: &Site) {
     for brawl_mod in &brawl_mods.mods {
         let mod_links = brawl_mods.gen_mod_links(brawl_mod.name.clone());
-        brawl_mod.fighters.par_iter().for_each(|fighter| {
-            let fighter = &fighter.fighter;
+        brawl_mod.fighters.par_iter().for_each(|brawl_fighter| {
+            let fighter = &brawl_fighter.fighter;
+            let dir = OutDir::new(&format!(
+                "{}/actions",
+                brawl_mod.fighter_path(&fighter.name)
+            ));
 
             let mut fighter_links = vec![];
             for other_fighter in &brawl_mod.fighters {
                 let other_name = &other_fighter.fighter.name;
                 fighter_links.push(NavLink {
                     name: other_name.clone(),
-                    link: format!("/{}/{}/actions", brawl_mod.name, other_fighter.fighter.name),
+                    link: format!(
+                        "/{}/actions",
+                        brawl_mod.fighter_path(&other_fighter.fighter.name)
+                    ),
                     current: other_name == &fighter.name,
                 });
             }
 
-            let page = ActionsPage {
-                mod_links: &mod_links,
-                title: format!("{} - {} - Actions", brawl_mod.name, fighter.name),
-                action_links: brawl_mod.gen_action_links(fighter, ""),
-                fighter_links,
-                assets,
-            };
-
-            let file = OutDir::new(&format!("{}/{}/actions", brawl_mod.name, fighter.name))
-                .compressed_file_writer("index.html");
-            handlebars.render_to_write("actions", &page, file).unwrap();
+            site.page(
+                handlebars,
+                "actions",
+                &dir,
+                "index.html",
+                &[&brawl_fighter.hash],
+                || ActionsPage {
+                    mod_links: &mod_links,
+                    title: format!("{} - {} - Actions", brawl_mod.name, fighter.name),
+                    action_links: brawl_mod.gen_action_links(fighter, ""),
+                    fighter_links,
+                    assets,
+                },
+            );
         });
     }
 }
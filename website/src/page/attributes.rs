@@ -2,33 +2,36 @@ use crate::assets::AssetPaths;
 use crate::brawl_data::BrawlMods;
 use crate::output::OutDir;
 use crate::page::NavLink;
+use crate::site::Site;
 use brawllib_rs::sakurai::fighter_data::FighterAttributes;
 use handlebars::Handlebars;
 use rayon::prelude::*;
 
-pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths) {
+pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths, site: &Site) {
     for brawl_mod in &brawl_mods.mods {
         let mod_links = brawl_mods.gen_mod_links(brawl_mod.name.clone());
-        brawl_mod.fighters.par_iter().for_each(|fighter| {
-            let fighter = &fighter.fighter;
-            let page = AttributesPage {
-                assets,
-                mod_links: &mod_links,
-                title: format!("{} - {} - Attributes", brawl_mod.name, fighter.name),
-                fighter_links: brawl_mod.gen_fighter_links(&fighter.name),
-                attributes: attributes_to_strings(&fighter.attributes),
-            };
-
-            let file = OutDir::new(&format!("{}/{}", brawl_mod.name, fighter.name))
-                .compressed_file_writer("attributes.html");
-            handlebars
-                .render_to_write("attributes", &page, file)
-                .unwrap();
+        brawl_mod.fighters.par_iter().for_each(|brawl_fighter| {
+            let fighter = &brawl_fighter.fighter;
+            let dir = OutDir::new(&brawl_mod.fighter_path(&fighter.name));
+            site.page(
+                handlebars,
+                "attributes",
+                &dir,
+                "attributes.html",
+                &[&brawl_fighter.hash],
+                || AttributesPage {
+                    assets,
+                    mod_links: &mod_links,
+                    title: format!("{} - {} - Attributes", brawl_mod.name, fighter.name),
+                    fighter_links: brawl_mod.gen_fighter_links(&fighter.name),
+                    attributes: attributes_to_strings(&fighter.attributes),
+                },
+            );
         });
     }
 }
 
-fn attributes_to_strings(attributes: &FighterAttributes) -> Vec<Attribute> {
+pub fn attributes_to_strings(attributes: &FighterAttributes) -> Vec<Attribute> {
     vec![
         Attribute {
             name: "walk init vel",
@@ -335,7 +338,7 @@ struct AttributesPage<'a> {
 }
 
 #[derive(Serialize)]
-struct Attribute {
-    name: &'static str,
-    value: String,
+pub struct Attribute {
+    pub name: &'static str,
+    pub value: String,
 }
@@ -2,28 +2,47 @@ use crate::assets::AssetPaths;
 use crate::brawl_data::BrawlMods;
 use crate::output::OutDir;
 use crate::page::NavLink;
+use crate::site::Site;
 use handlebars::Handlebars;
 
-pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths) {
+pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths, site: &Site) {
     for brawl_mod in &brawl_mods.mods {
         let mut fighter_links = vec![];
         for fighter in &brawl_mod.fighters {
             fighter_links.push(NavLink {
                 name: fighter.fighter.name.clone(),
-                link: format!("/{}/{}", brawl_mod.name, fighter.fighter.name),
+                link: format!("/{}", brawl_mod.fighter_path(&fighter.fighter.name)),
                 current: false,
             });
         }
 
-        let page = ModPage {
-            mod_links: brawl_mods.gen_mod_links(brawl_mod.name.clone()),
-            title: format!("{} Fighters", brawl_mod.name),
-            fighter_links,
-            assets,
-        };
+        let compare_links = brawl_mods
+            .mods
+            .iter()
+            .filter(|x| x.name != brawl_mod.name)
+            .map(|other| NavLink {
+                name: format!("Compare with {}", other.display_name),
+                link: format!("/compare/{}/{}/", other.name, brawl_mod.name),
+                current: false,
+            })
+            .collect();
 
-        let file = OutDir::new(&brawl_mod.name).compressed_file_writer("index.html");
-        handlebars.render_to_write("mod", &page, file).unwrap();
+        site.page(
+            handlebars,
+            "mod",
+            &OutDir::new(&brawl_mod.name),
+            "index.html",
+            &[],
+            || ModPage {
+                mod_links: brawl_mods.gen_mod_links(brawl_mod.name.clone()),
+                title: format!("{} Fighters", brawl_mod.display_name),
+                version: brawl_mod.version.clone(),
+                description: brawl_mod.description.clone(),
+                fighter_links,
+                compare_links,
+                assets,
+            },
+        );
     }
 }
 
@@ -32,5 +51,8 @@ struct ModPage<'a> {
     assets: &'a AssetPaths,
     mod_links: Vec<NavLink>,
     fighter_links: Vec<NavLink>,
+    compare_links: Vec<NavLink>,
     title: String,
+    version: Option<String>,
+    description: Option<String>,
 }
@@ -0,0 +1,225 @@
+//! Generates patch notes between two releases of a mod, e.g. `--changelog P+2.4,P+3.0`
+//!
+//! The changelog is written as html to `/changelog/{old_mod}/{new_mod}/index.html`
+//! and as markdown to both `/changelog/{old_mod}/{new_mod}/changelog.md` and `../changelog/{old_mod}-{new_mod}.md`.
+//! The latter is uncompressed so it can be pasted directly into release announcements.
+
+use crate::assets::AssetPaths;
+use crate::brawl_data::{BrawlMod, BrawlMods};
+use crate::output::OutDir;
+use crate::page::compare::{self, FighterComparison, HIT_VALUE_NAMES, Value};
+use crate::page::{NavLink, SITE_URL};
+use handlebars::Handlebars;
+use std::fs;
+use std::path::Path;
+
+pub fn generate(
+    handlebars: &Handlebars,
+    brawl_mods: &BrawlMods,
+    assets: &AssetPaths,
+    old_name: &str,
+    new_name: &str,
+) {
+    let find_mod = |name: &str| {
+        let found = brawl_mods
+            .mods
+            .iter()
+            .find(|x| x.name.eq_ignore_ascii_case(name));
+        if found.is_none() {
+            error!("Cannot generate changelog, the mod {name:?} was not loaded");
+        }
+        found
+    };
+    let (Some(old_mod), Some(new_mod)) = (find_mod(old_name), find_mod(new_name)) else {
+        return;
+    };
+
+    let old_frame_data = compare::gen_frame_data(old_mod);
+    let new_frame_data = compare::gen_frame_data(new_mod);
+
+    let mut fighters = vec![];
+    let mut fighters_added = vec![];
+    for (new_index, new_fighter) in new_mod.fighters.iter().enumerate() {
+        let name = &new_fighter.fighter.name;
+        match old_mod
+            .fighters
+            .iter()
+            .position(|x| &x.fighter.name == name)
+        {
+            Some(old_index) => {
+                let comparison = FighterComparison::new(
+                    (old_mod, &old_mod.fighters[old_index]),
+                    (new_mod, new_fighter),
+                    &old_frame_data[old_index],
+                    &new_frame_data[new_index],
+                );
+                if comparison.attributes.iter().any(|x| x.value.changed)
+                    || !comparison.subactions.is_empty()
+                {
+                    fighters.push(comparison);
+                }
+            }
+            None => fighters_added.push(fighter_link(new_mod, name)),
+        }
+    }
+    let fighters_removed: Vec<_> = old_mod
+        .fighters
+        .iter()
+        .filter(|old| {
+            !new_mod
+                .fighters
+                .iter()
+                .any(|new| new.fighter.name == old.fighter.name)
+        })
+        .map(|old| fighter_link(old_mod, &old.fighter.name))
+        .collect();
+
+    let title = format!("{} to {} Changelog", old_mod.name, new_mod.name);
+    let markdown = gen_markdown(&title, &fighters, &fighters_added, &fighters_removed);
+
+    let page = ChangelogPage {
+        assets,
+        mod_links: brawl_mods.gen_mod_links(new_mod.name.clone()),
+        title,
+        compare_link: format!("/compare/{}/{}", old_mod.name, new_mod.name),
+        fighters: &fighters,
+        fighters_added,
+        fighters_removed,
+        hit_value_names: HIT_VALUE_NAMES,
+    };
+
+    // The changelog is not tracked by the build cache as it is only generated on request.
+    let dir = OutDir::new(&format!("changelog/{}/{}", old_mod.name, new_mod.name));
+    let html = handlebars.render("changelog", &page).unwrap();
+    dir.create_compressed_file("index.html", html.as_bytes());
+    dir.create_compressed_file("changelog.md", markdown.as_bytes());
+
+    let markdown_path =
+        Path::new("../changelog").join(format!("{}-{}.md", old_mod.name, new_mod.name));
+    fs::create_dir_all(markdown_path.parent().unwrap()).unwrap();
+    fs::write(&markdown_path, &markdown)
+        .map_err(|e| anyhow::anyhow!("Failed to write to {markdown_path:?} {e}"))
+        .unwrap();
+    info!(
+        "{} changelog written to {}/index.html and {markdown_path:?}",
+        page.title,
+        dir.url("")
+    );
+}
+
+fn fighter_link(brawl_mod: &BrawlMod, name: &str) -> NavLink {
+    NavLink {
+        name: name.to_owned(),
+        link: format!("/{}", brawl_mod.fighter_path(name)),
+        current: false,
+    }
+}
+
+fn gen_markdown(
+    title: &str,
+    fighters: &[FighterComparison],
+    fighters_added: &[NavLink],
+    fighters_removed: &[NavLink],
+) -> String {
+    let mut md = format!("# {title}\n");
+
+    for (heading, links) in [
+        ("Fighters Added", fighters_added),
+        ("Fighters Removed", fighters_removed),
+    ] {
+        if !links.is_empty() {
+            md.push_str(&format!("\n## {heading}\n\n"));
+            for link in links {
+                md.push_str(&format!("* [{}]({SITE_URL}{})\n", link.name, link.link));
+            }
+        }
+    }
+
+    for fighter in fighters {
+        md.push_str(&format!("\n## {}\n", fighter.name));
+
+        let attributes: Vec<_> = fighter
+            .attributes
+            .iter()
+            .filter(|x| x.value.changed)
+            .collect();
+        if !attributes.is_empty() {
+            md.push_str("\n### Attributes\n\n");
+            for attribute in attributes {
+                md.push_str(&format!(
+                    "* {}: {}\n",
+                    attribute.name,
+                    markdown_value(&attribute.value)
+                ));
+            }
+        }
+
+        if !fighter.subactions.is_empty() {
+            md.push_str("\n### Subactions\n\n");
+        }
+        for subaction in &fighter.subactions {
+            let mut links = vec![];
+            if let Some(link) = &subaction.old_link {
+                links.push(format!("[old]({SITE_URL}{link})"));
+            }
+            if let Some(link) = &subaction.new_link {
+                links.push(format!("[new]({SITE_URL}{link})"));
+            }
+            md.push_str(&format!("* **{}** ({})", subaction.name, links.join(", ")));
+            if let Some(status) = subaction.status {
+                md.push_str(&format!(" {status}\n"));
+                continue;
+            }
+            md.push('\n');
+
+            for timing in subaction.timings.iter().filter(|x| x.value.changed) {
+                md.push_str(&format!(
+                    "    * {}: {}\n",
+                    timing.name,
+                    markdown_value(&timing.value)
+                ));
+            }
+
+            for hit in subaction.hitboxes.iter().filter(|x| x.changed) {
+                let changes: Vec<_> = HIT_VALUE_NAMES
+                    .iter()
+                    .zip(&hit.values)
+                    .filter(|(_, value)| value.changed)
+                    .map(|(name, value)| format!("{name} {}", markdown_value(value)))
+                    .collect();
+                md.push_str(&format!(
+                    "    * Hitbox set {} id {}: {}\n",
+                    hit.set_id,
+                    hit.hitbox_id,
+                    changes.join(", ")
+                ));
+            }
+
+            if !subaction.scripts_changed.is_empty() {
+                md.push_str(&format!(
+                    "    * Scripts changed: {}\n",
+                    subaction.scripts_changed.join(", ")
+                ));
+            }
+        }
+    }
+
+    md
+}
+
+fn markdown_value(value: &Value) -> String {
+    let display = |x: &str| if x.is_empty() { "None" } else { x }.to_owned();
+    format!("{} → {}", display(&value.old), display(&value.new))
+}
+
+#[derive(Serialize)]
+struct ChangelogPage<'a> {
+    assets: &'a AssetPaths,
+    mod_links: Vec<NavLink>,
+    title: String,
+    compare_link: String,
+    fighters: &'a [FighterComparison],
+    fighters_added: Vec<NavLink>,
+    fighters_removed: Vec<NavLink>,
+    hit_value_names: &'static [&'static str],
+}
@@ -0,0 +1,577 @@
+use crate::assets::AssetPaths;
+use crate::brawl_data::{BrawlFighter, BrawlMod, BrawlMods, slug};
+use crate::frame_data::{self, Hit, SubactionFrameData};
+use crate::output::OutDir;
+use crate::page::NavLink;
+use crate::page::attributes::attributes_to_strings;
+use crate::site::Site;
+use brawllib_rs::high_level_fighter::HighLevelSubaction;
+use brawllib_rs::script_ast::Block;
+use handlebars::Handlebars;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Generates `/compare/{old_mod}/{new_mod}/{fighter}/` for every pair of mods that share a fighter,
+/// along with `/compare/{old_mod}/{new_mod}/` listing how much each fighter changed.
+pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths, site: &Site) {
+    // Frame data is shared between every pair of mods so only calculate it once, when a comparison first needs it.
+    let frame_data: Vec<Vec<OnceLock<Vec<SubactionFrameData>>>> = brawl_mods
+        .mods
+        .iter()
+        .map(|x| x.fighters.iter().map(|_| OnceLock::new()).collect())
+        .collect();
+    let fighter_frame_data = |mod_index: usize, fighter_index: usize| {
+        frame_data[mod_index][fighter_index].get_or_init(|| {
+            let brawl_mod = &brawl_mods.mods[mod_index];
+            gen_fighter_frame_data(brawl_mod, &brawl_mod.fighters[fighter_index])
+        })
+    };
+
+    for (old_index, old_mod) in brawl_mods.mods.iter().enumerate() {
+        for (new_index, new_mod) in brawl_mods.mods.iter().enumerate() {
+            if old_index == new_index {
+                continue;
+            }
+
+            let compare_dir = OutDir::new(&format!("compare/{}/{}", old_mod.name, new_mod.name));
+            if !site.wants(&compare_dir) {
+                continue;
+            }
+
+            let mod_links = brawl_mods.gen_mod_links(new_mod.name.clone());
+            let fighters: Vec<_> = new_mod
+                .fighters
+                .iter()
+                .enumerate()
+                .filter_map(|(new_fighter_index, new_fighter)| {
+                    old_mod
+                        .fighters
+                        .iter()
+                        .position(|x| x.fighter.name == new_fighter.fighter.name)
+                        .map(|old_fighter_index| (old_fighter_index, new_fighter_index))
+                })
+                .collect();
+
+            let compare_link = format!("/compare/{}/{}", old_mod.name, new_mod.name);
+            // Only compared when a page showing the comparison is stale.
+            let comparisons: Vec<OnceLock<FighterComparison>> =
+                fighters.iter().map(|_| OnceLock::new()).collect();
+            let comparison = |index: usize| {
+                comparisons[index].get_or_init(|| {
+                    let (old_fighter_index, new_fighter_index) = fighters[index];
+                    FighterComparison::new(
+                        (old_mod, &old_mod.fighters[old_fighter_index]),
+                        (new_mod, &new_mod.fighters[new_fighter_index]),
+                        fighter_frame_data(old_index, old_fighter_index),
+                        fighter_frame_data(new_index, new_fighter_index),
+                    )
+                })
+            };
+
+            (0..fighters.len()).into_par_iter().for_each(|index| {
+                let (old_fighter_index, new_fighter_index) = fighters[index];
+                let old_fighter = &old_mod.fighters[old_fighter_index];
+                let new_fighter = &new_mod.fighters[new_fighter_index];
+                let dir = OutDir::new(&format!(
+                    "compare/{}/{}/{}",
+                    old_mod.name,
+                    new_mod.name,
+                    slug(&new_fighter.fighter.name)
+                ));
+                site.page(
+                    handlebars,
+                    "compare",
+                    &dir,
+                    "index.html",
+                    &[&old_fighter.hash, &new_fighter.hash],
+                    || {
+                        let comparison = comparison(index);
+                        let fighter_links = fighters
+                            .iter()
+                            .map(|(_, new_fighter_index)| {
+                                let name = &new_mod.fighters[*new_fighter_index].fighter.name;
+                                NavLink {
+                                    name: name.clone(),
+                                    link: format!("{}/{}/", compare_link, slug(name)),
+                                    current: *name == comparison.name,
+                                }
+                            })
+                            .collect();
+
+                        ComparePage {
+                            assets,
+                            mod_links: &mod_links,
+                            fighter_links,
+                            title: format!(
+                                "{} - {} vs {}",
+                                comparison.name, old_mod.name, new_mod.name
+                            ),
+                            old_mod: &old_mod.name,
+                            new_mod: &new_mod.name,
+                            comparison,
+                        }
+                    },
+                );
+            });
+
+            site.page(
+                handlebars,
+                "compare_index",
+                &compare_dir,
+                "index.html",
+                &[&old_mod.hash, &new_mod.hash],
+                || {
+                    let fighters = (0..fighters.len())
+                        .into_par_iter()
+                        .map(|index| {
+                            let x = comparison(index);
+                            CompareSummary {
+                                name: x.name.clone(),
+                                link: format!("{}/{}/", compare_link, x.slug),
+                                attributes_changed: x
+                                    .attributes
+                                    .iter()
+                                    .filter(|x| x.value.changed)
+                                    .count(),
+                                subactions_changed: x.subactions.len(),
+                            }
+                        })
+                        .collect();
+
+                    CompareIndexPage {
+                        assets,
+                        mod_links: &mod_links,
+                        title: format!("{} vs {}", old_mod.name, new_mod.name),
+                        old_mod: &old_mod.name,
+                        new_mod: &new_mod.name,
+                        fighters,
+                    }
+                },
+            );
+            info!("{} vs {} comparison", old_mod.name, new_mod.name);
+        }
+    }
+}
+
+/// The frame data of every subaction of every fighter in the mod.
+pub fn gen_frame_data(brawl_mod: &BrawlMod) -> Vec<Vec<SubactionFrameData>> {
+    brawl_mod
+        .fighters
+        .par_iter()
+        .map(|fighter| gen_fighter_frame_data(brawl_mod, fighter))
+        .collect()
+}
+
+/// The frame data of every subaction of the fighter.
+fn gen_fighter_frame_data(brawl_mod: &BrawlMod, fighter: &BrawlFighter) -> Vec<SubactionFrameData> {
+    fighter
+        .fighter
+        .subactions
+        .iter()
+        .enumerate()
+        .map(|(index, subaction)| SubactionFrameData::new(brawl_mod, fighter, index, subaction))
+        .collect()
+}
+
+/// Links to the comparisons between `brawl_mod` and every other mod containing `fighter_name`.
+pub fn gen_compare_links(
+    brawl_mods: &BrawlMods,
+    brawl_mod: &BrawlMod,
+    fighter_name: &str,
+) -> Vec<NavLink> {
+    brawl_mods
+        .mods
+        .iter()
+        .filter(|x| x.name != brawl_mod.name)
+        .filter(|x| x.fighters.iter().any(|x| x.fighter.name == fighter_name))
+        .map(|other| NavLink {
+            name: format!("Compare with {}", other.name),
+            link: format!(
+                "/compare/{}/{}/{}/",
+                other.name,
+                brawl_mod.name,
+                slug(fighter_name)
+            ),
+            current: false,
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+pub struct FighterComparison {
+    pub name: String,
+    /// The comparison's directory in `/compare/{old_mod}/{new_mod}/`
+    pub slug: String,
+    pub attributes: Vec<NamedValue>,
+    /// Only subactions that changed are included.
+    pub subactions: Vec<SubactionComparison>,
+    pub subactions_unchanged: usize,
+}
+
+impl FighterComparison {
+    pub fn new(
+        (old_mod, old_fighter): (&BrawlMod, &BrawlFighter),
+        (new_mod, new_fighter): (&BrawlMod, &BrawlFighter),
+        old_frame_data: &[SubactionFrameData],
+        new_frame_data: &[SubactionFrameData],
+    ) -> FighterComparison {
+        let name = new_fighter.fighter.name.clone();
+
+        let attributes = attributes_to_strings(&old_fighter.fighter.attributes)
+            .into_iter()
+            .zip(attributes_to_strings(&new_fighter.fighter.attributes))
+            .map(|(old, new)| NamedValue {
+                name: new.name,
+                value: Value::new(old.value, new.value),
+            })
+            .collect();
+
+        let old_subactions: HashMap<&str, &SubactionFrameData> = old_frame_data
+            .iter()
+            .map(|x| (x.name.as_str(), x))
+            .collect();
+
+        // Subactions are listed in the order of the new mod, followed by any subactions that were removed.
+        let mut pairs: Vec<(Option<&SubactionFrameData>, Option<&SubactionFrameData>)> =
+            new_frame_data
+                .iter()
+                .map(|new| (old_subactions.get(new.name.as_str()).copied(), Some(new)))
+                .collect();
+        for old in old_frame_data {
+            if !new_frame_data.iter().any(|new| new.name == old.name) {
+                pairs.push((Some(old), None));
+            }
+        }
+
+        let mut subactions = vec![];
+        let mut subactions_unchanged = 0;
+        for (old, new) in pairs {
+            let subaction_name = new.or(old).unwrap().name.clone();
+            let link = |brawl_mod: &BrawlMod| {
+                format!(
+                    "/{}/subactions/{}.html",
+                    brawl_mod.fighter_path(&name),
+                    subaction_name
+                )
+            };
+            let scripts_changed = scripts_changed(
+                old_fighter
+                    .fighter
+                    .subactions
+                    .iter()
+                    .find(|x| x.name == subaction_name),
+                new_fighter
+                    .fighter
+                    .subactions
+                    .iter()
+                    .find(|x| x.name == subaction_name),
+            );
+            let comparison = SubactionComparison::new(
+                subaction_name.clone(),
+                old.map(|_| link(old_mod)),
+                new.map(|_| link(new_mod)),
+                old,
+                new,
+                scripts_changed,
+            );
+            if comparison.changed {
+                subactions.push(comparison);
+            } else {
+                subactions_unchanged += 1;
+            }
+        }
+
+        FighterComparison {
+            slug: slug(&name),
+            name,
+            attributes,
+            subactions,
+            subactions_unchanged,
+        }
+    }
+}
+
+/// The names of the scripts that differ between the two versions of a subaction.
+fn scripts_changed(
+    old: Option<&HighLevelSubaction>,
+    new: Option<&HighLevelSubaction>,
+) -> Vec<&'static str> {
+    let (Some(old), Some(new)) = (old, new) else {
+        return vec![];
+    };
+    [
+        ("Main", &old.scripts.script_main, &new.scripts.script_main),
+        ("GFX", &old.scripts.script_gfx, &new.scripts.script_gfx),
+        ("SFX", &old.scripts.script_sfx, &new.scripts.script_sfx),
+        (
+            "Other",
+            &old.scripts.script_other,
+            &new.scripts.script_other,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, old, new)| script_value(&old.block) != script_value(&new.block))
+    .map(|(name, _, _)| name)
+    .collect()
+}
+
+/// The script serialized without any offsets, as the offset of the script itself and of the scripts called by
+/// `Goto`, `Subroutine`, `CallEveryFrame` and `IndependentSubroutine` move around whenever anything else in the file changes.
+fn script_value(block: &Block) -> serde_json::Value {
+    let mut value = serde_json::to_value(block).unwrap();
+    zero_offsets(&mut value);
+    value
+}
+
+fn zero_offsets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let is_offset =
+                map.len() == 2 && map.contains_key("offset") && map.contains_key("origin");
+            for value in map.values_mut() {
+                if is_offset {
+                    *value = 0.into();
+                } else {
+                    zero_offsets(value);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(zero_offsets),
+        _ => {}
+    }
+}
+
+/// An attribute or subaction timing
+#[derive(Serialize)]
+pub struct NamedValue {
+    pub name: &'static str,
+    pub value: Value,
+}
+
+#[derive(Serialize)]
+pub struct SubactionComparison {
+    pub name: String,
+    pub old_link: Option<String>,
+    pub new_link: Option<String>,
+    /// "Added" or "Removed" when the subaction only exists in one of the mods
+    pub status: Option<&'static str>,
+    /// In the same order as the header in compare.html.hbs
+    pub timings: Vec<NamedValue>,
+    pub hitboxes: Vec<HitComparison>,
+    pub scripts_changed: Vec<&'static str>,
+    #[serde(skip)]
+    pub changed: bool,
+}
+
+impl SubactionComparison {
+    fn new(
+        name: String,
+        old_link: Option<String>,
+        new_link: Option<String>,
+        old: Option<&SubactionFrameData>,
+        new: Option<&SubactionFrameData>,
+        scripts_changed: Vec<&'static str>,
+    ) -> SubactionComparison {
+        let status = match (old, new) {
+            (None, Some(_)) => Some("Added"),
+            (Some(_), None) => Some("Removed"),
+            _ => None,
+        };
+
+        let value = |name, f: &dyn Fn(&SubactionFrameData) -> String| NamedValue {
+            name,
+            value: Value::new(
+                old.map(f).unwrap_or_else(missing),
+                new.map(f).unwrap_or_else(missing),
+            ),
+        };
+        let timings = vec![
+            value("Frames", &|x| x.frames.to_string()),
+            value("IASA", &|x| option_string_or_none(x.iasa)),
+            value("Auto Cancel", &|x| {
+                frame_data::ranges_string(&x.auto_cancel)
+            }),
+            value("Landing Lag", &|x| option_string_or_none(x.landing_lag)),
+            value("Hitboxes Active", &|x| {
+                frame_data::ranges_string(&x.hitboxes_active)
+            }),
+            value("Fully Intangible", &|x| {
+                frame_data::ranges_string(&x.fully_intangible)
+            }),
+        ];
+
+        let hitboxes = compare_hits(
+            old.map(hits).unwrap_or_default(),
+            new.map(hits).unwrap_or_default(),
+        );
+
+        let changed = status.is_some()
+            || timings.iter().any(|x| x.value.changed)
+            || hitboxes.iter().any(|x| x.changed)
+            || !scripts_changed.is_empty();
+
+        SubactionComparison {
+            name,
+            old_link,
+            new_link,
+            status,
+            timings,
+            hitboxes,
+            scripts_changed,
+            changed,
+        }
+    }
+}
+
+/// A hit and the frames it is active on.
+/// The same hitbox can occur in multiple groups, so hits are identified by their set, id and occurrence.
+struct IdentifiedHit<'a> {
+    key: (u8, u8, usize),
+    frames: String,
+    hit: &'a Hit,
+}
+
+fn hits(frame_data: &SubactionFrameData) -> Vec<IdentifiedHit<'_>> {
+    let mut occurrences: HashMap<(u8, u8), usize> = HashMap::new();
+    let mut hits = vec![];
+    for group in &frame_data.hitbox_groups {
+        for hit in group.hits() {
+            let occurrence = occurrences.entry((hit.set_id, hit.hitbox_id)).or_default();
+            hits.push(IdentifiedHit {
+                key: (hit.set_id, hit.hitbox_id, *occurrence),
+                frames: group.frames.to_string(),
+                hit,
+            });
+            *occurrence += 1;
+        }
+    }
+    hits
+}
+
+fn compare_hits(old: Vec<IdentifiedHit>, new: Vec<IdentifiedHit>) -> Vec<HitComparison> {
+    let mut pairs: Vec<(Option<&IdentifiedHit>, Option<&IdentifiedHit>)> = new
+        .iter()
+        .map(|new| (old.iter().find(|old| old.key == new.key), Some(new)))
+        .collect();
+    for old in &old {
+        if !new.iter().any(|new| new.key == old.key) {
+            pairs.push((Some(old), None));
+        }
+    }
+
+    pairs
+        .into_iter()
+        .map(|(old, new)| {
+            let value = |f: &dyn Fn(&IdentifiedHit) -> String| {
+                Value::new(
+                    old.map(f).unwrap_or_else(missing),
+                    new.map(f).unwrap_or_else(missing),
+                )
+            };
+            let key = new.or(old).unwrap().key;
+            let values = vec![
+                value(&|x| x.frames.clone()),
+                value(&|x| x.hit.damage.to_string()),
+                value(&|x| x.hit.wdsk.to_string()),
+                value(&|x| x.hit.bkb.to_string()),
+                value(&|x| x.hit.kbg.to_string()),
+                value(&|x| x.hit.angle.to_string()),
+                value(&|x| format!("{:?}", x.hit.effect)),
+                value(&|x| x.hit.shield_damage.to_string()),
+                value(&|x| x.hit.hitlag_mult.to_string()),
+                value(&|x| x.hit.sdi_mult.to_string()),
+                value(&|x| x.hit.shieldstun.to_string()),
+                value(&|x| x.hit.hitlag.to_string()),
+            ];
+            HitComparison {
+                set_id: key.0,
+                hitbox_id: key.1,
+                changed: values.iter().any(|x| x.changed),
+                values,
+            }
+        })
+        .collect()
+}
+
+/// The names of `HitComparison::values`
+pub const HIT_VALUE_NAMES: &[&str] = &[
+    "Frames",
+    "Damage",
+    "WDSK",
+    "BKB",
+    "KBG",
+    "Angle",
+    "Effect",
+    "Shield Damage",
+    "Hitlag Mult",
+    "SDI Mult",
+    "Shieldstun",
+    "Hitlag",
+];
+
+#[derive(Serialize)]
+pub struct HitComparison {
+    pub set_id: u8,
+    pub hitbox_id: u8,
+    /// In the same order as `HIT_VALUE_NAMES` and the header in compare.html.hbs
+    pub values: Vec<Value>,
+    pub changed: bool,
+}
+
+#[derive(Serialize)]
+pub struct Value {
+    pub old: String,
+    pub new: String,
+    pub changed: bool,
+}
+
+impl Value {
+    fn new(old: String, new: String) -> Value {
+        Value {
+            changed: old != new,
+            old,
+            new,
+        }
+    }
+}
+
+fn missing() -> String {
+    "-".into()
+}
+
+/// Spells out that there is no value, so it can be compared against the other fighter's value.
+fn option_string_or_none<T: ToString>(value: Option<T>) -> String {
+    value
+        .map(|x| x.to_string())
+        .unwrap_or_else(|| "None".into())
+}
+
+#[derive(Serialize)]
+struct ComparePage<'a> {
+    assets: &'a AssetPaths,
+    mod_links: &'a [NavLink],
+    fighter_links: Vec<NavLink>,
+    title: String,
+    old_mod: &'a str,
+    new_mod: &'a str,
+    comparison: &'a FighterComparison,
+}
+
+#[derive(Serialize)]
+struct CompareIndexPage<'a> {
+    assets: &'a AssetPaths,
+    mod_links: &'a [NavLink],
+    title: String,
+    old_mod: &'a str,
+    new_mod: &'a str,
+    fighters: Vec<CompareSummary>,
+}
+
+#[derive(Serialize)]
+struct CompareSummary {
+    name: String,
+    link: String,
+    attributes_changed: usize,
+    subactions_changed: usize,
+}
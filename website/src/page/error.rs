@@ -2,16 +2,21 @@ use crate::assets::AssetPaths;
 use crate::brawl_data::BrawlMods;
 use crate::output::OutDir;
 use crate::page::NavLink;
+use crate::site::Site;
 use handlebars::Handlebars;
 
-pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths) {
-    let page = ErrorPage {
-        assets,
-        mod_links: brawl_mods.gen_mod_links(String::new()),
-    };
-    let file =
-        OutDir::new(assets.root_index.trim_start_matches('/')).compressed_file_writer("error.html");
-    handlebars.render_to_write("error", &page, file).unwrap();
+pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths, site: &Site) {
+    site.page(
+        handlebars,
+        "error",
+        &OutDir::new(assets.root_index.trim_start_matches('/')),
+        "error.html",
+        &[],
+        || ErrorPage {
+            assets,
+            mod_links: brawl_mods.gen_mod_links(String::new()),
+        },
+    );
 }
 
 #[derive(Serialize)]
@@ -1,33 +1,211 @@
 use crate::assets::AssetPaths;
-use crate::brawl_data::BrawlMods;
+use crate::brawl_data::{BrawlFighter, BrawlMod, BrawlMods};
+use crate::frame_data::{self, HitBox, SubactionFrameData};
 use crate::output::OutDir;
 use crate::page::NavLink;
+use crate::page::compare;
+use crate::page::out_of_shield::OutOfShield;
+use crate::site::Site;
 use handlebars::Handlebars;
 use rayon::prelude::*;
 
-pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths) {
-    for brawl_mod in &brawl_mods.mods {
+pub fn generate(
+    handlebars: &Handlebars,
+    brawl_mods: &BrawlMods,
+    assets: &AssetPaths,
+    site: &Site,
+    out_of_shield: &OutOfShield,
+) {
+    for (mod_index, brawl_mod) in brawl_mods.mods.iter().enumerate() {
+        if !site.wants(&OutDir::new(&brawl_mod.name)) {
+            continue;
+        }
         let mod_links = brawl_mods.gen_mod_links(brawl_mod.name.clone());
-        brawl_mod.fighters.par_iter().for_each(|fighter| {
-            let fighter = &fighter.fighter;
-            let page = FighterPage {
-                mod_links: &mod_links,
-                title: format!("{} - {}", brawl_mod.name, fighter.name),
-                fighter_links: brawl_mod.gen_fighter_links(&fighter.name),
-                assets,
-            };
-
-            let file = OutDir::new(&format!("{}/{}", brawl_mod.name, fighter.name))
-                .compressed_file_writer("index.html");
-            handlebars.render_to_write("fighter", &page, file).unwrap();
+        brawl_mod.fighters.par_iter().for_each(|brawl_fighter| {
+            let fighter = &brawl_fighter.fighter;
+            let dir = OutDir::new(&brawl_mod.fighter_path(&fighter.name));
+            // The fighter is compared against the out of shield options of every fighter in the mod.
+            site.page(
+                handlebars,
+                "fighter",
+                &dir,
+                "index.html",
+                &[&brawl_mod.hash],
+                || {
+                    let attacks = gen_attacks(brawl_mod, brawl_fighter);
+                    let fastest_out_of_shield = out_of_shield.fastest_options(mod_index);
+                    FighterPage {
+                        mod_links: &mod_links,
+                        title: format!("{} - {}", brawl_mod.name, fighter.name),
+                        fighter_links: brawl_mod.gen_fighter_links(&fighter.name),
+                        compare_links: compare::gen_compare_links(
+                            brawl_mods,
+                            brawl_mod,
+                            &fighter.name,
+                        ),
+                        frame_data: gen_frame_data_summary(&attacks),
+                        on_shield: gen_on_shield(&attacks, &fastest_out_of_shield),
+                        out_of_shield_link: format!("/{}/out_of_shield.html", brawl_mod.name),
+                        assets,
+                    }
+                },
+            );
         });
     }
 }
 
+/// The frame data of every attack, grouped in the same way as the subaction navigation.
+pub fn gen_attacks(
+    brawl_mod: &BrawlMod,
+    fighter: &BrawlFighter,
+) -> Vec<(&'static str, Vec<(NavLink, SubactionFrameData)>)> {
+    let links = brawl_mod.gen_subaction_links(fighter, String::new());
+    [
+        ("Jabs", links.attacks_jab),
+        ("Tilt Attacks", links.attacks_tilt),
+        ("Smash Attacks", links.attacks_smash),
+        ("Dash Attack", links.attacks_dash),
+        ("Aerial Attacks", links.attacks_aerial),
+        ("Specials", links.specials),
+        ("Grabs and Throws", links.grabs),
+    ]
+    .into_iter()
+    .map(|(name, links)| {
+        let attacks = links
+            .into_iter()
+            .filter_map(|link| {
+                let (index, subaction) = fighter
+                    .fighter
+                    .subactions
+                    .iter()
+                    .enumerate()
+                    .find(|(_, x)| x.name == link.name)?;
+                let data = SubactionFrameData::new(brawl_mod, fighter, index, subaction);
+                Some((link, data))
+            })
+            .collect();
+        (name, attacks)
+    })
+    .collect()
+}
+
+/// Summarizes the frame data of every attack.
+fn gen_frame_data_summary(
+    attacks: &[(&'static str, Vec<(NavLink, SubactionFrameData)>)],
+) -> Vec<FrameDataSection> {
+    attacks
+        .iter()
+        .map(|(name, attacks)| FrameDataSection {
+            name,
+            rows: attacks
+                .iter()
+                .map(|(link, data)| FrameDataRow::new(link.clone(), data))
+                .collect(),
+        })
+        .filter(|x| !x.rows.is_empty())
+        .collect()
+}
+
+/// Lists every attack that can be shielded, safest first.
+/// `fastest_out_of_shield` is the frame of the fastest out of shield option of each fighter in the mod.
+fn gen_on_shield(
+    attacks: &[(&'static str, Vec<(NavLink, SubactionFrameData)>)],
+    fastest_out_of_shield: &[i32],
+) -> Vec<OnShieldRow> {
+    let mut rows: Vec<_> = attacks
+        .iter()
+        .flat_map(|(_, attacks)| attacks)
+        .filter_map(|(link, data)| {
+            // Use the hitbox the attacker would want to be shielded
+            let (hit, advantage) = data
+                .hitbox_groups
+                .iter()
+                .flat_map(|x| &x.hitboxes)
+                .filter_map(|x| match x {
+                    HitBox::Hit(hit) => Some((hit, hit.shield_advantage.as_ref()?)),
+                    HitBox::Grab(_) => None,
+                })
+                .max_by_key(|(_, advantage)| advantage.best())?;
+            let best = advantage.best();
+            // The attack is safe when the attacker can act before the defender's fastest option becomes active.
+            let safe_against = fastest_out_of_shield
+                .iter()
+                .filter(|frame| best + **frame > 0)
+                .count();
+            Some(OnShieldRow {
+                subaction: link.clone(),
+                shieldstun: hit.shieldstun.to_string(),
+                advantage: advantage.to_string(),
+                best,
+                safe_against: format!("{} / {}", safe_against, fastest_out_of_shield.len()),
+            })
+        })
+        .collect();
+    rows.sort_by_key(|x| -x.best);
+    rows
+}
+
+#[derive(Serialize)]
+struct FrameDataSection {
+    name: &'static str,
+    rows: Vec<FrameDataRow>,
+}
+
+#[derive(Serialize)]
+struct FrameDataRow {
+    subaction: NavLink,
+    startup: String,
+    active: String,
+    frames: usize,
+    iasa: String,
+    landing_lag: String,
+    landing_lag_l_cancel: String,
+    auto_cancel: String,
+    max_damage: String,
+    shieldstun: String,
+}
+
+impl FrameDataRow {
+    fn new(subaction: NavLink, data: &SubactionFrameData) -> FrameDataRow {
+        let hits = || data.hitbox_groups.iter().flat_map(|x| x.hits());
+        let max_damage = hits()
+            .map(|x| x.damage)
+            .chain(data.throw.as_ref().map(|x| x.damage as f32))
+            .reduce(f32::max);
+        let shieldstun = hits().map(|x| x.shieldstun).reduce(f32::max);
+        FrameDataRow {
+            subaction,
+            startup: frame_data::option_string(data.hitboxes_active.first().map(|x| x.start)),
+            active: frame_data::ranges_string(&data.hitboxes_active),
+            frames: data.frames,
+            iasa: frame_data::option_string(data.iasa),
+            landing_lag: frame_data::option_string(data.landing_lag),
+            landing_lag_l_cancel: frame_data::option_string(data.landing_lag_l_cancel),
+            auto_cancel: frame_data::ranges_string(&data.auto_cancel),
+            max_damage: frame_data::option_string(max_damage),
+            shieldstun: frame_data::option_string(shieldstun),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OnShieldRow {
+    subaction: NavLink,
+    shieldstun: String,
+    advantage: String,
+    best: i32,
+    /// How many fighters in the mod can't punish the attack with their fastest out of shield option.
+    safe_against: String,
+}
+
 #[derive(Serialize)]
 struct FighterPage<'a> {
     assets: &'a AssetPaths,
     mod_links: &'a [NavLink],
     fighter_links: Vec<NavLink>,
+    compare_links: Vec<NavLink>,
+    frame_data: Vec<FrameDataSection>,
+    on_shield: Vec<OnShieldRow>,
+    out_of_shield_link: String,
     title: String,
 }
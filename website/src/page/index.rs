@@ -2,22 +2,51 @@ use crate::assets::AssetPaths;
 use crate::brawl_data::BrawlMods;
 use crate::output::OutDir;
 use crate::page::NavLink;
+use crate::site::Site;
 use handlebars::Handlebars;
 
-pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths) {
-    let page = IndexPage {
-        title: "Rukai Data",
-        mod_links: brawl_mods.gen_mod_links(String::new()),
-        assets,
-    };
-    let writer =
-        OutDir::new(assets.root_index.trim_start_matches('/')).compressed_file_writer("index.html");
-    handlebars.render_to_write("index", &page, writer).unwrap();
+pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths, site: &Site) {
+    let mod_links = brawl_mods.gen_mod_links(String::new());
+    let mods = mod_links
+        .iter()
+        .map(|link| {
+            let brawl_mod = brawl_mods
+                .mods
+                .iter()
+                .find(|x| format!("/{}", x.name) == link.link);
+            IndexMod {
+                link: link.clone(),
+                version: brawl_mod.and_then(|x| x.version.clone()),
+                description: brawl_mod.and_then(|x| x.description.clone()),
+            }
+        })
+        .collect();
+    site.page(
+        handlebars,
+        "index",
+        &OutDir::new(assets.root_index.trim_start_matches('/')),
+        "index.html",
+        &[],
+        || IndexPage {
+            title: "Rukai Data",
+            mod_links,
+            mods,
+            assets,
+        },
+    );
 }
 
 #[derive(Serialize)]
 struct IndexPage<'a> {
     assets: &'a AssetPaths,
     mod_links: Vec<NavLink>,
+    mods: Vec<IndexMod>,
     title: &'static str,
 }
+
+#[derive(Serialize)]
+struct IndexMod {
+    link: NavLink,
+    version: Option<String>,
+    description: Option<String>,
+}
@@ -0,0 +1,146 @@
+use crate::assets::{self, AssetPaths};
+use crate::brawl_data::{BrawlFighter, BrawlMod, BrawlMods};
+use crate::frame_data::Hit;
+use crate::knockback_formulas::{self, BlastZone, KnockbackHit, KnockbackTarget};
+use crate::output::OutDir;
+use crate::page::NavLink;
+use crate::page::fighter::gen_attacks;
+use crate::site::Site;
+use handlebars::Handlebars;
+use rayon::prelude::*;
+
+/// Generates `/{mod}/{fighter}/kill_percents.html`, the estimated kill percent of every attack against every fighter in the mod.
+pub fn generate(
+    handlebars: &Handlebars,
+    brawl_mods: &BrawlMods,
+    assets: &AssetPaths,
+    site: &Site,
+    blast_zone: &BlastZone,
+) {
+    for brawl_mod in &brawl_mods.mods {
+        let mod_links = brawl_mods.gen_mod_links(brawl_mod.name.clone());
+        let targets: Vec<_> = brawl_mod
+            .fighters
+            .iter()
+            .map(|x| KnockbackTarget::new(&x.fighter))
+            .collect();
+        let targets_hash = assets::hash(&serde_json::to_vec(&targets).unwrap());
+
+        brawl_mod.fighters.par_iter().for_each(|fighter| {
+            let dir = OutDir::new(&brawl_mod.fighter_path(&fighter.fighter.name));
+            // Kill percents depend on the attributes of every fighter in the mod.
+            site.page(
+                handlebars,
+                "kill_percents",
+                &dir,
+                "kill_percents.html",
+                &[&fighter.hash, &targets_hash],
+                || {
+                    let sections = gen_sections(brawl_mod, fighter, &targets, blast_zone);
+                    KillPercentsPage {
+                        assets,
+                        mod_links: &mod_links,
+                        fighter_links: brawl_mod.gen_fighter_links(&fighter.fighter.name),
+                        title: format!(
+                            "{} - {} - Kill Percents",
+                            brawl_mod.name, fighter.fighter.name
+                        ),
+                        targets: targets.iter().map(|x| x.name.as_str()).collect(),
+                        columns: targets.len() + 1,
+                        blast_zone: *blast_zone,
+                        sections,
+                    }
+                },
+            );
+        });
+    }
+}
+
+/// The lowest kill percent of each attack against each target.
+fn gen_sections(
+    brawl_mod: &BrawlMod,
+    fighter: &BrawlFighter,
+    targets: &[KnockbackTarget],
+    blast_zone: &BlastZone,
+) -> Vec<KillPercentSection> {
+    gen_attacks(brawl_mod, fighter)
+        .into_iter()
+        .map(|(name, attacks)| KillPercentSection {
+            name,
+            rows: attacks
+                .into_iter()
+                .filter_map(|(subaction, data)| {
+                    // Only hitboxes that can hit grounded fighters, skipping duplicates as many hitboxes only differ in size and position.
+                    let mut hits: Vec<&Hit> = vec![];
+                    for hit in data.hitbox_groups.iter().flat_map(|x| x.hits()) {
+                        if hit.targets.fighter_ground
+                            && !hits.iter().any(|x| same_knockback(x, hit))
+                        {
+                            hits.push(hit);
+                        }
+                    }
+                    if hits.is_empty() {
+                        return None;
+                    }
+
+                    let kill_percents = targets
+                        .iter()
+                        .map(|target| {
+                            hits.iter()
+                                .filter_map(|hit| {
+                                    knockback_formulas::kill_percent(
+                                        &KnockbackHit::new(hit),
+                                        target,
+                                        false,
+                                        blast_zone,
+                                    )
+                                })
+                                .min()
+                                .map(|x| x.to_string())
+                                .unwrap_or_default()
+                        })
+                        .collect();
+                    Some(KillPercentRow {
+                        subaction,
+                        kill_percents,
+                    })
+                })
+                .collect(),
+        })
+        .filter(|x: &KillPercentSection| !x.rows.is_empty())
+        .collect()
+}
+
+fn same_knockback(a: &Hit, b: &Hit) -> bool {
+    a.damage == b.damage
+        && a.wdsk == b.wdsk
+        && a.bkb == b.bkb
+        && a.kbg == b.kbg
+        && a.angle == b.angle
+}
+
+#[derive(Serialize)]
+struct KillPercentSection {
+    name: &'static str,
+    rows: Vec<KillPercentRow>,
+}
+
+#[derive(Serialize)]
+struct KillPercentRow {
+    subaction: NavLink,
+    /// The lowest kill percent of any hitbox, against each target in the same order as `KillPercentsPage::targets`
+    kill_percents: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct KillPercentsPage<'a> {
+    assets: &'a AssetPaths,
+    mod_links: &'a [NavLink],
+    fighter_links: Vec<NavLink>,
+    title: String,
+    targets: Vec<&'a str>,
+    /// Number of columns in the table
+    columns: usize,
+    blast_zone: BlastZone,
+    sections: Vec<KillPercentSection>,
+}
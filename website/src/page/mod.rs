@@ -1,16 +1,156 @@
 pub mod action;
+pub mod action_graph;
 pub mod actions;
 pub mod attributes;
 pub mod brawl_mod;
+pub mod changelog;
+pub mod compare;
 pub mod error;
 pub mod fighter;
 pub mod index;
+pub mod kill_percents;
+pub mod mod_attributes;
+pub mod out_of_shield;
 pub mod script;
 pub mod scripts;
 pub mod subaction;
 pub mod subactions;
 pub mod variables;
 
+use crate::assets::AssetPaths;
+use crate::brawl_data::BrawlMods;
+use crate::config::Config;
+use crate::output::OutDir;
+use crate::site::Site;
+use crate::{frame_data, redirects, search, sitemap};
+use handlebars::Handlebars;
+use out_of_shield::OutOfShield;
+use serde::Serialize;
+
+/// Used wherever links need to be absolute, such as canonical links, the sitemap and markdown posted outside of the website.
+pub const SITE_URL: &str = "https://rukaidata.com";
+
+/// The page serialized for its template, with `canonical_url` added for the `<link rel=canonical>` in `base.html.hbs`.
+/// `url` is the url the page is linked to at, see `OutDir::page_url`.
+pub fn context<T: Serialize>(page: &T, url: &str) -> serde_json::Value {
+    let mut context = serde_json::to_value(page).unwrap();
+    context["canonical_url"] = format!("{SITE_URL}{url}").into();
+    context
+}
+
+/// Generates every page, along with the frame data exports and search index that the pages link to.
+pub fn generate_all(
+    handlebars: &Handlebars,
+    brawl_mods: &BrawlMods,
+    assets: &AssetPaths,
+    site: &Site,
+    config: &Config,
+) {
+    site.set_page_inputs(brawl_mods, assets);
+    let out_of_shield = OutOfShield::new(brawl_mods);
+    index::generate(handlebars, brawl_mods, assets, site);
+    error::generate(handlebars, brawl_mods, assets, site);
+    brawl_mod::generate(handlebars, brawl_mods, assets, site);
+    mod_attributes::generate(handlebars, brawl_mods, assets, site);
+    out_of_shield::generate(handlebars, brawl_mods, assets, site, &out_of_shield);
+    kill_percents::generate(handlebars, brawl_mods, assets, site, &config.blast_zone);
+    fighter::generate(handlebars, brawl_mods, assets, site, &out_of_shield);
+    attributes::generate(handlebars, brawl_mods, assets, site);
+    actions::generate(handlebars, brawl_mods, assets, site);
+    action::generate(handlebars, brawl_mods, assets, site);
+    action_graph::generate(handlebars, brawl_mods, assets, site);
+    subactions::generate(handlebars, brawl_mods, assets, site);
+    subaction::generate(
+        handlebars,
+        brawl_mods,
+        assets,
+        site,
+        config.legacy_renderer,
+        &config.blast_zone,
+    );
+    script::generate(handlebars, brawl_mods, assets, site);
+    scripts::generate(handlebars, brawl_mods, assets, site);
+    variables::generate(handlebars, brawl_mods, assets, site);
+    compare::generate(handlebars, brawl_mods, assets, site);
+    frame_data::generate(brawl_mods, site);
+    search::generate(brawl_mods, assets, site);
+    // Both need every page to have been rendered first.
+    redirects::generate(brawl_mods, site);
+    sitemap::generate(site);
+}
+
+/// Generates only the file at `url`, used by the dev server to generate pages as they are requested.
+/// The url is routed to the one generator that writes it, which skips the work for every other file as `site` only wants the one file.
+/// Redirect stubs and the sitemap list every page so are never generated this way.
+pub fn generate_url(
+    handlebars: &Handlebars,
+    brawl_mods: &BrawlMods,
+    assets: &AssetPaths,
+    site: &Site,
+    config: &Config,
+    url: &str,
+) {
+    let root = OutDir::new(assets.root_index.trim_start_matches('/'));
+    if url == root.url("index.html") {
+        return index::generate(handlebars, brawl_mods, assets, site);
+    }
+    if url == root.url("error.html") {
+        return error::generate(handlebars, brawl_mods, assets, site);
+    }
+
+    let segments: Vec<&str> = url.trim_start_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["compare", ..] => compare::generate(handlebars, brawl_mods, assets, site),
+        [.., "search", _] => search::generate(brawl_mods, assets, site),
+        [_, "index.html"] => brawl_mod::generate(handlebars, brawl_mods, assets, site),
+        [_, "attributes.html"] => mod_attributes::generate(handlebars, brawl_mods, assets, site),
+        [_, "out_of_shield.html"] => out_of_shield::generate(
+            handlebars,
+            brawl_mods,
+            assets,
+            site,
+            &OutOfShield::new(brawl_mods),
+        ),
+        [_, _, "index.html"] => fighter::generate(
+            handlebars,
+            brawl_mods,
+            assets,
+            site,
+            &OutOfShield::new(brawl_mods),
+        ),
+        [_, _, "attributes.html"] => attributes::generate(handlebars, brawl_mods, assets, site),
+        [_, _, "kill_percents.html"] => {
+            kill_percents::generate(handlebars, brawl_mods, assets, site, &config.blast_zone)
+        }
+        [_, _, "variables.html"] => variables::generate(handlebars, brawl_mods, assets, site),
+        [_, _, "frame_data.csv"] => frame_data::generate(brawl_mods, site),
+        [_, _, "actions", "index.html"] => actions::generate(handlebars, brawl_mods, assets, site),
+        [_, _, "actions", "graph.html" | "graph.dot"] => {
+            action_graph::generate(handlebars, brawl_mods, assets, site)
+        }
+        [_, _, "actions", _] => action::generate(handlebars, brawl_mods, assets, site),
+        [_, _, "subactions", "index.html"] => {
+            subactions::generate(handlebars, brawl_mods, assets, site)
+        }
+        [_, _, "subactions", file] if file.ends_with(".json") => {
+            frame_data::generate(brawl_mods, site)
+        }
+        [_, _, "subactions", _] => subaction::generate(
+            handlebars,
+            brawl_mods,
+            assets,
+            site,
+            config.legacy_renderer,
+            &config.blast_zone,
+        ),
+        [_, _, "scripts", "index.html"] => scripts::generate(handlebars, brawl_mods, assets, site),
+        [_, _, "scripts" | "scripts_common", _] => {
+            script::generate(handlebars, brawl_mods, assets, site)
+        }
+        _ => {}
+    }
+}
+
 #[derive(Clone, Serialize)]
 pub struct NavLink {
     pub name: String,
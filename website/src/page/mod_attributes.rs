@@ -0,0 +1,229 @@
+use crate::assets::AssetPaths;
+use crate::brawl_data::{BrawlMod, BrawlMods};
+use crate::output::OutDir;
+use crate::page::NavLink;
+use crate::page::attributes::attributes_to_strings;
+use crate::site::Site;
+use handlebars::Handlebars;
+
+/// Columns of the table are grouped by these headings, attributes not listed here are grouped under "Misc".
+#[rustfmt::skip]
+const ATTRIBUTE_GROUPS: &[(&str, &[&str])] = &[
+    ("Ground Movement", &[
+        "walk init vel", "walk acc", "walk max vel", "ground friction", "dash init vel", "dash run acc a",
+        "dash run acc b", "dash run term vel", "grounded max x vel", "dash cancel frame window",
+    ]),
+    ("Air Movement", &[
+        "gravity", "term vel", "fastfall velocity", "air mobility a", "air mobility b", "air x term vel",
+        "air friction x", "air friction y", "air y term vel", "air x term vel hard", "term vel hard frames",
+        "glide frame window",
+    ]),
+    ("Jumps", &[
+        "jump squat frames", "jump x init vel", "jump y init vel", "jump y init vel short", "jump x vel ground mult",
+        "jump x init term vel", "num jumps", "air jump x mult", "air jump y mult", "footstool init vel",
+        "footstool init vel short", "edge jump x vel", "edge jump y vel", "walljump x vel", "walljump y vel",
+    ]),
+    ("Landing", &[
+        "light landing lag", "normal landing lag", "nair landing lag", "fair landing lag", "bair landing lag",
+        "uair landing lag", "dair landing lag",
+    ]),
+    ("Shield", &[
+        "shield size", "shield strength", "shield break vel", "guard on max momentum",
+    ]),
+    ("Body", &[
+        "weight", "size", "results screen size", "respawn platform size", "tag height value",
+    ]),
+    ("Attacks", &[
+        "jab2 window", "jab3 window", "ftilt2 window", "ftilt3 window", "fsmash2 window", "flip dir frame",
+        "meteor cancel delay",
+    ]),
+    ("Items", &[
+        "item throw strength", "projectile item move speed", "projectile item move speed dash f",
+        "projectile item move speed dash b",
+    ]),
+];
+
+/// Generates `/{mod}/attributes.html`, a table of every attribute of every fighter in the mod.
+pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths, site: &Site) {
+    for brawl_mod in &brawl_mods.mods {
+        if brawl_mod.fighters.is_empty() {
+            continue;
+        }
+        let dir = OutDir::new(&brawl_mod.name);
+        site.page(
+            handlebars,
+            "mod_attributes",
+            &dir,
+            "attributes.html",
+            &[&brawl_mod.hash],
+            || gen_page(brawl_mods, brawl_mod, assets),
+        );
+    }
+}
+
+/// Must only be called for mods with fighters.
+fn gen_page<'a>(
+    brawl_mods: &BrawlMods,
+    brawl_mod: &BrawlMod,
+    assets: &'a AssetPaths,
+) -> ModAttributesPage<'a> {
+    let fighters: Vec<_> = brawl_mod
+        .fighters
+        .iter()
+        .map(|x| {
+            (
+                &x.fighter.name,
+                attributes_to_strings(&x.fighter.attributes),
+            )
+        })
+        .collect();
+
+    // Every fighter has the same attributes in the same order, so use the first fighter to lay out the columns.
+    let (_, first) = &fighters[0];
+    let mut groups: Vec<ColumnGroup> = ATTRIBUTE_GROUPS
+        .iter()
+        .map(|(name, _)| ColumnGroup {
+            name,
+            span: 0,
+            columns: vec![],
+        })
+        .collect();
+    groups.push(ColumnGroup {
+        name: "Misc",
+        span: 0,
+        columns: vec![],
+    });
+    for (index, attribute) in first.iter().enumerate() {
+        let group = ATTRIBUTE_GROUPS
+            .iter()
+            .position(|(_, names)| names.contains(&attribute.name))
+            .unwrap_or(ATTRIBUTE_GROUPS.len());
+        groups[group].columns.push(Column {
+            name: attribute.name,
+            index,
+        });
+    }
+    groups.retain(|x| !x.columns.is_empty());
+    for group in &mut groups {
+        group.span = group.columns.len();
+        group.columns.sort_by_key(|column| {
+            ATTRIBUTE_GROUPS
+                .iter()
+                .flat_map(|(_, names)| names.iter())
+                .position(|x| *x == column.name)
+        });
+    }
+    let columns: Vec<_> = groups.iter().flat_map(|x| x.columns.iter()).collect();
+
+    let values: Vec<Vec<Option<f64>>> = fighters
+        .iter()
+        .map(|(_, attributes)| {
+            attributes
+                .iter()
+                .map(|x| x.value.parse::<f64>().ok().filter(|x| x.is_finite()))
+                .collect()
+        })
+        .collect();
+
+    let rows = fighters
+        .iter()
+        .enumerate()
+        .map(|(fighter_index, (name, attributes))| {
+            let link = format!("/{}/attributes.html", brawl_mod.fighter_path(name));
+            let cells = columns
+                .iter()
+                .map(|column| {
+                    let value = values[fighter_index][column.index];
+                    let column_values: Vec<f64> =
+                        values.iter().filter_map(|x| x[column.index]).collect();
+                    let rank = value.map(|value| Rank::new(value, &column_values));
+                    Cell {
+                        value: attributes[column.index].value.clone(),
+                        link: link.clone(),
+                        rank,
+                    }
+                })
+                .collect();
+            Row {
+                fighter: NavLink {
+                    name: name.to_string(),
+                    link: format!("/{}", brawl_mod.fighter_path(name)),
+                    current: false,
+                },
+                cells,
+            }
+        })
+        .collect();
+
+    ModAttributesPage {
+        assets,
+        mod_links: brawl_mods.gen_mod_links(brawl_mod.name.clone()),
+        title: format!("{} - Attributes", brawl_mod.name),
+        groups,
+        rows,
+    }
+}
+
+#[derive(Serialize)]
+struct ColumnGroup {
+    name: &'static str,
+    /// Number of columns in the group
+    span: usize,
+    columns: Vec<Column>,
+}
+
+#[derive(Serialize)]
+struct Column {
+    name: &'static str,
+    /// Index into the `attributes_to_strings` list
+    #[serde(skip)]
+    index: usize,
+}
+
+#[derive(Serialize)]
+struct Row {
+    fighter: NavLink,
+    cells: Vec<Cell>,
+}
+
+#[derive(Serialize)]
+struct Cell {
+    value: String,
+    link: String,
+    rank: Option<Rank>,
+}
+
+/// Where the value places among all fighters, rank 1 is the highest value.
+#[derive(Serialize)]
+struct Rank {
+    rank: usize,
+    of: usize,
+    /// The percentage of other fighters with a lower value
+    percentile: usize,
+}
+
+impl Rank {
+    fn new(value: f64, all: &[f64]) -> Rank {
+        let higher = all.iter().filter(|x| **x > value).count();
+        let lower = all.iter().filter(|x| **x < value).count();
+        let others = all.len().saturating_sub(1);
+        Rank {
+            rank: higher + 1,
+            of: all.len(),
+            percentile: if others == 0 {
+                100
+            } else {
+                (lower as f64 / others as f64 * 100.0).round() as usize
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ModAttributesPage<'a> {
+    assets: &'a AssetPaths,
+    mod_links: Vec<NavLink>,
+    title: String,
+    groups: Vec<ColumnGroup>,
+    rows: Vec<Row>,
+}
@@ -0,0 +1,217 @@
+use crate::assets::AssetPaths;
+use crate::brawl_data::{BrawlFighter, BrawlMod, BrawlMods};
+use crate::output::OutDir;
+use crate::page::NavLink;
+use crate::site::Site;
+use handlebars::Handlebars;
+use rayon::prelude::*;
+use std::sync::OnceLock;
+
+/// How an option is started while shielding.
+enum Start {
+    /// Directly out of shield e.g. a shield grab.
+    Shield,
+    /// Cancels jump squat on its first frame e.g. up smash and up special.
+    JumpCancel,
+    /// After jump squat has finished e.g. aerials.
+    Jump,
+}
+
+/// The name of each option, the subactions that could be used for it (the first one with hitboxes is used) and how it is started.
+#[rustfmt::skip]
+const OPTIONS: &[(&str, &[&str], Start)] = &[
+    ("Grab",        &["Catch"],                        Start::Shield),
+    ("Up Smash",    &["AttackHi4"],                    Start::JumpCancel),
+    ("Up Special",  &["SpecialHi", "SpecialHiStart"],  Start::JumpCancel),
+    ("Neutral Air", &["AttackAirN"],                   Start::Jump),
+    ("Forward Air", &["AttackAirF"],                   Start::Jump),
+    ("Back Air",    &["AttackAirB"],                   Start::Jump),
+    ("Up Air",      &["AttackAirHi"],                  Start::Jump),
+    ("Down Air",    &["AttackAirLw"],                  Start::Jump),
+];
+
+/// Up smash is charged from a separate subaction that plays before the subaction with the hitboxes.
+const UP_SMASH_START: &str = "AttackHi4Start";
+
+#[derive(Serialize)]
+pub struct OutOfShieldOption {
+    pub name: &'static str,
+    pub subaction: NavLink,
+    /// The frame the first hitbox is active, counting from the first frame the fighter can act out of shield.
+    pub frame: i32,
+}
+
+/// The out of shield options of every fighter, shared by the out of shield and fighter pages.
+/// A mod's options are only calculated once a page that needs them is stale.
+pub struct OutOfShield<'a> {
+    brawl_mods: &'a BrawlMods,
+    mods: Vec<OnceLock<Vec<Vec<OutOfShieldOption>>>>,
+}
+
+impl<'a> OutOfShield<'a> {
+    pub fn new(brawl_mods: &'a BrawlMods) -> Self {
+        OutOfShield {
+            brawl_mods,
+            mods: brawl_mods.mods.iter().map(|_| OnceLock::new()).collect(),
+        }
+    }
+
+    /// The options of every fighter in the mod at `mod_index`, in the same order as `BrawlMod::fighters`.
+    pub fn fighters(&self, mod_index: usize) -> &[Vec<OutOfShieldOption>] {
+        self.mods[mod_index].get_or_init(|| {
+            let brawl_mod = &self.brawl_mods.mods[mod_index];
+            brawl_mod
+                .fighters
+                .par_iter()
+                .map(|fighter| fighter_options(brawl_mod, fighter))
+                .collect()
+        })
+    }
+
+    /// The frame of the fastest out of shield option of every fighter in the mod at `mod_index`, skipping fighters without any options.
+    pub fn fastest_options(&self, mod_index: usize) -> Vec<i32> {
+        self.fighters(mod_index)
+            .iter()
+            .filter_map(|options| Some(options.first()?.frame))
+            .collect()
+    }
+}
+
+/// Every out of shield option the fighter has, fastest first.
+fn fighter_options(brawl_mod: &BrawlMod, fighter: &BrawlFighter) -> Vec<OutOfShieldOption> {
+    let subactions = &fighter.fighter.subactions;
+    let jump_squat = fighter.fighter.attributes.jump_squat_frames;
+    let mut options: Vec<_> = OPTIONS
+        .iter()
+        .filter_map(|(name, subaction_names, start)| {
+            subaction_names.iter().find_map(|subaction_name| {
+                let subaction = subactions.iter().find(|x| &x.name == subaction_name)?;
+                // Same as the start of the first range of `SubactionFrameData::hitboxes_active`.
+                let first_hitbox = subaction
+                    .frames
+                    .iter()
+                    .position(|x| !x.hit_boxes.is_empty())?
+                    as i32
+                    + 1;
+                let windup = match *subaction_name {
+                    "AttackHi4" => subactions
+                        .iter()
+                        .find(|x| x.name == UP_SMASH_START)
+                        .map_or(0, |x| x.frames.len() as i32),
+                    _ => 0,
+                };
+                Some(OutOfShieldOption {
+                    name,
+                    subaction: NavLink {
+                        name: subaction.name.clone(),
+                        link: format!(
+                            "/{}/subactions/{}.html",
+                            brawl_mod.fighter_path(&fighter.fighter.name),
+                            subaction.name
+                        ),
+                        current: false,
+                    },
+                    frame: option_frame(start, first_hitbox, windup, jump_squat),
+                })
+            })
+        })
+        .collect();
+    options.sort_by_key(|x| x.frame);
+    options
+}
+
+/// The frame the option's first hitbox is active out of shield,
+/// from the frame its subaction's first hitbox is active and the length of any subaction played before it.
+fn option_frame(start: &Start, first_hitbox: i32, windup: i32, jump_squat: i32) -> i32 {
+    let startup = first_hitbox + windup;
+    match start {
+        Start::Shield => startup,
+        Start::JumpCancel => startup + 1,
+        Start::Jump => jump_squat + startup,
+    }
+}
+
+/// Generates `/{mod}/out_of_shield.html`, the out of shield options of every fighter in the mod.
+pub fn generate(
+    handlebars: &Handlebars,
+    brawl_mods: &BrawlMods,
+    assets: &AssetPaths,
+    site: &Site,
+    out_of_shield: &OutOfShield,
+) {
+    for (mod_index, brawl_mod) in brawl_mods.mods.iter().enumerate() {
+        let dir = OutDir::new(&brawl_mod.name);
+        site.page(
+            handlebars,
+            "out_of_shield",
+            &dir,
+            "out_of_shield.html",
+            &[&brawl_mod.hash],
+            || {
+                let mut rows: Vec<_> = brawl_mod
+                    .fighters
+                    .iter()
+                    .zip(out_of_shield.fighters(mod_index))
+                    .map(|(fighter, options)| Row {
+                        fighter: NavLink {
+                            name: fighter.fighter.name.clone(),
+                            link: format!("/{}", brawl_mod.fighter_path(&fighter.fighter.name)),
+                            current: false,
+                        },
+                        jump_squat: fighter.fighter.attributes.jump_squat_frames,
+                        options,
+                    })
+                    .collect();
+                rows.sort_by_key(|x| x.options.first().map(|x| x.frame).unwrap_or(i32::MAX));
+
+                OutOfShieldPage {
+                    assets,
+                    mod_links: brawl_mods.gen_mod_links(brawl_mod.name.clone()),
+                    title: format!("{} - Out of Shield Options", brawl_mod.name),
+                    rows,
+                }
+            },
+        );
+    }
+}
+
+#[derive(Serialize)]
+struct Row<'a> {
+    fighter: NavLink,
+    jump_squat: i32,
+    options: &'a [OutOfShieldOption],
+}
+
+#[derive(Serialize)]
+struct OutOfShieldPage<'a> {
+    assets: &'a AssetPaths,
+    mod_links: Vec<NavLink>,
+    title: String,
+    rows: Vec<Row<'a>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shield_grab() {
+        assert_eq!(option_frame(&Start::Shield, 7, 0, 5), 7);
+    }
+
+    #[test]
+    fn jump_cancel_adds_a_frame() {
+        assert_eq!(option_frame(&Start::JumpCancel, 8, 0, 5), 9);
+    }
+
+    #[test]
+    fn jump_adds_jump_squat() {
+        assert_eq!(option_frame(&Start::Jump, 3, 0, 5), 8);
+    }
+
+    #[test]
+    fn up_smash_windup() {
+        // AttackHi4Start plays for 4 frames before AttackHi4 with its first hitbox on frame 6.
+        assert_eq!(option_frame(&Start::JumpCancel, 6, 4, 5), 11);
+    }
+}
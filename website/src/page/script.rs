@@ -1,12 +1,14 @@
 use crate::assets::AssetPaths;
-use crate::brawl_data::BrawlMods;
+use crate::brawl_data::{BrawlMods, ScriptCallerLinks};
 use crate::output::OutDir;
 use crate::page::NavLink;
-use crate::process_scripts;
+use crate::process_scripts::{self, ScriptBlock};
+use crate::script_callers::ScriptKey;
+use crate::site::Site;
 use handlebars::Handlebars;
 use rayon::prelude::*;
 
-pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths) {
+pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths, site: &Site) {
     for brawl_mod in &brawl_mods.mods {
         let mod_links = brawl_mods.gen_mod_links(brawl_mod.name.clone());
 
@@ -16,7 +18,7 @@ pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetP
                 let other_name = &other_fighter.fighter.name;
                 fighter_links.push(NavLink {
                     name: other_name.clone(),
-                    link: format!("/{}/{}/scripts", brawl_mod.name, fighter.fighter.name),
+                    link: format!("/{}/scripts", brawl_mod.fighter_path(&fighter.fighter.name)),
                     current: other_name == &fighter.fighter.name,
                 });
             }
@@ -26,37 +28,50 @@ pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetP
                 .scripts_fragment_fighter
                 .par_iter()
                 .for_each(|script| {
-                    let page = ScriptPage {
-                        mod_links: &mod_links,
-                        title: format!(
-                            "{} - {} - Subroutine - 0x{:x}",
-                            brawl_mod.name, fighter.fighter.name, script.offset
-                        ),
-                        script_fighter_links: brawl_mod
-                            .gen_script_fragment_fighter_links(&fighter.fighter, script.offset),
-                        script_common_links: brawl_mod
-                            .gen_script_fragment_common_links(&fighter.fighter, 0),
-                        script_section_links: brawl_mod
-                            .gen_script_section_links(&fighter.fighter, ""),
-                        script: process_scripts::process_events(
-                            &script.block.events,
-                            false,
-                            brawl_mod,
-                            fighter,
-                        ),
-                        fighter_links: &fighter_links,
-                        assets,
-                    };
-
                     let dir = OutDir::new(&format!(
-                        "{}/{}/scripts",
-                        brawl_mod.name, fighter.fighter.name
+                        "{}/scripts",
+                        brawl_mod.fighter_path(&fighter.fighter.name)
                     ));
-                    let file = dir.compressed_file_writer(&format!("0x{:x}.html", script.offset));
-                    handlebars.render_to_write("script", &page, file).unwrap();
-                    info!(
-                        "{} {} 0x{:x}",
-                        brawl_mod.name, fighter.fighter.name, script.offset
+                    let file_name = format!("0x{:x}.html", script.offset);
+                    site.page(
+                        handlebars,
+                        "script",
+                        &dir,
+                        &file_name,
+                        &[&fighter.hash],
+                        || {
+                            info!(
+                                "{} {} 0x{:x}",
+                                brawl_mod.name, fighter.fighter.name, script.offset
+                            );
+                            ScriptPage {
+                                mod_links: &mod_links,
+                                title: format!(
+                                    "{} - {} - Subroutine - 0x{:x}",
+                                    brawl_mod.name, fighter.fighter.name, script.offset
+                                ),
+                                script_fighter_links: brawl_mod.gen_script_fragment_fighter_links(
+                                    &fighter.fighter,
+                                    script.offset,
+                                ),
+                                script_common_links: brawl_mod
+                                    .gen_script_fragment_common_links(&fighter.fighter, 0),
+                                script_section_links: brawl_mod
+                                    .gen_script_section_links(&fighter.fighter, ""),
+                                script: process_scripts::process_events(
+                                    &script.block.events,
+                                    false,
+                                    brawl_mod,
+                                    fighter,
+                                ),
+                                callers: brawl_mod.gen_script_callers(
+                                    fighter,
+                                    &ScriptKey::Fighter(script.offset),
+                                ),
+                                fighter_links: &fighter_links,
+                                assets,
+                            }
+                        },
                     );
                 });
 
@@ -65,37 +80,48 @@ pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetP
                 .scripts_fragment_common
                 .par_iter()
                 .for_each(|script| {
-                    let page = ScriptPage {
-                        mod_links: &mod_links,
-                        title: format!(
-                            "{} - {} - Common Subroutine 0x{:x}",
-                            brawl_mod.name, fighter.fighter.name, script.offset
-                        ),
-                        script_fighter_links: brawl_mod
-                            .gen_script_fragment_fighter_links(&fighter.fighter, 0),
-                        script_common_links: brawl_mod
-                            .gen_script_fragment_common_links(&fighter.fighter, script.offset),
-                        script_section_links: brawl_mod
-                            .gen_script_section_links(&fighter.fighter, ""),
-                        script: process_scripts::process_events(
-                            &script.block.events,
-                            true,
-                            brawl_mod,
-                            fighter,
-                        ),
-                        fighter_links: &fighter_links,
-                        assets,
-                    };
-
                     let dir = OutDir::new(&format!(
-                        "{}/{}/scripts_common",
-                        brawl_mod.name, fighter.fighter.name
+                        "{}/scripts_common",
+                        brawl_mod.fighter_path(&fighter.fighter.name)
                     ));
-                    let file = dir.compressed_file_writer(&format!("0x{:x}.html", script.offset));
-                    handlebars.render_to_write("script", &page, file).unwrap();
-                    info!(
-                        "{} {} 0x{:x}",
-                        brawl_mod.name, fighter.fighter.name, script.offset
+                    let file_name = format!("0x{:x}.html", script.offset);
+                    site.page(
+                        handlebars,
+                        "script",
+                        &dir,
+                        &file_name,
+                        &[&fighter.hash],
+                        || {
+                            info!(
+                                "{} {} 0x{:x}",
+                                brawl_mod.name, fighter.fighter.name, script.offset
+                            );
+                            ScriptPage {
+                                mod_links: &mod_links,
+                                title: format!(
+                                    "{} - {} - Common Subroutine 0x{:x}",
+                                    brawl_mod.name, fighter.fighter.name, script.offset
+                                ),
+                                script_fighter_links: brawl_mod
+                                    .gen_script_fragment_fighter_links(&fighter.fighter, 0),
+                                script_common_links: brawl_mod.gen_script_fragment_common_links(
+                                    &fighter.fighter,
+                                    script.offset,
+                                ),
+                                script_section_links: brawl_mod
+                                    .gen_script_section_links(&fighter.fighter, ""),
+                                script: process_scripts::process_events(
+                                    &script.block.events,
+                                    true,
+                                    brawl_mod,
+                                    fighter,
+                                ),
+                                callers: brawl_mod
+                                    .gen_script_callers(fighter, &ScriptKey::Common(script.offset)),
+                                fighter_links: &fighter_links,
+                                assets,
+                            }
+                        },
                     );
                 });
 
@@ -104,37 +130,48 @@ pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetP
                 .scripts_section
                 .par_iter()
                 .for_each(|script| {
-                    let page = ScriptPage {
-                        mod_links: &mod_links,
-                        title: format!(
-                            "{} - {} - Common Section {}",
-                            brawl_mod.name, fighter.fighter.name, script.name
-                        ),
-                        script_fighter_links: brawl_mod
-                            .gen_script_fragment_fighter_links(&fighter.fighter, 0),
-                        script_common_links: brawl_mod
-                            .gen_script_fragment_common_links(&fighter.fighter, 0),
-                        script_section_links: brawl_mod
-                            .gen_script_section_links(&fighter.fighter, &script.name),
-                        script: process_scripts::process_events(
-                            &script.script.block.events,
-                            true,
-                            brawl_mod,
-                            fighter,
-                        ),
-                        fighter_links: &fighter_links,
-                        assets,
-                    };
-
                     let dir = OutDir::new(&format!(
-                        "{}/{}/scripts_common",
-                        brawl_mod.name, fighter.fighter.name
+                        "{}/scripts_common",
+                        brawl_mod.fighter_path(&fighter.fighter.name)
                     ));
-                    let file = dir.compressed_file_writer(&format!("{}.html", script.name));
-                    handlebars.render_to_write("script", &page, file).unwrap();
-                    info!(
-                        "{} {} {}",
-                        brawl_mod.name, fighter.fighter.name, script.name
+                    let file_name = format!("{}.html", script.name);
+                    site.page(
+                        handlebars,
+                        "script",
+                        &dir,
+                        &file_name,
+                        &[&fighter.hash],
+                        || {
+                            info!(
+                                "{} {} {}",
+                                brawl_mod.name, fighter.fighter.name, script.name
+                            );
+                            ScriptPage {
+                                mod_links: &mod_links,
+                                title: format!(
+                                    "{} - {} - Common Section {}",
+                                    brawl_mod.name, fighter.fighter.name, script.name
+                                ),
+                                script_fighter_links: brawl_mod
+                                    .gen_script_fragment_fighter_links(&fighter.fighter, 0),
+                                script_common_links: brawl_mod
+                                    .gen_script_fragment_common_links(&fighter.fighter, 0),
+                                script_section_links: brawl_mod
+                                    .gen_script_section_links(&fighter.fighter, &script.name),
+                                script: process_scripts::process_events(
+                                    &script.script.block.events,
+                                    true,
+                                    brawl_mod,
+                                    fighter,
+                                ),
+                                callers: brawl_mod.gen_script_callers(
+                                    fighter,
+                                    &ScriptKey::Section(script.name.clone()),
+                                ),
+                                fighter_links: &fighter_links,
+                                assets,
+                            }
+                        },
                     );
                 });
         }
@@ -150,5 +187,6 @@ pub struct ScriptPage<'a> {
     script_common_links: Vec<NavLink>,
     script_section_links: Vec<NavLink>,
     title: String,
-    script: String,
+    script: ScriptBlock,
+    callers: ScriptCallerLinks,
 }
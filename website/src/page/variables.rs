@@ -1,27 +1,170 @@
 use crate::assets::AssetPaths;
-use crate::brawl_data::BrawlMods;
+use crate::brawl_data::{BrawlFighter, BrawlMod, BrawlMods};
 use crate::output::OutDir;
 use crate::page::NavLink;
+use crate::site::Site;
+use crate::variable_key::VariableKey;
+use crate::variable_names::{self, Access, NamedVariable};
+use brawllib_rs::script_ast::EventAst;
 use handlebars::Handlebars;
 use rayon::prelude::*;
+use std::collections::BTreeMap;
 
-pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths) {
+pub fn generate(handlebars: &Handlebars, brawl_mods: &BrawlMods, assets: &AssetPaths, site: &Site) {
     for brawl_mod in &brawl_mods.mods {
         let mod_links = brawl_mods.gen_mod_links(brawl_mod.name.clone());
-        brawl_mod.fighters.par_iter().for_each(|fighter| {
-            let fighter = &fighter.fighter;
-            let page = VariablesPage {
-                mod_links: &mod_links,
-                fighter_links: brawl_mod.gen_fighter_links(&fighter.name),
-                title: format!("{} - {} - Variables", brawl_mod.name, fighter.name),
-                assets,
-            };
+        brawl_mod.fighters.par_iter().for_each(|brawl_fighter| {
+            let fighter = &brawl_fighter.fighter;
+            let dir = OutDir::new(&brawl_mod.fighter_path(&fighter.name));
+            site.page(
+                handlebars,
+                "variables",
+                &dir,
+                "variables.html",
+                &[&brawl_fighter.hash],
+                || VariablesPage {
+                    mod_links: &mod_links,
+                    fighter_links: brawl_mod.gen_fighter_links(&fighter.name),
+                    title: format!("{} - {} - Variables", brawl_mod.name, fighter.name),
+                    variables: gen_variables(brawl_mod, brawl_fighter),
+                    assets,
+                },
+            );
+        });
+    }
+}
+
+/// Walks every script the fighter has and records which scripts set and read each variable.
+fn gen_variables(brawl_mod: &BrawlMod, brawl_fighter: &BrawlFighter) -> Vec<Variable> {
+    let fighter = &brawl_fighter.fighter;
+    let mut variables = VariableUses::default();
+
+    for action in &fighter.actions {
+        let link = format!(
+            "/{}/actions/{}.html",
+            brawl_mod.fighter_path(&fighter.name),
+            action.name
+        );
+        variables.process_events(
+            &action.script_entry.block.events,
+            &NavLink {
+                name: format!("{} Entry", action.name),
+                link: format!("{link}#script-entry"),
+                current: false,
+            },
+        );
+        variables.process_events(
+            &action.script_exit.block.events,
+            &NavLink {
+                name: format!("{} Exit", action.name),
+                link: format!("{link}#script-exit"),
+                current: false,
+            },
+        );
+    }
+
+    for subaction in &fighter.subactions {
+        let link = format!(
+            "/{}/subactions/{}.html",
+            brawl_mod.fighter_path(&fighter.name),
+            subaction.name
+        );
+        let scripts = &subaction.scripts;
+        for (script, name, id) in [
+            (&scripts.script_main, "Main", "script-main"),
+            (&scripts.script_gfx, "GFX", "script-gfx"),
+            (&scripts.script_sfx, "SFX", "script-sfx"),
+            (&scripts.script_other, "Other", "script-other"),
+        ] {
+            variables.process_events(
+                &script.block.events,
+                &NavLink {
+                    name: format!("{} {}", subaction.name, name),
+                    link: format!("{link}#{id}"),
+                    current: false,
+                },
+            );
+        }
+    }
+
+    for script in &fighter.scripts_fragment_fighter {
+        variables.process_events(
+            &script.block.events,
+            &NavLink {
+                name: format!("Subroutine 0x{:x}", script.offset),
+                link: format!(
+                    "/{}/scripts/0x{:x}.html",
+                    brawl_mod.fighter_path(&fighter.name),
+                    script.offset
+                ),
+                current: false,
+            },
+        );
+    }
+
+    for script in &fighter.scripts_fragment_common {
+        variables.process_events(
+            &script.block.events,
+            &NavLink {
+                name: format!("Common Subroutine 0x{:x}", script.offset),
+                link: format!(
+                    "/{}/scripts_common/0x{:x}.html",
+                    brawl_mod.fighter_path(&fighter.name),
+                    script.offset
+                ),
+                current: false,
+            },
+        );
+    }
 
-            let file = OutDir::new(&format!("{}/{}", brawl_mod.name, fighter.name))
-                .compressed_file_writer("variables.html");
-            handlebars
-                .render_to_write("variables", &page, file)
-                .unwrap();
+    for script in &fighter.scripts_section {
+        variables.process_events(
+            &script.script.block.events,
+            &NavLink {
+                name: format!("Section {}", script.name),
+                link: format!(
+                    "/{}/scripts_common/{}.html",
+                    brawl_mod.fighter_path(&fighter.name),
+                    script.name
+                ),
+                current: false,
+            },
+        );
+    }
+
+    variables
+        .uses
+        .into_iter()
+        .map(|(key, uses)| Variable {
+            variable: brawl_fighter.variable_names.get_key(key),
+            set_by: uses.set_by,
+            read_by: uses.read_by,
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct VariableUses {
+    uses: BTreeMap<VariableKey, Uses>,
+}
+
+#[derive(Default)]
+struct Uses {
+    set_by: Vec<NavLink>,
+    read_by: Vec<NavLink>,
+}
+
+impl VariableUses {
+    fn process_events(&mut self, events: &[EventAst], script: &NavLink) {
+        variable_names::walk_variables(events, &mut |variable, access| {
+            let uses = self.uses.entry(VariableKey::new(variable)).or_default();
+            let scripts = match access {
+                Access::Set => &mut uses.set_by,
+                Access::Read => &mut uses.read_by,
+            };
+            if !scripts.iter().any(|x| x.link == script.link) {
+                scripts.push(script.clone());
+            }
         });
     }
 }
@@ -32,4 +175,12 @@ struct VariablesPage<'a> {
     mod_links: &'a [NavLink],
     fighter_links: Vec<NavLink>,
     title: String,
+    variables: Vec<Variable>,
+}
+
+#[derive(Serialize)]
+struct Variable {
+    variable: NamedVariable,
+    set_by: Vec<NavLink>,
+    read_by: Vec<NavLink>,
 }
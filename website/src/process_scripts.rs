@@ -1,227 +1,957 @@
-use brawllib_rs::script::Argument;
-use brawllib_rs::script_ast::variable_ast::{
-    InternalConstantInt, LongtermAccessBool, LongtermAccessFloat, LongtermAccessInt,
-    RandomAccessBool, RandomAccessFloat, RandomAccessInt, VariableAst,
-};
+//! Renders scripts as a `ScriptBlock`, lines of typed tokens that `templates/script_block.html.hbs` renders with syntax highlighting.
+//! The model is plain data, so the same tokens are also rendered as text e.g. for the requirements in the action graph.
+
+use brawllib_rs::script::{Argument, Event, Offset, Requirement};
+use brawllib_rs::script_ast::variable_ast::VariableAst;
 use brawllib_rs::script_ast::{
-    BinaryExpression, EventAst, Expression, FloatValue, ForLoop, IfStatement, Interrupt,
-    Iterations, UnaryExpression,
+    ApplyThrow, BinaryExpression, ComparisonOperator, EventAst, Expression, FloatValue, ForLoop,
+    HitBoxArguments, IfStatement, Interrupt, Iterations, UnaryExpression,
 };
+use serde::Serialize;
+use serde_json::{Map, Value};
 
 use crate::brawl_data::{BrawlFighter, BrawlMod};
+use crate::event_definitions::{ArgumentType, EventDefinition, EventKey};
+use crate::link_check;
+use crate::variable_names::VariableNames;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ScriptBlock {
+    pub lines: Vec<ScriptLine>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ScriptLine {
+    /// Counts every line of the script in order, including the lines of nested blocks.
+    pub number: usize,
+    pub id: Option<String>,
+    pub tokens: Vec<Token>,
+    /// The body of a loop or a branch of an if statement.
+    pub block: Option<ScriptBlock>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    /// Only set for `TokenKind::Link`
+    pub href: Option<String>,
+    /// Shown when hovering over the token
+    pub title: Option<String>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenKind {
+    /// Control flow e.g. `if`, `else` and `loop`
+    Keyword,
+    Event,
+    /// The name of an argument, followed by its value.
+    Argument,
+    /// Separators and brackets, rendered without highlighting.
+    Punctuation,
+    Number,
+    /// Bools, enum variants and other values that aren't numbers.
+    Value,
+    Variable,
+    Requirement,
+    Operator,
+    /// A script, action or subaction in another page.
+    Link,
+}
 
 pub fn process_events(
     events: &[EventAst],
     common: bool,
     brawl_mod: &BrawlMod,
     fighter: &BrawlFighter,
-) -> String {
-    let script_lookup = if common {
-        &fighter.script_lookup_common
-    } else {
-        &fighter.script_lookup
-    };
-
-    let mut result = String::from("<ol>");
-    for event in events {
+) -> ScriptBlock {
+    Renderer::new(common, brawl_mod, fighter).block(events, None)
+}
+
+/// Same as `process_events` but every line is given an id so that `fighter_renderer` can highlight the events as they run.
+/// e.g. with the prefix `event-main` the third event is `event-main-2` and the first event in its then branch is `event-main-2-0`.
+/// Events in an else branch are prefixed with `-else` e.g. `event-main-2-else-0`.
+pub fn process_events_with_ids(
+    events: &[EventAst],
+    brawl_mod: &BrawlMod,
+    fighter: &BrawlFighter,
+    id_prefix: &str,
+) -> ScriptBlock {
+    Renderer::new(false, brawl_mod, fighter).block(events, Some(id_prefix))
+}
+
+pub fn process_expression(expr: &Expression, variable_names: &VariableNames) -> String {
+    let mut tokens = Tokens::new(variable_names);
+    tokens.expression(expr, false);
+    tokens.text()
+}
+
+struct Renderer<'a> {
+    common: bool,
+    brawl_mod: &'a BrawlMod,
+    fighter: &'a BrawlFighter,
+    line_count: usize,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(common: bool, brawl_mod: &'a BrawlMod, fighter: &'a BrawlFighter) -> Self {
+        Renderer {
+            common,
+            brawl_mod,
+            fighter,
+            line_count: 0,
+        }
+    }
+
+    fn next_line_number(&mut self) -> usize {
+        self.line_count += 1;
+        self.line_count
+    }
+
+    fn block(&mut self, events: &[EventAst], id_prefix: Option<&str>) -> ScriptBlock {
+        let mut lines = vec![];
+        for (index, event) in events.iter().enumerate() {
+            // exclude nops
+            if let EventAst::Nop = event {
+                continue;
+            }
+            let id = id_prefix.map(|prefix| format!("{prefix}-{index}"));
+            let number = self.next_line_number();
+            let mut tokens = Tokens::new(&self.fighter.variable_names);
+            let mut block = None;
+            let mut else_branch = None;
+            match event {
+                EventAst::ForLoop(ForLoop {
+                    iterations,
+                    block: body,
+                }) => {
+                    tokens.push(TokenKind::Keyword, "loop");
+                    tokens.space();
+                    match iterations {
+                        Iterations::Finite(i) => {
+                            tokens.push(TokenKind::Number, i.to_string());
+                            tokens.space();
+                            tokens.push(TokenKind::Keyword, "times");
+                        }
+                        Iterations::Infinite => tokens.push(TokenKind::Keyword, "forever"),
+                    }
+                    block = Some(self.block(&body.events, id.as_deref()));
+                }
+                EventAst::IfStatement(IfStatement {
+                    test,
+                    then_branch,
+                    else_branch: else_,
+                }) => {
+                    tokens.push(TokenKind::Keyword, "if");
+                    tokens.space();
+                    tokens.expression(test, false);
+                    block = Some(self.block(&then_branch.events, id.as_deref()));
+                    else_branch = else_.as_ref();
+                }
+                _ => self.event(&mut tokens, event),
+            }
+            lines.push(ScriptLine {
+                number,
+                id: id.clone(),
+                tokens: tokens.tokens,
+                block,
+            });
+
+            if let Some(else_branch) = else_branch {
+                let number = self.next_line_number();
+                let else_id = id.map(|id| format!("{id}-else"));
+                let mut tokens = Tokens::new(&self.fighter.variable_names);
+                tokens.push(TokenKind::Keyword, "else");
+                lines.push(ScriptLine {
+                    number,
+                    id: None,
+                    tokens: tokens.tokens,
+                    block: Some(self.block(&else_branch.events, else_id.as_deref())),
+                });
+            }
+        }
+        ScriptBlock { lines }
+    }
+
+    fn event(&self, tokens: &mut Tokens, event: &EventAst) {
+        let fighter_path = self.brawl_mod.fighter_path(&self.fighter.fighter.name);
         match event {
-            EventAst::Nop => { } // exclude nops
-            EventAst::CreateInterrupt (Interrupt { interrupt_id, action, test }) => {
-                if let Some(action) = fighter.fighter.actions.get(*action as usize) {
-                    result.push_str(&format!("<li>CreateInterrupt {{ interrupt_id: {:?}, action: <a href='/{}/{}/actions/{}.html'>{}</a>, requirement: ({}) }}</li>",
-                        interrupt_id, brawl_mod.name, fighter.fighter.name, action.name, action.name, process_expression(test)));
+            EventAst::CreateInterrupt(Interrupt {
+                interrupt_id,
+                action,
+                test,
+            }) => {
+                tokens.event("CreateInterrupt");
+                tokens.open();
+                tokens.argument("interrupt_id");
+                tokens.serialized(interrupt_id);
+                tokens.argument("action");
+                if let Some(action) = self.fighter.fighter.actions.get(*action as usize) {
+                    tokens.link(
+                        &action.name,
+                        format!("/{fighter_path}/actions/{}.html", action.name),
+                    );
                 } else {
-                    result.push_str(&format!("<li>{:?}</li>", event));
-                    error!("Failed to lookup action for CreateInterrupt");
-                }
-            }
-            EventAst::PreviousInterruptAddRequirement { test } =>
-                result.push_str(&format!("<li>PreviousInterruptAddRequirement({})</li>", process_expression(test))),
-            EventAst::InterruptAddRequirement { interrupt_type, interrupt_id, test } =>
-                result.push_str(&format!("<li>InterruptAddRequirement {{ interrupt_type: {:?}, interrupt_id: {}, test: {} }}</li>", interrupt_type, interrupt_id, process_expression(test))),
-            EventAst::ChangeSubaction (subaction) => {
-                if let Some(subaction) = fighter.fighter.subactions.get(*subaction as usize) {
-                    result.push_str(&format!("<li>ChangeSubaction(<a href='/{}/{}/subactions/{}.html'>{}</a>)</li>",
-                        brawl_mod.name, fighter.fighter.name, subaction.name, subaction.name));
-                } else {
-                    result.push_str(&format!("<li>{:?}</li>", event));
-                    error!("Failed to lookup action for ChangeSubaction");
+                    tokens.push(TokenKind::Number, format!("0x{action:x}"));
+                    self.lookup_failed(format!(
+                        "Failed to lookup action 0x{action:x} for CreateInterrupt"
+                    ));
                 }
+                tokens.argument("requirement");
+                tokens.expression(test, false);
+                tokens.close();
             }
-            EventAst::ChangeSubactionRestartFrame (subaction) => {
-                if let Some(subaction) = fighter.fighter.subactions.get(*subaction as usize) {
-                    result.push_str(&format!("<li>ChangeSubactionRestartFrame(<a href='/{}/{}/subactions/{}.html'>{}</a>)</li>",
-                        brawl_mod.name, fighter.fighter.name, subaction.name, subaction.name));
-                } else {
-                    result.push_str(&format!("<li>{:?}</li>", event));
-                    error!("Failed to lookup action for ChangeSubactionRestartFrame");
+            EventAst::PreviousInterruptAddRequirement { test } => {
+                tokens.requirement_event("PreviousInterruptAddRequirement", test)
+            }
+            EventAst::IfStatementAnd(test) => tokens.requirement_event("IfStatementAnd", test),
+            EventAst::IfStatementOr(test) => tokens.requirement_event("IfStatementOr", test),
+            EventAst::InterruptAddRequirement {
+                interrupt_type,
+                interrupt_id,
+                test,
+            } => {
+                tokens.event("InterruptAddRequirement");
+                tokens.open();
+                tokens.argument("interrupt_type");
+                tokens.serialized(interrupt_type);
+                tokens.argument("interrupt_id");
+                tokens.push(TokenKind::Number, interrupt_id.to_string());
+                tokens.argument("requirement");
+                tokens.expression(test, false);
+                tokens.close();
+            }
+            EventAst::ChangeSubaction(subaction) => {
+                self.change_subaction(tokens, "ChangeSubaction", *subaction)
+            }
+            EventAst::ChangeSubactionRestartFrame(subaction) => {
+                self.change_subaction(tokens, "ChangeSubactionRestartFrame", *subaction)
+            }
+            EventAst::Goto(offset) => {
+                tokens.event("Goto");
+                tokens.open();
+                self.call(tokens, offset, "goto");
+                tokens.close();
+            }
+            EventAst::Subroutine(offset) => {
+                tokens.event("Subroutine");
+                tokens.open();
+                self.call(tokens, offset, "subroutine");
+                tokens.close();
+            }
+            EventAst::CallEveryFrame { thread_id, offset } => {
+                self.thread_call(tokens, "CallEveryFrame", *thread_id, offset)
+            }
+            EventAst::IndependentSubroutine { thread_id, offset } => {
+                self.thread_call(tokens, "IndependentSubroutine", *thread_id, offset)
+            }
+            EventAst::Unknown(event) => match self.brawl_mod.event_definitions.get(event) {
+                Some(definition) => tokens.defined_event(event, definition),
+                None => {
+                    tokens.event("UnknownEvent");
+                    tokens.open();
+                    tokens.argument("namespace");
+                    tokens.push(TokenKind::Number, format!("0x{:x}", event.namespace));
+                    tokens.argument("code");
+                    tokens.push(TokenKind::Number, format!("0x{:x}", event.code));
+                    tokens.argument("unk1");
+                    tokens.push(TokenKind::Number, format!("0x{:x}", event.unk1));
+                    tokens.argument("arguments");
+                    tokens.punctuation("[");
+                    for argument in &event.arguments {
+                        tokens.separate();
+                        tokens.raw_argument(argument);
+                    }
+                    tokens.punctuation("]");
+                    tokens.close();
                 }
+            },
+            _ => tokens.data_event(event),
+        }
+    }
+
+    fn change_subaction(&self, tokens: &mut Tokens, name: &str, subaction: i32) {
+        tokens.event(name);
+        tokens.open();
+        if let Some(subaction) = self.fighter.fighter.subactions.get(subaction as usize) {
+            tokens.link(
+                &subaction.name,
+                format!(
+                    "/{}/subactions/{}.html",
+                    self.brawl_mod.fighter_path(&self.fighter.fighter.name),
+                    subaction.name
+                ),
+            );
+        } else {
+            tokens.push(TokenKind::Number, format!("0x{subaction:x}"));
+            self.lookup_failed(format!(
+                "Failed to lookup subaction 0x{subaction:x} for {name}"
+            ));
+        }
+        tokens.close();
+    }
+
+    fn thread_call(&self, tokens: &mut Tokens, name: &str, thread_id: i32, offset: &Offset) {
+        tokens.event(name);
+        tokens.open();
+        tokens.argument("thread_id");
+        tokens.push(TokenKind::Number, thread_id.to_string());
+        tokens.argument("script");
+        self.call(tokens, offset, name);
+        tokens.close();
+    }
+
+    /// Links to the script called by `Goto`, `Subroutine`, `CallEveryFrame` or `IndependentSubroutine`
+    fn call(&self, tokens: &mut Tokens, offset: &Offset, kind: &str) {
+        let script_lookup = if self.common {
+            &self.fighter.script_lookup_common
+        } else {
+            &self.fighter.script_lookup
+        };
+
+        if let Some(script_info) = script_lookup.get(&offset.offset) {
+            tokens.link(&script_info.name, script_info.address.clone());
+        } else if let Some(script) = self
+            .fighter
+            .fighter
+            .scripts_section
+            .iter()
+            .find(|x| x.callers.contains(&offset.origin))
+        {
+            tokens.link(
+                &format!("External: {}", script.name),
+                format!(
+                    "/{}/scripts_common/{}.html",
+                    self.brawl_mod.fighter_path(&self.fighter.fighter.name),
+                    script.name
+                ),
+            );
+        } else {
+            tokens.argument("offset");
+            tokens.push(TokenKind::Number, format!("0x{:x}", offset.offset));
+            tokens.argument("origin");
+            tokens.push(TokenKind::Number, format!("0x{:x}", offset.origin));
+            self.lookup_failed(format!(
+                "Failed to lookup script 0x{:x} for {kind} destination",
+                offset.offset
+            ));
+        }
+    }
+
+    fn lookup_failed(&self, message: String) {
+        link_check::lookup_failed(self.brawl_mod, self.fighter, message);
+    }
+}
+
+struct Tokens<'a> {
+    tokens: Vec<Token>,
+    variable_names: &'a VariableNames,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(variable_names: &'a VariableNames) -> Self {
+        Tokens {
+            tokens: vec![],
+            variable_names,
+        }
+    }
+
+    fn push(&mut self, kind: TokenKind, text: impl Into<String>) {
+        self.push_titled(kind, text, None);
+    }
+
+    fn push_titled(&mut self, kind: TokenKind, text: impl Into<String>, title: Option<String>) {
+        self.tokens.push(Token {
+            kind,
+            text: text.into(),
+            href: None,
+            title,
+        });
+    }
+
+    fn text(&self) -> String {
+        self.tokens.iter().map(|x| x.text.as_str()).collect()
+    }
+
+    fn link(&mut self, text: &str, href: String) {
+        self.tokens.push(Token {
+            kind: TokenKind::Link,
+            text: text.to_string(),
+            href: Some(href),
+            title: None,
+        });
+    }
+
+    fn punctuation(&mut self, text: &str) {
+        self.push(TokenKind::Punctuation, text);
+    }
+
+    fn space(&mut self) {
+        self.punctuation(" ");
+    }
+
+    fn event(&mut self, name: &str) {
+        self.push(TokenKind::Event, readable_name(name));
+    }
+
+    fn open(&mut self) {
+        self.punctuation("(");
+    }
+
+    fn close(&mut self) {
+        self.punctuation(")");
+    }
+
+    /// Separates a value from the previous value, unless it is the first value in the brackets.
+    fn separate(&mut self) {
+        let first = self
+            .tokens
+            .last()
+            .is_some_and(|x| x.kind == TokenKind::Punctuation && x.text.ends_with(['(', '[']));
+        if !first {
+            self.punctuation(", ");
+        }
+    }
+
+    fn argument(&mut self, name: &str) {
+        self.separate();
+        self.push(TokenKind::Argument, name.replace('_', " "));
+        self.punctuation(": ");
+    }
+
+    /// The name of the variable, with its address in the tooltip.
+    fn variable(&mut self, variable: &VariableAst) {
+        let variable = self.variable_names.get(variable);
+        let title = variable.tooltip();
+        self.push_titled(TokenKind::Variable, variable.name, Some(title));
+    }
+
+    fn requirement(&mut self, requirement: &Requirement) {
+        let name = match requirement {
+            Requirement::Unknown(value) => format!("Unknown Requirement 0x{value:x}"),
+            _ => readable_name(&format!("{requirement:?}")),
+        };
+        self.push(TokenKind::Requirement, name);
+    }
+
+    /// Binary expressions nested in another expression are wrapped in brackets.
+    fn expression(&mut self, expr: &Expression, nested: bool) {
+        match expr {
+            Expression::Nullary(requirement) => self.requirement(requirement),
+            Expression::Unary(UnaryExpression { requirement, value }) => {
+                self.requirement(requirement);
+                self.open();
+                self.expression(value, false);
+                self.close();
             }
-            EventAst::ForLoop ( ForLoop { iterations, block } ) => {
-                let iterations = match iterations {
-                    Iterations::Finite (i) => i.to_string(),
-                    Iterations::Infinite => "Infinite".to_string(),
+            Expression::Binary(BinaryExpression {
+                left,
+                operator,
+                right,
+            }) => {
+                if nested {
+                    self.open();
+                }
+                self.expression(left, true);
+                self.space();
+                let operator = match operator {
+                    ComparisonOperator::LessThan => "<".to_string(),
+                    ComparisonOperator::LessThanOrEqual => "<=".to_string(),
+                    ComparisonOperator::Equal => "==".to_string(),
+                    ComparisonOperator::NotEqual => "!=".to_string(),
+                    ComparisonOperator::GreaterThanOrEqual => ">=".to_string(),
+                    ComparisonOperator::GreaterThan => ">".to_string(),
+                    ComparisonOperator::And => "and".to_string(),
+                    ComparisonOperator::Or => "or".to_string(),
+                    ComparisonOperator::UnknownArg(value) => format!("unknown operator {value}"),
                 };
-                result.push_str(&format!("<li>loop {} times: {}</li>", iterations, &process_events(&block.events, common, brawl_mod, fighter)));
-            }
-            EventAst::IfStatement ( IfStatement { test, then_branch, else_branch } ) => {
-                result.push_str(&format!("<li>if ({}) {}</li>", process_expression(test), &process_events(&then_branch.events, common, brawl_mod, fighter)));
-
-                if let Some(else_branch) = else_branch {
-                    result.push_str("<li>else");
-                    result.push_str(&process_events(&else_branch.events, common, brawl_mod, fighter));
-                    result.push_str("</li>");
-                }
-            }
-            EventAst::IfStatementAnd (test) =>
-                result.push_str(&format!("<li>IfStatementAnd ({})</li>", process_expression(test))),
-            EventAst::IfStatementOr (test) =>
-                result.push_str(&format!("<li>IfStatementOr ({})</li>", process_expression(test))),
-            EventAst::Goto (offset) => {
-                if let Some(script_info) = script_lookup.get(&offset.offset) {
-                    result.push_str(&format!("<li>Goto(<a href='{}'>{}</a>)</li>", script_info.address, script_info.name));
-                } else if let Some(script) = fighter.fighter.scripts_section.iter().find(|x| x.callers.contains(&offset.origin)) {
-                    result.push_str(&format!("<li>Goto(<a href='help'>External: {}</a>)</li>", script.name));
-                } else {
-                    result.push_str(&format!("<li>Goto(Offset {{ offset: 0x{:x}, origin: 0x{:x} }})</li>", offset.offset, offset.origin));
-                    error!("Failed to lookup script for goto destination");
+                self.push(TokenKind::Operator, operator);
+                self.space();
+                self.expression(right, true);
+                if nested {
+                    self.close();
                 }
             }
-            EventAst::Subroutine (offset) => {
-                if let Some(script_info) = script_lookup.get(&offset.offset) {
-                    result.push_str(&format!("<li>Subroutine(<a href='{}'>{}</a>)</li>", script_info.address, script_info.name));
-                } else if let Some(script) = fighter.fighter.scripts_section.iter().find(|x| x.callers.contains(&offset.origin)) {
-                    result.push_str(&format!("<li>Subroutine(<a href='/{}/{}/scripts_common/{}.html'>External: {}</a>)</li>", brawl_mod.name, fighter.fighter.name, script.name, script.name));
-                } else {
-                    result.push_str(&format!("<li>Subroutine(Offset {{ offset: 0x{:x}, origin: 0x{:x} }})</li>", offset.offset, offset.origin));
-                    error!("Failed to lookup script for subroutine destination");
-                }
+            Expression::Not(expr) => {
+                self.push(TokenKind::Keyword, "not");
+                self.space();
+                self.expression(expr, true);
             }
-            EventAst::CallEveryFrame { thread_id, offset } => {
-                if let Some(script_info) = script_lookup.get(&offset.offset) {
-                    result.push_str(&format!("<li>CallEveryFrame {{ thread_id: {}, script: <a href='{}'>{}</a> }}</li>", thread_id, script_info.address, script_info.name));
-                } else if let Some(script) = fighter.fighter.scripts_section.iter().find(|x| x.callers.contains(&offset.origin)) {
-                    result.push_str(&format!("<li>CallEveryFrame(<a href='/{}/{}/scripts_common/{}.html'>External: {}</a>)</li>", brawl_mod.name, fighter.fighter.name, script.name, script.name));
-                } else {
-                    result.push_str(&format!("<li>{:x?}</li>", event));
-                    error!("Failed to lookup script for CallEveryFrame destination");
+            Expression::Variable(variable) => self.variable(variable),
+            Expression::Value(value) => self.push(TokenKind::Number, value.to_string()),
+            Expression::Scalar(scalar) => self.push(TokenKind::Number, format!("{scalar:?}")),
+        }
+    }
+
+    /// An argument of an event that brawllib doesn't know the meaning of.
+    fn raw_argument(&mut self, argument: &Argument) {
+        match argument {
+            Argument::Value(value) => self.push(TokenKind::Number, value.to_string()),
+            Argument::Scalar(scalar) => self.push(TokenKind::Number, format!("{scalar:?}")),
+            Argument::Offset(offset) => {
+                self.push(TokenKind::Number, format!("0x{:x}", offset.offset))
+            }
+            Argument::Bool(value) => self.push(TokenKind::Value, value.to_string()),
+            Argument::File(file) => {
+                self.push(TokenKind::Value, "File");
+                self.open();
+                self.push(TokenKind::Number, file.to_string());
+                self.close();
+            }
+            Argument::Variable(variable) => self.variable(&VariableAst::new(variable)),
+            Argument::Requirement { flip, ty } => {
+                if *flip {
+                    self.push(TokenKind::Keyword, "not");
+                    self.space();
                 }
+                self.requirement(ty);
             }
-            EventAst::IndependentSubroutine { thread_id, offset } => {
-                if let Some(script_info) = script_lookup.get(&offset.offset) {
-                    result.push_str(&format!("<li>IndependentSubroutine {{ thread_id: {}, script: <a href='{}'>{}</a> }}</li>", thread_id, script_info.address, script_info.name));
-                } else if let Some(script) = fighter.fighter.scripts_section.iter().find(|x| x.callers.contains(&offset.origin)) {
-                    result.push_str(&format!("<li>IndependentSubroutine(<a href='/{}/{}/scripts_common/{}.html'>External: {}</a>)</li>", brawl_mod.name, fighter.fighter.name, script.name, script.name));
-                } else {
-                    result.push_str(&format!("<li>{:x?}</li>", event));
-                    error!("Failed to lookup script for IndependentSubroutine destination");
-                }
-            }
-            EventAst::IntVariableSet        { value, variable } => result.push_str(&format!("<li>IntVariableSet {{ variable: {}, value: {} }}</li>",        process_expression(&Expression::Variable(variable.clone())), value)),
-            EventAst::IntVariableAdd        { value, variable } => result.push_str(&format!("<li>IntVariableAdd {{ variable: {}, value: {} }}</li>",        process_expression(&Expression::Variable(variable.clone())), value)),
-            EventAst::IntVariableSubtract   { value, variable } => result.push_str(&format!("<li>IntVariableSubtract {{ variable: {}, value: {} }}</li>",   process_expression(&Expression::Variable(variable.clone())), value)),
-            EventAst::IntVariableIncrement  { variable        } => result.push_str(&format!("<li>IntVariableIncrement {{ variable: {} }}</li>",             process_expression(&Expression::Variable(variable.clone())))),
-            EventAst::IntVariableDecrement  { variable        } => result.push_str(&format!("<li>IntVariableDecrement {{ variable: {} }}</li>",             process_expression(&Expression::Variable(variable.clone())))),
-            EventAst::FloatVariableSet      { value, variable } => result.push_str(&format!("<li>FloatVariableSet {{ variable: {}, value: {} }}</li>",      process_expression(&Expression::Variable(variable.clone())), process_float_value(value))),
-            EventAst::FloatVariableAdd      { value, variable } => result.push_str(&format!("<li>FloatVariableAdd {{ variable: {}, value: {} }}</li>",      process_expression(&Expression::Variable(variable.clone())), process_float_value(value))),
-            EventAst::FloatVariableSubtract { value, variable } => result.push_str(&format!("<li>FloatVariableSubtract {{ variable: {}, value: {} }}</li>", process_expression(&Expression::Variable(variable.clone())), process_float_value(value))),
-            EventAst::FloatVariableMultiply { value, variable } => result.push_str(&format!("<li>FloatVariableMultiply {{ variable: {}, value: {} }}</li>", process_expression(&Expression::Variable(variable.clone())), process_float_value(value))),
-            EventAst::FloatVariableDivide   { value, variable } => result.push_str(&format!("<li>FloatVariableDivide {{ variable: {}, value: {} }}</li>",   process_expression(&Expression::Variable(variable.clone())), process_float_value(value))),
-            EventAst::BoolVariableSetTrue   { variable        } => result.push_str(&format!("<li>BoolVariableSetTrue {{ variable: {} }}</li>",              process_expression(&Expression::Variable(variable.clone())))),
-            EventAst::BoolVariableSetFalse  { variable        } => result.push_str(&format!("<li>BoolVariableSetFalse {{ variable: {} }}</li>",             process_expression(&Expression::Variable(variable.clone())))),
-            EventAst::ItemThrow { unk1, unk2, unk3, unk4, unk5 } => result.push_str(&format!("<li>ItemThrow {{ unk1: {}, unk2: {}, unk3: {} unk4: {}, unk5: {} }}</li>",
-                process_expression(&Expression::Variable(unk1.clone())),
-                process_expression(&Expression::Variable(unk2.clone())),
-                process_expression(&Expression::Variable(unk3.clone())),
-                unk4.as_ref().map(|x| process_expression(&Expression::Variable(x.clone()))).unwrap_or_else(|| "None".into()),
-                unk5.as_ref().map(|x| process_expression(&Expression::Variable(x.clone()))).unwrap_or_else(|| "None".into()),
-            )),
-            EventAst::ItemThrow2 { unk1, unk2, unk3 } => result.push_str(&format!("<li>ItemThrow2 {{ unk1: {}, unk2: {}, unk3: {}}}</li>", unk1, unk2, process_expression(&Expression::Variable(unk3.clone())))),
-            EventAst::ApplyThrow (throw) => result.push_str(&format!("<li>ApplyThrow {{ unk0: {}, bone: {}, unk1: {} unk2: {}, unk3: {} }}</li>",
-                throw.unk0,
-                throw.bone,
-                process_expression(&Expression::Variable(throw.unk1.clone())),
-                process_expression(&Expression::Variable(throw.unk2.clone())),
-                process_expression(&Expression::Variable(throw.unk3.clone())),
-            )),
-            EventAst::Unknown (event) => {
-                let args: Vec<String> = event.arguments.iter().map(|arg| match arg {
-                    Argument::Unknown (ty, value) => format!("Unknown {{ ty: 0x{:x}, value: 0x{:x} }}", ty, value),
-                    _ => format!("{:?}", arg),
-                }).collect();
-                let args = args.join(", ");
-                result.push_str(&format!("<li>UnknownEvent {{ namespace: 0x{:x}, code: 0x{:x}, unk1: 0x{:x}, arguments: [{}] }}</li>", event.namespace, event.code, event.unk1, args));
-            }
-            _ => result.push_str(&format!("<li>{:?}</li>", event)),
-        }
-    }
-    result.push_str("</ol>");
-    result
-}
+            Argument::Unknown(ty, value) => {
+                self.push(TokenKind::Value, "Unknown");
+                self.open();
+                self.argument("ty");
+                self.push(TokenKind::Number, format!("0x{ty:x}"));
+                self.argument("value");
+                self.push(TokenKind::Number, format!("0x{value:x}"));
+                self.close();
+            }
+        }
+    }
 
-fn process_float_value(value: &FloatValue) -> String {
-    match value {
-        FloatValue::Constant(constant) => format!("{}", constant),
-        FloatValue::Variable(variable) => {
-            process_expression(&Expression::Variable(variable.clone()))
+    /// An event brawllib doesn't know, named by the mod's event definitions with its id in the tooltip.
+    /// Arguments past the end of the definition are shown without a name.
+    fn defined_event(&mut self, event: &Event, definition: &EventDefinition) {
+        let key = EventKey::new(event);
+        let title = match &definition.description {
+            Some(description) => format!("{key}\n{description}"),
+            None => key.to_string(),
+        };
+        self.push_titled(TokenKind::Event, &definition.name, Some(title));
+        self.open();
+        for (i, argument) in event.arguments.iter().enumerate() {
+            self.separate();
+            let Some(argument_definition) = definition.arguments.get(i) else {
+                self.raw_argument(argument);
+                continue;
+            };
+            self.push_titled(
+                TokenKind::Argument,
+                argument_definition.name.replace('_', " "),
+                argument_definition.description.clone(),
+            );
+            self.punctuation(": ");
+            match (argument, argument_definition.ty) {
+                (Argument::Value(value), Some(ArgumentType::Hex)) => {
+                    self.push(TokenKind::Number, format!("0x{value:x}"))
+                }
+                (Argument::Value(value), Some(ArgumentType::Bool)) => {
+                    self.push(TokenKind::Value, (*value != 0).to_string())
+                }
+                _ => self.raw_argument(argument),
+            }
         }
+        self.close();
     }
-}
 
-fn process_expression(expr: &Expression) -> String {
-    match expr {
-        Expression::Nullary(requirement) => format!("{:?}", requirement),
-        Expression::Unary(UnaryExpression { requirement, value }) => {
-            format!("{:?} {}", requirement, process_expression(value))
-        }
-        Expression::Binary(BinaryExpression {
-            left,
-            operator,
-            right,
-        }) => format!(
-            "({} {:?} {})",
-            process_expression(left),
-            operator,
-            process_expression(right)
-        ),
-        Expression::Not(expr) => format!("not({})", process_expression(expr)),
-        Expression::Variable(variable) =>
-        // TODO: This handler needs to be also called by stuff like IntVariableSet
-        {
-            match variable {
-                VariableAst::InternalConstantInt(InternalConstantInt::Address(address)) => {
-                    format!("InternalConstantInt (0x{:x})", address)
+    fn requirement_event(&mut self, name: &str, test: &Expression) {
+        self.event(name);
+        self.open();
+        self.expression(test, false);
+        self.close();
+    }
+
+    /// An event that doesn't link to other pages.
+    /// Events using variables are matched so they can be shown by name,
+    /// the rest only hold plain data and are rendered from their serialized form, which names the event and all of its arguments.
+    fn data_event(&mut self, event: &EventAst) {
+        match event {
+            EventAst::IntVariableSet { value, variable } => {
+                self.int_variable_event("IntVariableSet", *value, variable)
+            }
+            EventAst::IntVariableAdd { value, variable } => {
+                self.int_variable_event("IntVariableAdd", *value, variable)
+            }
+            EventAst::IntVariableSubtract { value, variable } => {
+                self.int_variable_event("IntVariableSubtract", *value, variable)
+            }
+            EventAst::FloatVariableSet { value, variable } => {
+                self.float_variable_event("FloatVariableSet", value, variable)
+            }
+            EventAst::FloatVariableAdd { value, variable } => {
+                self.float_variable_event("FloatVariableAdd", value, variable)
+            }
+            EventAst::FloatVariableSubtract { value, variable } => {
+                self.float_variable_event("FloatVariableSubtract", value, variable)
+            }
+            EventAst::FloatVariableMultiply { value, variable } => {
+                self.float_variable_event("FloatVariableMultiply", value, variable)
+            }
+            EventAst::FloatVariableDivide { value, variable } => {
+                self.float_variable_event("FloatVariableDivide", value, variable)
+            }
+            EventAst::IntVariableIncrement { variable } => {
+                self.single_variable_event("IntVariableIncrement", variable)
+            }
+            EventAst::IntVariableDecrement { variable } => {
+                self.single_variable_event("IntVariableDecrement", variable)
+            }
+            EventAst::BoolVariableSetTrue { variable } => {
+                self.single_variable_event("BoolVariableSetTrue", variable)
+            }
+            EventAst::BoolVariableSetFalse { variable } => {
+                self.single_variable_event("BoolVariableSetFalse", variable)
+            }
+            EventAst::AddHitBoxDamage {
+                hitbox_id,
+                add_damage,
+            } => {
+                self.event("AddHitBoxDamage");
+                self.open();
+                self.argument("hitbox_id");
+                self.push(TokenKind::Number, hitbox_id.to_string());
+                self.argument("add_damage");
+                self.float_value(add_damage);
+                self.close();
+            }
+            EventAst::AddVelocity { x_vel, y_vel } => {
+                self.event("AddVelocity");
+                self.open();
+                self.argument("x_vel");
+                self.float_value(x_vel);
+                self.argument("y_vel");
+                self.float_value(y_vel);
+                self.close();
+            }
+            EventAst::ApplyThrow(ApplyThrow {
+                unk0,
+                bone,
+                unk1,
+                unk2,
+                unk3,
+            }) => {
+                self.event("ApplyThrow");
+                self.open();
+                self.argument("unk0");
+                self.push(TokenKind::Number, unk0.to_string());
+                self.argument("bone");
+                self.push(TokenKind::Number, bone.to_string());
+                for (name, variable) in [("unk1", unk1), ("unk2", unk2), ("unk3", unk3)] {
+                    self.argument(name);
+                    self.variable(variable);
                 }
-                VariableAst::LongtermAccessInt(LongtermAccessInt::Address(address)) => {
-                    format!("LongtermAccessInt (0x{:x})", address)
+                self.close();
+            }
+            EventAst::ItemThrow {
+                unk1,
+                unk2,
+                unk3,
+                unk4,
+                unk5,
+            } => {
+                self.event("ItemThrow");
+                self.open();
+                for (name, variable) in [("unk1", unk1), ("unk2", unk2), ("unk3", unk3)] {
+                    self.argument(name);
+                    self.variable(variable);
                 }
-                VariableAst::LongtermAccessFloat(LongtermAccessFloat::Address(address)) => {
-                    format!("LongtermAccessFloat (0x{:x})", address)
+                for (name, variable) in [("unk4", unk4), ("unk5", unk5)] {
+                    self.argument(name);
+                    match variable {
+                        Some(variable) => self.variable(variable),
+                        None => self.push(TokenKind::Value, "None"),
+                    }
                 }
-                VariableAst::LongtermAccessBool(LongtermAccessBool::Address(address)) => {
-                    format!("LongtermAccessBool (0x{:x})", address)
+                self.close();
+            }
+            EventAst::ItemThrow2 { unk1, unk2, unk3 } => {
+                self.event("ItemThrow2");
+                self.open();
+                self.argument("unk1");
+                self.push(TokenKind::Number, format!("{unk1:?}"));
+                self.argument("unk2");
+                self.push(TokenKind::Number, format!("{unk2:?}"));
+                self.argument("unk3");
+                self.variable(unk3);
+                self.close();
+            }
+            EventAst::CreateHitBox(args) => {
+                self.event("CreateHitBox");
+                self.open();
+                self.hitbox_arguments(args);
+                self.close();
+            }
+            EventAst::ThrownHitBox(args) => {
+                self.event("ThrownHitBox");
+                self.open();
+                self.hitbox_arguments(args);
+                self.close();
+            }
+            EventAst::CreateSpecialHitBox(args) => {
+                self.event("CreateSpecialHitBox");
+                self.open();
+                let Value::Object(fields) = serde_json::to_value(args).unwrap() else {
+                    unreachable!("SpecialHitBoxArguments is a struct")
+                };
+                for (name, value) in fields {
+                    if name == "hitbox_args" {
+                        self.hitbox_arguments(&args.hitbox_args);
+                    } else {
+                        self.argument(&name);
+                        self.json(value);
+                    }
                 }
-                VariableAst::RandomAccessInt(RandomAccessInt::Address(address)) => {
-                    format!("RandomAccessInt (0x{:x})", address)
+                self.close();
+            }
+            _ => match serde_json::to_value(event).unwrap() {
+                Value::Object(map) => {
+                    let (name, arguments) = map.into_iter().next().unwrap();
+                    self.event(&name);
+                    self.open();
+                    self.arguments(arguments);
+                    self.close();
                 }
-                VariableAst::RandomAccessFloat(RandomAccessFloat::Address(address)) => {
-                    format!("RandomAccessFloat (0x{:x})", address)
+                // A unit variant
+                value => self.event(value.as_str().unwrap()),
+            },
+        }
+    }
+
+    /// An event that changes `variable` by `value`.
+    fn int_variable_event(&mut self, name: &str, value: i32, variable: &VariableAst) {
+        self.event(name);
+        self.open();
+        self.argument("value");
+        self.push(TokenKind::Number, value.to_string());
+        self.argument("variable");
+        self.variable(variable);
+        self.close();
+    }
+
+    /// Same as `int_variable_event` for float variables.
+    fn float_variable_event(&mut self, name: &str, value: &FloatValue, variable: &VariableAst) {
+        self.event(name);
+        self.open();
+        self.argument("value");
+        self.float_value(value);
+        self.argument("variable");
+        self.variable(variable);
+        self.close();
+    }
+
+    fn single_variable_event(&mut self, name: &str, variable: &VariableAst) {
+        self.event(name);
+        self.open();
+        self.argument("variable");
+        self.variable(variable);
+        self.close();
+    }
+
+    /// Either a constant or a variable, both are clear without naming which it is.
+    fn float_value(&mut self, value: &FloatValue) {
+        match value {
+            FloatValue::Constant(value) => self.push(TokenKind::Number, format!("{value:?}")),
+            FloatValue::Variable(variable) => self.variable(variable),
+        }
+    }
+
+    /// The damage can be a variable, every other argument is plain data.
+    fn hitbox_arguments(&mut self, args: &HitBoxArguments) {
+        let Value::Object(fields) = serde_json::to_value(args).unwrap() else {
+            unreachable!("HitBoxArguments is a struct")
+        };
+        for (name, value) in fields {
+            self.argument(&name);
+            if name == "damage" {
+                self.float_value(&args.damage);
+            } else {
+                self.json(value);
+            }
+        }
+    }
+
+    fn serialized<T: Serialize>(&mut self, value: &T) {
+        self.json(serde_json::to_value(value).unwrap());
+    }
+
+    /// The contents of a tuple, struct or newtype.
+    fn arguments(&mut self, value: Value) {
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    self.separate();
+                    self.json(item);
                 }
-                VariableAst::RandomAccessBool(RandomAccessBool::Address(address)) => {
-                    format!("RandomAccessBool (0x{:x})", address)
+            }
+            Value::Object(fields) if !is_enum(&fields) => self.fields(fields),
+            value => self.json(value),
+        }
+    }
+
+    /// Fields of a struct nested in another struct are flattened into the outer struct.
+    fn fields(&mut self, fields: Map<String, Value>) {
+        for (name, value) in fields {
+            match value {
+                Value::Object(inner) if !is_enum(&inner) => self.fields(inner),
+                value => {
+                    self.argument(&name);
+                    self.json(value);
                 }
-                VariableAst::Unknown {
-                    memory_type,
-                    data_type,
-                    address,
-                } => format!(
-                    "Unknown {{ memory_type: {:?}, data_type: {:?}, address: 0x{:x} }}",
-                    memory_type, data_type, address
-                ),
-                _ => format!("{:?}", variable),
             }
         }
-        Expression::Value(value) => format!("value({})", value),
-        Expression::Scalar(scalar) => format!("scalar({})", scalar),
+    }
+
+    fn json(&mut self, value: Value) {
+        match value {
+            Value::Null => self.push(TokenKind::Value, "None"),
+            Value::Bool(value) => self.push(TokenKind::Value, value.to_string()),
+            Value::Number(number) => {
+                let text = match number.as_i64() {
+                    Some(integer) => integer.to_string(),
+                    // All floats in the AST are f32, formatting them as f64 would show the rounding error.
+                    None => format!("{:?}", number.as_f64().unwrap() as f32),
+                };
+                self.push(TokenKind::Number, text);
+            }
+            // A unit variant
+            Value::String(name) => self.push(TokenKind::Value, readable_name(&name)),
+            Value::Array(items) => {
+                self.punctuation("[");
+                self.arguments(Value::Array(items));
+                self.punctuation("]");
+            }
+            Value::Object(map) => {
+                if is_enum(&map) {
+                    let (name, inner) = map.into_iter().next().unwrap();
+                    self.push(TokenKind::Value, readable_name(&name));
+                    self.open();
+                    self.arguments(inner);
+                    self.close();
+                } else {
+                    self.open();
+                    self.fields(map);
+                    self.close();
+                }
+            }
+        }
+    }
+}
+
+/// A serialized enum variant with data is an object with the variant name as its only key, struct field names are snake_case.
+fn is_enum(map: &Map<String, Value>) -> bool {
+    map.len() == 1
+        && map
+            .keys()
+            .next()
+            .is_some_and(|x| x.starts_with(|c: char| c.is_ascii_uppercase()))
+}
+
+/// Splits a CamelCase name into words e.g. `CreateHitBox` is `Create Hit Box` and `SDIInput` is `SDI Input`.
+fn readable_name(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if i > 0 {
+            let prev = chars[i - 1];
+            let next_lowercase = chars.get(i + 1).is_some_and(|x| x.is_ascii_lowercase());
+            let word_start = if c.is_ascii_uppercase() {
+                prev.is_ascii_lowercase() || next_lowercase
+            } else if c.is_ascii_digit() {
+                prev.is_ascii_alphabetic()
+            } else {
+                false
+            };
+            if word_start {
+                result.push(' ');
+            }
+        }
+        result.push(*c);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use brawllib_rs::script::{Variable, VariableDataType, VariableMemoryType};
+
+    fn variable(data_type: VariableDataType, address: u32) -> VariableAst {
+        VariableAst::new(&Variable {
+            memory_type: VariableMemoryType::RandomAccess,
+            data_type,
+            address,
+        })
+    }
+
+    fn event_text(event: &EventAst) -> String {
+        let names = VariableNames::default();
+        let mut tokens = Tokens::new(&names);
+        tokens.data_event(event);
+        tokens.text()
+    }
+
+    #[test]
+    fn readable_name_words() {
+        assert_eq!(readable_name("CreateHitBox"), "Create Hit Box");
+        assert_eq!(readable_name("IntVariableSet"), "Int Variable Set");
+        assert_eq!(readable_name("Goto"), "Goto");
+    }
+
+    #[test]
+    fn readable_name_acronyms_and_numbers() {
+        assert_eq!(readable_name("SDIInput"), "SDI Input");
+        assert_eq!(readable_name("ItemThrow2"), "Item Throw 2");
+        assert_eq!(readable_name("unk1"), "unk 1");
+    }
+
+    #[test]
+    fn int_variable_event() {
+        let event = EventAst::IntVariableSet {
+            value: 3,
+            variable: variable(VariableDataType::Int, 100),
+        };
+        assert_eq!(
+            event_text(&event),
+            "Int Variable Set(value: 3, variable: RA-Basic[100])"
+        );
+    }
+
+    #[test]
+    fn float_value_event() {
+        let event = EventAst::AddVelocity {
+            x_vel: FloatValue::Constant(1.5),
+            y_vel: FloatValue::Variable(variable(VariableDataType::Float, 5)),
+        };
+        assert_eq!(
+            event_text(&event),
+            "Add Velocity(x vel: 1.5, y vel: RA-Float[5])"
+        );
+    }
+
+    #[test]
+    fn plain_data_event() {
+        let event = EventAst::SetVelocity {
+            x_vel: 1.0,
+            y_vel: -0.5,
+        };
+        assert_eq!(event_text(&event), "Set Velocity(x vel: 1.0, y vel: -0.5)");
+        assert_eq!(event_text(&EventAst::ItemDrop), "Item Drop");
+    }
+
+    #[test]
+    fn expression() {
+        let test = Expression::Binary(BinaryExpression {
+            left: Box::new(Expression::Nullary(Requirement::OnGround)),
+            operator: ComparisonOperator::And,
+            right: Box::new(Expression::Binary(BinaryExpression {
+                left: Box::new(Expression::Variable(variable(VariableDataType::Int, 100))),
+                operator: ComparisonOperator::LessThan,
+                right: Box::new(Expression::Value(3)),
+            })),
+        });
+        assert_eq!(
+            process_expression(&test, &VariableNames::default()),
+            "On Ground and (RA-Basic[100] < 3)"
+        );
     }
 }
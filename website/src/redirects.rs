@@ -0,0 +1,149 @@
+//! Generates redirect stubs at the paths pages were generated at before fighter directories were slugged,
+//! e.g. `/P+/Captain Falcon/subactions/AttackAirN.html` redirects to `/P+/captain-falcon/subactions/AttackAirN.html`
+//!
+//! Static hosting such as AWS S3 can't redirect on its own, so each stub is a tiny page that redirects in the browser.
+
+use crate::brawl_data::{BrawlMods, slug};
+use crate::output::OutDir;
+use crate::page::SITE_URL;
+use crate::site::Site;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn generate(brawl_mods: &BrawlMods, site: &Site) {
+    // Fighters have the same name in every mod, so one lookup covers them all.
+    let names: HashMap<String, &str> = brawl_mods
+        .mods
+        .iter()
+        .flat_map(|x| &x.fighters)
+        .map(|x| (slug(&x.fighter.name), x.fighter.name.as_str()))
+        .filter(|(slug, name)| slug != name)
+        .collect();
+
+    for url in site.page_urls() {
+        let Some(old_url) = old_url(&url, &names) else {
+            continue;
+        };
+        let (dir, file_name) = old_url.rsplit_once('/').unwrap();
+        let file_name = if file_name.is_empty() {
+            "index.html"
+        } else {
+            file_name
+        };
+        let dir = OutDir::new(dir.trim_start_matches('/'));
+        if replaces_page(&url, &old_url) {
+            warn!(
+                "Skipping the redirect from {old_url} as the filesystem is case insensitive and it would replace {url}"
+            );
+            // Still marked as generated, otherwise pruning a stub from a previous run would delete the page.
+            site.keep(&dir, file_name);
+            continue;
+        }
+        site.create_compressed_file(&dir, file_name, stub(&url).as_bytes());
+    }
+}
+
+/// True when the old url is the same file as the page on disk, which happens on a case insensitive filesystem
+/// when a fighter's name only differs from its slug in case e.g. `Mario`.
+/// The page has just been written, so the file at the old url only has the same contents when it is the page.
+fn replaces_page(url: &str, old_url: &str) -> bool {
+    if !url.eq_ignore_ascii_case(old_url) {
+        return false;
+    }
+    match (fs::read(output_path(url)), fs::read(output_path(old_url))) {
+        (Ok(page), Ok(old)) => page == old,
+        _ => false,
+    }
+}
+
+fn output_path(url: &str) -> PathBuf {
+    let mut path = Path::new("../root").join(url.trim_start_matches('/'));
+    if url.ends_with('/') {
+        path.push("index.html");
+    }
+    path
+}
+
+/// The url the page was generated at before slugs, None if it hasn't changed.
+/// Fighter directories are `/{mod}/{fighter}/` and `/compare/{old_mod}/{new_mod}/{fighter}/`
+fn old_url(url: &str, names: &HashMap<String, &str>) -> Option<String> {
+    let mut segments: Vec<&str> = url.split('/').collect();
+    // The leading slash is an empty first segment.
+    let fighter_index = if segments.get(1) == Some(&"compare") {
+        4
+    } else {
+        2
+    };
+    // The last segment is the file name, which is never a fighter directory.
+    if fighter_index + 1 >= segments.len() {
+        return None;
+    }
+    segments[fighter_index] = names.get(segments[fighter_index])?;
+    Some(segments.join("/"))
+}
+
+/// Redirects to the url, keeping any `#fragment` so that links to a specific script still work.
+fn stub(url: &str) -> String {
+    let script_url = serde_json::to_string(url).unwrap();
+    let url = handlebars::html_escape(url);
+    format!(
+        r#"<!DOCTYPE html>
+<html lang=en>
+<head>
+<meta charset="utf-8">
+<title>Redirecting</title>
+<link rel="canonical" href="{SITE_URL}{url}">
+<meta name="robots" content="noindex">
+<script>location.replace({script_url} + location.hash);</script>
+<meta http-equiv="refresh" content="0; url={url}">
+</head>
+<body><a href="{url}">{url}</a></body>
+</html>
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> HashMap<String, &'static str> {
+        [("mario", "Mario"), ("captain-falcon", "Captain Falcon")]
+            .into_iter()
+            .map(|(slug, name)| (slug.to_owned(), name))
+            .collect()
+    }
+
+    #[test]
+    fn old_url_case_only() {
+        assert_eq!(
+            old_url("/P+/mario/subactions/AttackAirN.html", &names()).as_deref(),
+            Some("/P+/Mario/subactions/AttackAirN.html")
+        );
+        assert_eq!(
+            old_url("/P+/mario/", &names()).as_deref(),
+            Some("/P+/Mario/")
+        );
+    }
+
+    #[test]
+    fn old_url_spaces() {
+        assert_eq!(
+            old_url("/P+/captain-falcon/scripts.html", &names()).as_deref(),
+            Some("/P+/Captain Falcon/scripts.html")
+        );
+        assert_eq!(
+            old_url("/compare/Brawl/P+/captain-falcon/", &names()).as_deref(),
+            Some("/compare/Brawl/P+/Captain Falcon/")
+        );
+    }
+
+    #[test]
+    fn old_url_unchanged() {
+        assert_eq!(old_url("/P+/index.html", &names()), None);
+        assert_eq!(old_url("/P+/", &names()), None);
+        assert_eq!(old_url("/P+/unknown/index.html", &names()), None);
+        assert_eq!(old_url("/compare/Brawl/P+/", &names()), None);
+    }
+}
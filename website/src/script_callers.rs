@@ -0,0 +1,171 @@
+//! Reverse of the links `process_events` generates for `Subroutine`, `Goto`, `CallEveryFrame` and `IndependentSubroutine`,
+//! so that every script can list the scripts that call it.
+
+use crate::brawl_data::ScriptInfo;
+use brawllib_rs::high_level_fighter::HighLevelFighter;
+use brawllib_rs::script::Offset;
+use brawllib_rs::script_ast::{Block, EventAst, ForLoop, IfStatement};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Identifies a script in the same way `process_events` resolves call destinations.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ScriptKey {
+    /// An offset in `script_lookup`
+    Fighter(i32),
+    /// An offset in `script_lookup_common`
+    Common(i32),
+    /// A script in `scripts_section`, identified by name
+    Section(String),
+}
+
+impl ScriptKey {
+    /// The key of a script at this offset, `common` is true when the script is from Fighter.pac.
+    pub fn new(common: bool, offset: i32) -> Self {
+        if common {
+            ScriptKey::Common(offset)
+        } else {
+            ScriptKey::Fighter(offset)
+        }
+    }
+}
+
+pub struct Caller {
+    pub script: ScriptKey,
+    /// The name of the event making the call e.g. `Subroutine`
+    pub kind: &'static str,
+}
+
+pub struct ScriptCallers {
+    callers: HashMap<ScriptKey, Vec<Caller>>,
+}
+
+impl ScriptCallers {
+    pub fn new(
+        fighter: &HighLevelFighter,
+        script_lookup: &HashMap<i32, ScriptInfo>,
+        script_lookup_common: &HashMap<i32, ScriptInfo>,
+    ) -> Self {
+        let mut scripts: Vec<(ScriptKey, bool, &Block)> = vec![];
+        for action in &fighter.actions {
+            if action.script_entry.offset != 0 {
+                let common = action.script_entry_common;
+                let key = ScriptKey::new(common, action.script_entry.offset);
+                scripts.push((key, common, &action.script_entry.block));
+            }
+            if action.script_exit.offset != 0 {
+                let common = action.script_exit_common;
+                let key = ScriptKey::new(common, action.script_exit.offset);
+                scripts.push((key, common, &action.script_exit.block));
+            }
+        }
+        for subaction in &fighter.subactions {
+            let subaction_scripts = &subaction.scripts;
+            for script in [
+                &subaction_scripts.script_main,
+                &subaction_scripts.script_gfx,
+                &subaction_scripts.script_sfx,
+                &subaction_scripts.script_other,
+            ] {
+                scripts.push((ScriptKey::Fighter(script.offset), false, &script.block));
+            }
+        }
+        for script in &fighter.scripts_fragment_fighter {
+            scripts.push((ScriptKey::Fighter(script.offset), false, &script.block));
+        }
+        for script in &fighter.scripts_fragment_common {
+            scripts.push((ScriptKey::Common(script.offset), true, &script.block));
+        }
+        for script in &fighter.scripts_section {
+            scripts.push((
+                ScriptKey::Section(script.name.clone()),
+                true,
+                &script.script.block,
+            ));
+        }
+
+        let mut callers: HashMap<ScriptKey, Vec<Caller>> = HashMap::new();
+        for (key, common, block) in scripts {
+            let lookup = if common {
+                script_lookup_common
+            } else {
+                script_lookup
+            };
+            visit_calls(block, &mut |kind, offset| {
+                let callee = if lookup.contains_key(&offset.offset) {
+                    ScriptKey::new(common, offset.offset)
+                } else if let Some(script) = fighter
+                    .scripts_section
+                    .iter()
+                    .find(|x| x.callers.contains(&offset.origin))
+                {
+                    ScriptKey::Section(script.name.clone())
+                } else {
+                    return;
+                };
+
+                let callers = callers.entry(callee).or_default();
+                // Multiple actions can share a script, so the same call can be found more than once.
+                if !callers.iter().any(|x| x.script == key && x.kind == kind) {
+                    callers.push(Caller {
+                        script: key.clone(),
+                        kind,
+                    });
+                }
+            });
+        }
+
+        ScriptCallers { callers }
+    }
+
+    /// Scripts that directly call this script.
+    pub fn callers(&self, script: &ScriptKey) -> &[Caller] {
+        self.callers
+            .get(script)
+            .map(|x| x.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Every script that can eventually end up running this script, closest callers first.
+    /// Includes the direct callers.
+    pub fn reachable_from(&self, script: &ScriptKey) -> Vec<ScriptKey> {
+        let mut visited = HashSet::new();
+        visited.insert(script.clone());
+        let mut to_visit = VecDeque::from([script.clone()]);
+        let mut reachable = vec![];
+        while let Some(next) = to_visit.pop_front() {
+            for caller in self.callers(&next) {
+                if visited.insert(caller.script.clone()) {
+                    reachable.push(caller.script.clone());
+                    to_visit.push_back(caller.script.clone());
+                }
+            }
+        }
+        reachable
+    }
+}
+
+/// Calls `visit` for every event in the block, including loops and branches, that runs another script.
+fn visit_calls(block: &Block, visit: &mut impl FnMut(&'static str, &Offset)) {
+    for event in &block.events {
+        match event {
+            EventAst::ForLoop(ForLoop { block, .. }) => visit_calls(block, visit),
+            EventAst::IfStatement(IfStatement {
+                then_branch,
+                else_branch,
+                ..
+            }) => {
+                visit_calls(then_branch, visit);
+                if let Some(else_branch) = else_branch {
+                    visit_calls(else_branch, visit);
+                }
+            }
+            EventAst::Subroutine(offset) => visit("Subroutine", offset),
+            EventAst::Goto(offset) => visit("Goto", offset),
+            EventAst::CallEveryFrame { offset, .. } => visit("CallEveryFrame", offset),
+            EventAst::IndependentSubroutine { offset, .. } => {
+                visit("IndependentSubroutine", offset)
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use brawllib_rs::high_level_fighter::HighLevelFighter;
+use brawllib_rs::script_ast::{Block, EventAst, ForLoop, IfStatement, ScriptAst};
+use std::collections::HashSet;
+
+/// Visits every event that a script could run, including the events of any loops, branches and the fragment scripts it calls.
+pub struct ScriptWalker<'a> {
+    fighter: &'a HighLevelFighter,
+    /// (common, offset) of every fragment script already walked, guards against recursive subroutines.
+    visited: HashSet<(bool, i32)>,
+}
+
+impl<'a> ScriptWalker<'a> {
+    pub fn new(fighter: &'a HighLevelFighter) -> Self {
+        ScriptWalker {
+            fighter,
+            visited: HashSet::new(),
+        }
+    }
+
+    /// `common` is true when the block belongs to a script from Fighter.pac, which determines where Subroutine/Goto offsets point to.
+    pub fn walk(&mut self, block: &Block, common: bool, visit: &mut impl FnMut(&EventAst)) {
+        for event in &block.events {
+            visit(event);
+            match event {
+                EventAst::ForLoop(ForLoop { block, .. }) => self.walk(block, common, visit),
+                EventAst::IfStatement(IfStatement {
+                    then_branch,
+                    else_branch,
+                    ..
+                }) => {
+                    self.walk(then_branch, common, visit);
+                    if let Some(else_branch) = else_branch {
+                        self.walk(else_branch, common, visit);
+                    }
+                }
+                EventAst::Subroutine(offset) | EventAst::Goto(offset) => {
+                    let scripts: &[ScriptAst] = if common {
+                        &self.fighter.scripts_fragment_common
+                    } else {
+                        &self.fighter.scripts_fragment_fighter
+                    };
+                    if let Some(script) = scripts.iter().find(|x| x.offset == offset.offset) {
+                        if self.visited.insert((common, offset.offset)) {
+                            self.walk(&script.block, common, visit);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Indexes of every subaction the script can change to.
+    pub fn changed_subactions(&mut self, block: &Block, common: bool) -> Vec<usize> {
+        let mut subactions = vec![];
+        self.walk(block, common, &mut |event| match event {
+            EventAst::ChangeSubaction(index) | EventAst::ChangeSubactionRestartFrame(index) => {
+                if let Ok(index) = usize::try_from(*index) {
+                    subactions.push(index);
+                }
+            }
+            _ => {}
+        });
+        subactions
+    }
+}
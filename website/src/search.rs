@@ -0,0 +1,238 @@
+//! Generates the index used by the search box in the navbar.
+//!
+//! The index is split into `search/index.json` in the web root, listing every mod, and one shard per mod at `search/{mod}.json`.
+//! This way the client only downloads the fighters of the mods it is searching, while everything remains a static file.
+
+use crate::assets::AssetPaths;
+use crate::brawl_data::{BrawlMod, BrawlMods};
+use crate::output::OutDir;
+use crate::site::Site;
+use brawllib_rs::high_level_fighter::HighLevelFighter;
+use std::path::Path;
+
+pub fn generate(brawl_mods: &BrawlMods, assets: &AssetPaths, site: &Site) {
+    // Written to the directory of the index the navbar fetches.
+    let dir = OutDir::new_path(
+        Path::new(&assets.search_index)
+            .parent()
+            .unwrap()
+            .strip_prefix("/")
+            .unwrap(),
+    );
+
+    let mut mods = vec![];
+    for brawl_mod in &brawl_mods.mods {
+        let shard = gen_shard(brawl_mod);
+        let url = site.create_compressed_file(
+            &dir,
+            &format!("{}.json", brawl_mod.name),
+            serde_json::to_string(&shard).unwrap().as_bytes(),
+        );
+        mods.push(SearchMod {
+            name: brawl_mod.name.clone(),
+            link: format!("/{}", brawl_mod.name),
+            shard: url,
+        });
+    }
+
+    site.create_compressed_file(
+        &dir,
+        "index.json",
+        serde_json::to_string(&mods).unwrap().as_bytes(),
+    );
+}
+
+fn gen_shard(brawl_mod: &BrawlMod) -> SearchShard {
+    let mut fighters = vec![];
+    let mut entries = vec![];
+    for (fighter_index, fighter) in brawl_mod.fighters.iter().enumerate() {
+        let fighter = &fighter.fighter;
+        fighters.push(SearchFighter {
+            name: fighter.name.clone(),
+            aliases: fighter_aliases(fighter),
+            link: format!("/{}", brawl_mod.fighter_path(&fighter.name)),
+        });
+
+        let mut push = |kind: &'static str, name: String, link: String, aliases: Vec<&str>| {
+            entries.push(SearchEntry(
+                fighter_index,
+                kind,
+                name,
+                link,
+                aliases.join(" "),
+            ));
+        };
+
+        push("Fighter", String::new(), String::new(), vec![]);
+        push(
+            "Page",
+            "Attributes".into(),
+            "attributes.html".into(),
+            vec!["stats"],
+        );
+        push("Page", "Actions".into(), "actions".into(), vec![]);
+        push("Page", "Subactions".into(), "subactions".into(), vec![]);
+        push("Page", "Scripts".into(), "scripts".into(), vec![]);
+        push("Page", "Variables".into(), "variables.html".into(), vec![]);
+
+        for subaction in &fighter.subactions {
+            push(
+                "Subaction",
+                subaction.name.clone(),
+                format!("subactions/{}.html", subaction.name),
+                subaction_aliases(&subaction.name),
+            );
+        }
+
+        for action in &fighter.actions {
+            push(
+                "Action",
+                action.name.clone(),
+                format!("actions/{}.html", action.name),
+                subaction_aliases(&action.name),
+            );
+        }
+
+        for script in &fighter.scripts_fragment_fighter {
+            push(
+                "Script",
+                format!("Subroutine 0x{:x}", script.offset),
+                format!("scripts/0x{:x}.html", script.offset),
+                vec![],
+            );
+        }
+
+        for script in &fighter.scripts_fragment_common {
+            push(
+                "Script",
+                format!("Common Subroutine 0x{:x}", script.offset),
+                format!("scripts_common/0x{:x}.html", script.offset),
+                vec![],
+            );
+        }
+
+        for script in &fighter.scripts_section {
+            push(
+                "Script",
+                format!("Section {}", script.name),
+                format!("scripts_common/{}.html", script.name),
+                vec![],
+            );
+        }
+    }
+
+    SearchShard { fighters, entries }
+}
+
+/// The internal name and the names players commonly use for the fighter.
+fn fighter_aliases(fighter: &HighLevelFighter) -> String {
+    let mut aliases = vec![fighter.internal_name.as_str()];
+    aliases.extend_from_slice(match fighter.internal_name.as_ref() {
+        "Captain" => &["falcon", "cf"],
+        "Dedede" => &["ddd", "d3"],
+        "Donkey" => &["dk"],
+        "GameWatch" => &["gnw", "gw"],
+        "Metaknight" => &["mk"],
+        "Pikmin" => &["olimar", "oli"],
+        "PokeFushigisou" => &["ivy"],
+        "PokeLizardon" => &["zard"],
+        "PokeTrainer" => &["pt"],
+        "Popo" => &["ics", "icies"],
+        "Purin" => &["puff", "jiggs"],
+        "Robot" => &["rob"],
+        "SZerosuit" => &["zss"],
+        "ToonLink" => &["tink", "tl"],
+        _ => &[],
+    });
+    aliases.join(" ")
+}
+
+/// Common names for attacks, these are used for both subactions and actions as they share most names.
+/// Matching is done on the prefix of the name so that e.g. `AttackS4Start` and `AttackS4S` are both found by `fsmash`.
+fn subaction_aliases(name: &str) -> Vec<&'static str> {
+    let (name, landing) = match name.strip_prefix("Landing") {
+        Some(name) => (name, true),
+        None => (name, false),
+    };
+    #[rustfmt::skip]
+    const ALIASES: &[(&str, &[&str])] = &[
+        ("AttackAirN",  &["nair", "neutral", "air"]),
+        ("AttackAirF",  &["fair", "forward", "air"]),
+        ("AttackAirB",  &["bair", "back", "air"]),
+        ("AttackAirHi", &["uair", "up", "air"]),
+        ("AttackAirLw", &["dair", "down", "air"]),
+        ("Attack11",    &["jab", "jab1"]),
+        ("Attack12",    &["jab", "jab2"]),
+        ("Attack13",    &["jab", "jab3"]),
+        ("Attack100",   &["jab", "rapid"]),
+        ("AttackDash",  &["dash", "attack", "da"]),
+        ("AttackS3",    &["ftilt", "forward", "tilt"]),
+        ("AttackHi3",   &["utilt", "up", "tilt"]),
+        ("AttackLw3",   &["dtilt", "down", "tilt"]),
+        ("AttackS4",    &["fsmash", "forward", "smash"]),
+        ("AttackHi4",   &["usmash", "up", "smash"]),
+        ("AttackLw4",   &["dsmash", "down", "smash"]),
+        ("SpecialAirN", &["neutralb", "nspecial", "neutral", "b", "special"]),
+        ("SpecialAirS", &["sideb", "sspecial", "side", "b", "special"]),
+        ("SpecialAirHi",&["upb", "uspecial", "up", "b", "special", "recovery"]),
+        ("SpecialAirLw",&["downb", "dspecial", "down", "b", "special"]),
+        ("SpecialN",    &["neutralb", "nspecial", "neutral", "b", "special"]),
+        ("SpecialS",    &["sideb", "sspecial", "side", "b", "special"]),
+        ("SpecialHi",   &["upb", "uspecial", "up", "b", "special", "recovery"]),
+        ("SpecialLw",   &["downb", "dspecial", "down", "b", "special"]),
+        ("CatchDash",   &["dash", "grab"]),
+        ("CatchTurn",   &["pivot", "grab"]),
+        ("Catch",       &["grab"]),
+        ("ThrowF",      &["fthrow", "forward", "throw"]),
+        ("ThrowB",      &["bthrow", "back", "throw"]),
+        ("ThrowHi",     &["uthrow", "up", "throw"]),
+        ("ThrowLw",     &["dthrow", "down", "throw"]),
+        ("EscapeN",     &["spotdodge", "spot", "dodge"]),
+        ("EscapeF",     &["roll", "forward"]),
+        ("EscapeB",     &["roll", "back"]),
+        ("EscapeAir",   &["airdodge", "air", "dodge"]),
+        ("CliffAttack", &["ledge", "attack", "getup"]),
+        ("CliffClimb",  &["ledge", "getup"]),
+        ("CliffEscape", &["ledge", "roll"]),
+        ("CliffJump",   &["ledge", "jump"]),
+        ("DownAttack",  &["getup", "attack"]),
+        ("Final",       &["final", "smash"]),
+        ("Appeal",      &["taunt"]),
+    ];
+
+    let mut aliases = ALIASES
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map(|(_, aliases)| aliases.to_vec())
+        .unwrap_or_default();
+    if landing && !aliases.is_empty() {
+        aliases.push("landing");
+    }
+    aliases
+}
+
+#[derive(Serialize)]
+struct SearchMod {
+    name: String,
+    link: String,
+    shard: String,
+}
+
+#[derive(Serialize)]
+struct SearchShard {
+    fighters: Vec<SearchFighter>,
+    entries: Vec<SearchEntry>,
+}
+
+#[derive(Serialize)]
+struct SearchFighter {
+    name: String,
+    /// Space separated
+    aliases: String,
+    link: String,
+}
+
+/// Serialized as an array to keep the shards compact.
+/// Contains the index into `SearchShard::fighters`, the kind of page, the name of the page, the link relative to the fighter and space separated aliases.
+#[derive(Serialize)]
+struct SearchEntry(usize, &'static str, String, String, String);
@@ -1,16 +1,25 @@
-use axum::{Router, middleware::map_response, response::Response, routing};
-use tower_http::services::ServeDir;
+use crate::api;
+use crate::brawl_data::BrawlMods;
+use crate::output::Encoding;
+use axum::Router;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{HeaderMap, HeaderValue, StatusCode, Uri, header};
+use axum::response::Response;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
 
-pub fn serve() {
+pub fn serve(encodings: &[Encoding], brawl_mods: BrawlMods) {
     let rt = tokio::runtime::Runtime::new().unwrap();
-    rt.block_on(run())
+    rt.block_on(run(encodings.to_vec(), brawl_mods))
 }
 
-async fn run() {
+async fn run(encodings: Vec<Encoding>, brawl_mods: BrawlMods) {
     // build our application with a route
-    let app = Router::new().fallback_service(
-        routing::get_service(ServeDir::new("../root"))
-            .layer(map_response(insert_compression_header)),
+    let app = api::router(Arc::new(brawl_mods)).merge(
+        Router::new()
+            .fallback(serve_file)
+            .with_state(Arc::new(encodings)),
     );
 
     // run it
@@ -21,11 +30,215 @@ async fn run() {
     axum::serve(listener, app).await.unwrap();
 }
 
-// All files are compressed with gzip, so we need to overwrite content-encoding to let the client know.
-// We cant use `ServeDir::precompressed_gzip` since name on disk needs to match name served for hosting on AWS S3 to work.
-async fn insert_compression_header<B>(mut response: Response<B>) -> Response<B> {
-    response
-        .headers_mut()
-        .insert("content-encoding", "gzip".parse().unwrap());
-    response
+/// Serves a file from the output root in the encoding the client prefers out of the configured `output_encodings`.
+/// The file at the requested name is in the first encoding, so when the client accepts none of the encodings it is decoded on the fly.
+async fn serve_file(
+    State(encodings): State<Arc<Vec<Encoding>>>,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    let Some(mut path) = file_path(uri.path()) else {
+        return not_found();
+    };
+    if path.is_dir() {
+        // Pages link relative to their directory so need the trailing slash.
+        if !uri.path().ends_with('/') {
+            return Response::builder()
+                .status(StatusCode::PERMANENT_REDIRECT)
+                .header(header::LOCATION, format!("{}/", uri.path()))
+                .body(Body::empty())
+                .unwrap();
+        }
+        path = path.join("index.html");
+    }
+    if !path.is_file() {
+        return not_found();
+    }
+
+    let accept_encoding = headers
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|x| x.to_str().ok())
+        .unwrap_or("");
+    let chosen = choose_encoding(accept_encoding, &encodings, |encoding| {
+        variant_path(&path, encoding).is_file()
+    });
+
+    let (encoding, data) = match chosen {
+        Some(0) => (encodings[0], tokio::fs::read(&path).await),
+        Some(i) => (
+            encodings[i],
+            tokio::fs::read(variant_path(&path, encodings[i])).await,
+        ),
+        None => {
+            let data = tokio::fs::read(&path).await;
+            (
+                Encoding::Identity,
+                data.and_then(|data| encodings[0].decode(&data)),
+            )
+        }
+    };
+    let Ok(data) = data else {
+        return not_found();
+    };
+
+    let mime = mime_guess::from_path(&path).first_or_octet_stream();
+    let mut response = Response::builder()
+        .header(header::CONTENT_TYPE, mime.as_ref())
+        .header(header::VARY, "accept-encoding");
+    if let Some(content_encoding) = encoding.content_encoding() {
+        response = response.header(
+            header::CONTENT_ENCODING,
+            HeaderValue::from_static(content_encoding),
+        );
+    }
+    response.body(Body::from(data)).unwrap()
+}
+
+/// The path in the output root of the url path, None if the path tries to leave the output root.
+fn file_path(url_path: &str) -> Option<PathBuf> {
+    let decoded = percent_encoding::percent_decode_str(url_path)
+        .decode_utf8()
+        .ok()?;
+    let mut path = PathBuf::from("../root");
+    for component in Path::new(decoded.as_ref()).components() {
+        match component {
+            Component::Normal(x) => path.push(x),
+            Component::RootDir | Component::CurDir => {}
+            Component::ParentDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(path)
+}
+
+/// The path of a file written in a secondary encoding.
+fn variant_path(path: &Path, encoding: Encoding) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(encoding.extension());
+    PathBuf::from(path)
+}
+
+/// The index of the encoding to serve, None if the client accepts none of the encodings.
+/// The first encoding is always available, `has_variant` says whether the file was written in one of the other encodings.
+/// When the client accepts several encodings equally, the one that compresses best is served.
+fn choose_encoding(
+    accept_encoding: &str,
+    encodings: &[Encoding],
+    has_variant: impl Fn(Encoding) -> bool,
+) -> Option<usize> {
+    let mut chosen: Option<(usize, f32)> = None;
+    for (i, encoding) in encodings.iter().enumerate() {
+        let quality = quality(accept_encoding, *encoding);
+        let better = match chosen {
+            None => true,
+            Some((best_i, best)) => {
+                quality > best
+                    || (quality == best && preference(*encoding) > preference(encodings[best_i]))
+            }
+        };
+        if quality > 0.0 && better && (i == 0 || has_variant(*encoding)) {
+            chosen = Some((i, quality));
+        }
+    }
+    chosen.map(|(i, _)| i)
+}
+
+/// Higher is preferred when the client gives encodings the same quality.
+fn preference(encoding: Encoding) -> u8 {
+    match encoding {
+        Encoding::Identity => 0,
+        Encoding::Gzip => 1,
+        Encoding::Br => 2,
+        Encoding::Zstd => 3,
+    }
+}
+
+/// The quality value the `accept-encoding` header gives the encoding, 0 if it is not accepted.
+fn quality(accept_encoding: &str, encoding: Encoding) -> f32 {
+    let mut wildcard = None;
+    for item in accept_encoding.split(',') {
+        let mut parts = item.split(';');
+        let name = parts.next().unwrap_or("").trim();
+        let quality = parts
+            .find_map(|x| x.trim().strip_prefix("q="))
+            .and_then(|x| x.parse().ok())
+            .unwrap_or(1.0);
+        if name.eq_ignore_ascii_case(encoding.name()) {
+            return quality;
+        }
+        if name == "*" {
+            wildcard = Some(quality);
+        }
+    }
+    match (wildcard, encoding) {
+        (Some(quality), _) => quality,
+        // Uncompressed responses are always acceptable unless explicitly refused, but any accepted compression is preferred.
+        (None, Encoding::Identity) => f32::MIN_POSITIVE,
+        (None, _) => 0.0,
+    }
+}
+
+fn not_found() -> Response {
+    Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .body(Body::from("Not Found"))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Encoding; 4] = [
+        Encoding::Identity,
+        Encoding::Gzip,
+        Encoding::Br,
+        Encoding::Zstd,
+    ];
+
+    #[test]
+    fn quality_listed() {
+        assert_eq!(quality("gzip, br;q=0.5", Encoding::Gzip), 1.0);
+        assert_eq!(quality("gzip, br;q=0.5", Encoding::Br), 0.5);
+        assert_eq!(quality("GZIP", Encoding::Gzip), 1.0);
+    }
+
+    #[test]
+    fn quality_q0_excluded() {
+        assert_eq!(quality("gzip;q=0, br", Encoding::Gzip), 0.0);
+        assert_eq!(quality("identity;q=0", Encoding::Identity), 0.0);
+        assert_eq!(quality("*;q=0", Encoding::Zstd), 0.0);
+    }
+
+    #[test]
+    fn quality_wildcard() {
+        assert_eq!(quality("*", Encoding::Zstd), 1.0);
+        assert_eq!(quality("gzip, *;q=0.2", Encoding::Br), 0.2);
+        assert_eq!(quality("br;q=0, *", Encoding::Br), 0.0);
+    }
+
+    #[test]
+    fn quality_missing_header() {
+        assert_eq!(quality("", Encoding::Gzip), 0.0);
+        assert_eq!(quality("", Encoding::Zstd), 0.0);
+        assert!(quality("", Encoding::Identity) > 0.0);
+        assert!(quality("gzip", Encoding::Identity) < quality("gzip", Encoding::Gzip));
+    }
+
+    #[test]
+    fn choose_tie_prefers_best_compression() {
+        let accept = "gzip, deflate, br, zstd";
+        assert_eq!(choose_encoding(accept, &ALL, |_| true), Some(3));
+        assert_eq!(
+            choose_encoding(accept, &ALL, |x| x != Encoding::Zstd),
+            Some(2)
+        );
+        assert_eq!(choose_encoding("gzip, br;q=0.5", &ALL, |_| true), Some(1));
+    }
+
+    #[test]
+    fn choose_none_accepted() {
+        let encodings = [Encoding::Gzip, Encoding::Br];
+        assert_eq!(choose_encoding("", &encodings, |_| true), None);
+        assert_eq!(choose_encoding("", &ALL, |_| true), Some(0));
+    }
 }
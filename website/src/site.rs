@@ -0,0 +1,261 @@
+//! Where the files generated by a run go.
+//!
+//! A normal run writes them to the output root through the `BuildCache`, skipping files whose inputs haven't changed.
+//! The dev server keeps them in memory instead, and only generates the file that was requested.
+
+use crate::assets::{self, AssetPaths};
+use crate::brawl_data::BrawlMods;
+use crate::build_cache::BuildCache;
+use crate::output::OutDir;
+use crate::page;
+use handlebars::Handlebars;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub struct Site {
+    destination: Destination,
+    filter: FileFilter,
+    pages: PageUrls,
+}
+
+enum Destination {
+    Disk(BuildCache),
+    Memory(MemoryFiles),
+}
+
+impl Site {
+    pub fn new(cache: BuildCache) -> Site {
+        Site {
+            destination: Destination::Disk(cache),
+            filter: FileFilter::default(),
+            pages: PageUrls::default(),
+        }
+    }
+
+    /// Keeps every file in memory for the dev server, nothing is written to disk.
+    pub fn in_memory() -> Site {
+        Site {
+            destination: Destination::Memory(MemoryFiles::default()),
+            filter: FileFilter::default(),
+            pages: PageUrls::default(),
+        }
+    }
+
+    /// Same as `Site::in_memory` but only the file at `url` is kept.
+    /// Generators check `Site::wants` to skip the work for every other file.
+    pub fn in_memory_only(url: &str) -> Site {
+        Site {
+            filter: FileFilter {
+                only: Some(url.to_owned()),
+            },
+            ..Site::in_memory()
+        }
+    }
+
+    /// Saves the build cache for the next run, does nothing for files kept in memory.
+    pub fn save(self) {
+        if let Destination::Disk(cache) = self.destination {
+            cache.save();
+        }
+    }
+
+    /// Every page includes the nav links to every mod and fighter and the urls of the assets,
+    /// so they are inputs of every page. Must be called before any pages are generated.
+    pub fn set_page_inputs(&self, brawl_mods: &BrawlMods, assets: &AssetPaths) {
+        if let Destination::Disk(cache) = &self.destination {
+            let mut inputs = serde_json::to_vec(assets).unwrap();
+            inputs.extend(serde_json::to_vec(&brawl_mods.mod_links).unwrap());
+            for brawl_mod in &brawl_mods.mods {
+                inputs.extend(brawl_mod.name.as_bytes());
+                for fighter in &brawl_mod.fighters {
+                    inputs.extend(fighter.fighter.name.as_bytes());
+                }
+            }
+            cache.set_shared_hash(assets::hash(&inputs));
+        }
+    }
+
+    /// False when only a single file is wanted and it is not in `dir` or its subdirectories.
+    pub fn wants(&self, dir: &OutDir) -> bool {
+        self.filter.wants(dir)
+    }
+
+    /// False when only a single file is wanted and it is not this one.
+    pub fn wants_file(&self, dir: &OutDir, file_name: &str) -> bool {
+        self.filter.wants_file(dir, file_name)
+    }
+
+    /// The url of every page generated so far, sorted.
+    pub fn page_urls(&self) -> Vec<String> {
+        self.pages.sorted()
+    }
+
+    /// False when the run was filtered to some mods or fighters or is kept in memory,
+    /// so files listing every page would be missing pages.
+    pub fn is_complete(&self) -> bool {
+        match &self.destination {
+            Destination::Disk(cache) => cache.is_complete(),
+            Destination::Memory(_) => false,
+        }
+    }
+
+    /// Generates the page to `file_name` in `dir` from the page struct returned by `page`.
+    ///
+    /// `inputs` are hashes of the data the page is generated from e.g. `BrawlFighter::hash`.
+    /// On disk `page` is only called when the inputs changed since the previous run.
+    pub fn page<T: Serialize>(
+        &self,
+        handlebars: &Handlebars,
+        template: &str,
+        dir: &OutDir,
+        file_name: &str,
+        inputs: &[&str],
+        page: impl FnOnce() -> T,
+    ) {
+        if !self.wants_file(dir, file_name) {
+            return;
+        }
+        let url = dir.page_url(file_name);
+        let context = || page::context(&page(), &url);
+        match &self.destination {
+            Destination::Disk(cache) => {
+                cache.render_page(handlebars, template, dir, file_name, inputs, context)
+            }
+            Destination::Memory(memory) => memory.insert(
+                dir.url(file_name),
+                MemoryFile::Page {
+                    template: template.to_owned(),
+                    context: serde_json::to_vec(&context()).unwrap(),
+                },
+            ),
+        }
+        self.pages.push(url);
+    }
+
+    /// Writes the data returned by `data` to `file_name` in `dir`.
+    /// Same as `Site::page`, on disk `data` is only called when the inputs changed since the previous run.
+    /// Returns the url of the file.
+    pub fn file(
+        &self,
+        dir: &OutDir,
+        file_name: &str,
+        inputs: &[&str],
+        data: impl FnOnce() -> Vec<u8>,
+    ) -> String {
+        if !self.wants_file(dir, file_name) {
+            return dir.url(file_name);
+        }
+        match &self.destination {
+            Destination::Disk(cache) => cache.create_file_from_inputs(dir, file_name, inputs, data),
+            Destination::Memory(memory) => {
+                let url = dir.url(file_name);
+                memory.insert(url.clone(), MemoryFile::Data(data()));
+                url
+            }
+        }
+    }
+
+    /// Writes the data in every output encoding to `file_name` in `dir`, skipping the write if the data is identical to the previous run.
+    /// Returns the url of the file.
+    pub fn create_compressed_file(&self, dir: &OutDir, file_name: &str, data: &[u8]) -> String {
+        if !self.wants_file(dir, file_name) {
+            return dir.url(file_name);
+        }
+        match &self.destination {
+            Destination::Disk(cache) => cache.create_compressed_file(dir, file_name, data),
+            Destination::Memory(memory) => {
+                let url = dir.url(file_name);
+                memory.insert(url.clone(), MemoryFile::Data(data.to_vec()));
+                url
+            }
+        }
+    }
+
+    /// Marks the file as generated by this run without writing it, so that it isn't pruned.
+    pub fn keep(&self, dir: &OutDir, file_name: &str) {
+        if let Destination::Disk(cache) = &self.destination {
+            cache.keep(dir, file_name);
+        }
+    }
+
+    /// The file generated for the url, only available for sites kept in memory.
+    pub fn memory_file(&self, url: &str) -> Option<MemoryFile> {
+        match &self.destination {
+            Destination::Memory(memory) => memory.get(url),
+            Destination::Disk(_) => None,
+        }
+    }
+
+    /// Keeps the file in memory as if it was generated for the url, only available for sites kept in memory.
+    pub fn insert_memory_file(&self, url: String, file: MemoryFile) {
+        if let Destination::Memory(memory) = &self.destination {
+            memory.insert(url, file);
+        }
+    }
+}
+
+/// Which files a run generates.
+#[derive(Default)]
+struct FileFilter {
+    /// When set, only the file at this url is generated, everything else is skipped.
+    only: Option<String>,
+}
+
+impl FileFilter {
+    fn wants(&self, dir: &OutDir) -> bool {
+        match &self.only {
+            Some(url) => url.starts_with(&format!("{}/", dir.url("").trim_end_matches('/'))),
+            None => true,
+        }
+    }
+
+    fn wants_file(&self, dir: &OutDir, file_name: &str) -> bool {
+        match &self.only {
+            Some(url) => *url == dir.url(file_name),
+            None => true,
+        }
+    }
+}
+
+/// The url of every page generated by a run, whether or not it was stale, used by the sitemap and redirects.
+#[derive(Default)]
+struct PageUrls(Mutex<Vec<String>>);
+
+impl PageUrls {
+    fn push(&self, url: String) {
+        self.0.lock().unwrap().push(url);
+    }
+
+    fn sorted(&self) -> Vec<String> {
+        let mut pages = self.0.lock().unwrap().clone();
+        pages.sort();
+        pages
+    }
+}
+
+/// Files kept in memory by the dev server, keyed by url.
+#[derive(Default)]
+struct MemoryFiles(Mutex<HashMap<String, MemoryFile>>);
+
+impl MemoryFiles {
+    fn get(&self, url: &str) -> Option<MemoryFile> {
+        self.0.lock().unwrap().get(url).cloned()
+    }
+
+    fn insert(&self, url: String, file: MemoryFile) {
+        self.0.lock().unwrap().insert(url, file);
+    }
+}
+
+/// A file kept in memory by the dev server.
+#[derive(Clone)]
+pub enum MemoryFile {
+    /// Rendered on each request, so that edits to templates show up without generating the page again.
+    /// `context` is the page serialized to json.
+    Page {
+        template: String,
+        context: Vec<u8>,
+    },
+    Data(Vec<u8>),
+}
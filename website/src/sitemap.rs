@@ -0,0 +1,67 @@
+//! Generates `/sitemap.xml` listing every page, so that search engines find pages only reachable through the nav menus.
+//! `/robots.txt` points search engines to it.
+//!
+//! A sitemap can only list 50,000 urls, so `/sitemap.xml` is a sitemap index of `/sitemap/{n}.xml`, each listing up to 50,000 pages.
+
+use crate::output::OutDir;
+use crate::page::SITE_URL;
+use crate::site::Site;
+use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
+
+const URLS_PER_SITEMAP: usize = 50_000;
+
+/// Characters that can't appear in the path of a url.
+const PATH: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+pub fn generate(site: &Site) {
+    // A filtered run would replace the sitemap with one missing most pages, so the previous sitemap is kept instead.
+    if !site.is_complete() {
+        return;
+    }
+
+    let pages: Vec<_> = site
+        .page_urls()
+        .into_iter()
+        .filter(|x| x != "/error.html")
+        .collect();
+
+    let mut index = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    let dir = OutDir::new("sitemap");
+    for (i, chunk) in pages.chunks(URLS_PER_SITEMAP).enumerate() {
+        let mut sitemap = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        for url in chunk {
+            sitemap.push_str(&format!("<url><loc>{}</loc></url>\n", absolute_url(url)));
+        }
+        sitemap.push_str("</urlset>\n");
+
+        let url = site.create_compressed_file(&dir, &format!("{i}.xml"), sitemap.as_bytes());
+        index.push_str(&format!(
+            "<sitemap><loc>{}</loc></sitemap>\n",
+            absolute_url(&url)
+        ));
+    }
+    index.push_str("</sitemapindex>\n");
+
+    let root = OutDir::new("");
+    let sitemap_url = site.create_compressed_file(&root, "sitemap.xml", index.as_bytes());
+    let robots = format!("User-agent: *\nAllow: /\nSitemap: {SITE_URL}{sitemap_url}\n");
+    site.create_compressed_file(&root, "robots.txt", robots.as_bytes());
+}
+
+/// The url percent encoded and escaped for xml.
+fn absolute_url(url: &str) -> String {
+    handlebars::html_escape(&format!("{SITE_URL}{}", utf8_percent_encode(url, PATH)))
+}
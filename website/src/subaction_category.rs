@@ -0,0 +1,283 @@
+//! Sorts the subactions of a fighter into the categories used by the subaction navigation.
+//!
+//! The category of each subaction is decided by the first of these that applies:
+//! 1. An override for the subaction in `data/subaction_categories.json`
+//! 2. The actions whose entry or exit scripts change to the subaction, using the action index ranges brawl uses for each kind of action.
+//! 3. Changed to by the script of an already categorized subaction, e.g. `AttackS4Start` changing to `AttackS4S`.
+//! 4. Heuristics on the name of the subaction.
+//!
+//! Most subactions of vanilla fighters are chosen by the game engine rather than by scripts, so the name heuristics still do a lot of work.
+//! The data driven steps mostly matter for custom subactions added by mods.
+//!
+//! The override file maps a mod folder name to fighter names, which map subaction names to a category.
+//! The fighter name `*` applies to every fighter in the mod, e.g.
+//!
+//! ```json
+//! {
+//!     "P+": {
+//!         "*": { "AttackS4Charge": "attack_smash" },
+//!         "Marth": { "SpecialAirNCustom": "special" }
+//!     }
+//! }
+//! ```
+
+use crate::script_walker::ScriptWalker;
+use anyhow::{Result, anyhow};
+use brawllib_rs::high_level_fighter::HighLevelFighter;
+use std::collections::HashMap;
+use std::env::current_dir;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SubactionCategory {
+    AttackJab,
+    AttackTilt,
+    AttackSmash,
+    AttackDash,
+    AttackAerial,
+    Grab,
+    Special,
+    Knockdown,
+    Trip,
+    LedgeOption,
+    Dodge,
+    Tech,
+    Footstool,
+    Glide,
+    Crawl,
+    Movement,
+    Final,
+    Taunt,
+    Stun,
+    Sleep,
+    Swim,
+    Item,
+    ItemThrow,
+    Misc,
+    None,
+}
+
+#[derive(Deserialize, Default)]
+pub struct SubactionCategoryOverrides(
+    HashMap<String, HashMap<String, HashMap<String, SubactionCategory>>>,
+);
+
+impl SubactionCategoryOverrides {
+    /// Loads `data/subaction_categories.json`, no overrides are used if the file does not exist.
+    pub fn load() -> Result<Self> {
+        let path = current_dir()?
+            .join("..")
+            .join("data")
+            .join("subaction_categories.json");
+        if path.exists() {
+            serde_json::from_slice(&std::fs::read(&path)?)
+                .map_err(|e| anyhow!("Failed to parse {path:?} {e}"))
+        } else {
+            Ok(SubactionCategoryOverrides::default())
+        }
+    }
+
+    fn get(
+        &self,
+        mod_name: &str,
+        fighter_name: &str,
+        subaction_name: &str,
+    ) -> Option<SubactionCategory> {
+        let fighters = self
+            .0
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(mod_name))?
+            .1;
+        [fighter_name, "*"]
+            .iter()
+            .filter_map(|name| fighters.get(*name)?.get(subaction_name))
+            .next()
+            .copied()
+    }
+}
+
+/// Returns the category of every subaction of the fighter, in the same order as `fighter.subactions`.
+pub fn categorize(
+    overrides: &SubactionCategoryOverrides,
+    mod_name: &str,
+    fighter: &HighLevelFighter,
+) -> Vec<SubactionCategory> {
+    let mut categories: Vec<Option<SubactionCategory>> = vec![None; fighter.subactions.len()];
+
+    // Actions are checked in order so that when multiple actions use the same subaction, the lowest action index wins.
+    for (action_index, action) in fighter.actions.iter().enumerate() {
+        let Some(category) = action_category(action_index) else {
+            continue;
+        };
+        let mut walker = ScriptWalker::new(fighter);
+        let mut subactions =
+            walker.changed_subactions(&action.script_entry.block, action.script_entry_common);
+        subactions.extend(
+            walker.changed_subactions(&action.script_exit.block, action.script_exit_common),
+        );
+        for subaction_index in subactions {
+            if let Some(slot @ None) = categories.get_mut(subaction_index) {
+                *slot = Some(category);
+            }
+        }
+    }
+
+    // Subactions changed to by categorized subactions share their category.
+    // Repeat until nothing changes so that chains of subactions are followed to the end.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (index, subaction) in fighter.subactions.iter().enumerate() {
+            let Some(category) = categories[index] else {
+                continue;
+            };
+            let subactions = ScriptWalker::new(fighter)
+                .changed_subactions(&subaction.scripts.script_main.block, false);
+            for subaction_index in subactions {
+                if let Some(slot @ None) = categories.get_mut(subaction_index) {
+                    *slot = Some(category);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    fighter
+        .subactions
+        .iter()
+        .zip(categories)
+        .map(|(subaction, category)| {
+            overrides
+                .get(mod_name, &fighter.name, &subaction.name)
+                .or(category)
+                .unwrap_or_else(|| name_category(&subaction.name))
+        })
+        .collect()
+}
+
+/// The category of the subactions used by the action at this index.
+/// Returns None for actions whose subactions don't clearly belong to a single category.
+fn action_category(index: usize) -> Option<SubactionCategory> {
+    use SubactionCategory::*;
+    Some(match index {
+        0x00..=0x12 | 0x15..=0x19 => Movement,
+        0x13..=0x14 => Crawl,
+        0x1a..=0x21 => Dodge,
+        0x24..=0x25 => AttackJab,
+        0x26 => AttackDash,
+        0x27..=0x29 => AttackTilt,
+        0x2a..=0x32 => AttackSmash,
+        0x33 => AttackAerial,
+        0x34..=0x3c => Grab,
+        0x4d..=0x55 => Knockdown,
+        0x56..=0x5b | 0x5f => Stun,
+        0x5c..=0x5e => Sleep,
+        0x60..=0x64 => Tech,
+        0x65..=0x6c | 0x72 | 0x7c..=0x7e => Movement,
+        0x6d..=0x71 => Footstool,
+        0x73..=0x7a => LedgeOption,
+        0x84..=0x88 => Glide,
+        0x89..=0x91 => Trip,
+        0x96..=0x97 | 0x9e..=0xb2 => Item,
+        0x9b..=0x9d => ItemThrow,
+        0xb3..=0xbc => Swim,
+        0x10c..=0x110 => Taunt,
+        0x112..=0x115 => Special,
+        0x116 => Final,
+        // Actions after Final are fighter specific, in vanilla brawl these are all used by specials.
+        0x117.. => Special,
+        _ => return Option::None,
+    })
+}
+
+/// Fallback for subactions that no action or other subaction refers to.
+fn name_category(name: &str) -> SubactionCategory {
+    use SubactionCategory::*;
+
+    // NOTE: Be careful that sometimes the check uses name.contains(..) and other times it uses name.starts_with(..)
+    if name.contains("Cliff") {
+        LedgeOption
+    } else if name.contains("Item") || name.contains("Gekikara") {
+        Item
+    } else if name.contains("Ganon")
+        || name.contains("Snake")
+        || name.contains("Bitten")
+        || name.contains("Stick")
+        || name.contains("Rope")
+        || name.contains("Ladder")
+        || name.contains("Egg")
+        || name.contains("Capture")
+        || name.contains("Zitabata")
+        || name.contains("Swing")
+    {
+        Misc
+    } else if name.contains("FuraFura") {
+        Stun
+    } else if name.contains("FuraSleep") {
+        Sleep
+    } else if name.contains("Final") {
+        Final
+    } else if name.contains("Swim") {
+        Swim
+    } else if name.contains("Slip") {
+        Trip
+    } else if name.contains("Glide") {
+        Glide
+    } else if name.contains("Shank")
+        || name.contains("AttackSquat")
+        || name.contains("SquatF")
+        || name.contains("SquatB")
+    {
+        Crawl
+    } else if name.contains("Down") {
+        Knockdown
+    } else if name.contains("AirCatch") {
+        Misc
+    } else if name.contains("Step") {
+        Footstool
+    } else if name.contains("Fall") || name.contains("Landing") {
+        Movement
+    } else if name.contains("Special") {
+        Special
+    } else if name.contains("Catch") || name.starts_with("Throw") && !name.contains("Thrown") {
+        Grab
+    } else if !name.starts_with("Throw") && name.contains("Throw") && !name.contains("Thrown") {
+        ItemThrow
+    } else if name.contains("AttackEnd") {
+        Misc
+    } else if name.contains("Attack") {
+        let number: String = name.chars().filter(char::is_ascii_digit).collect();
+        if name.contains("Air") {
+            AttackAerial
+        } else if number.starts_with('1') {
+            AttackJab
+        } else if number.starts_with('3') {
+            AttackTilt
+        } else if number.starts_with('4') {
+            AttackSmash
+        } else {
+            AttackDash
+        }
+    } else if name.contains("Appeal") || name.contains("Win") || name == "Lose" {
+        Taunt
+    } else if name.contains("Wait")
+        || name.contains("Dash")
+        || name.contains("Run")
+        || name.contains("Turn")
+        || name.contains("Walk")
+        || name.contains("Jump")
+        || name.contains("MissFoot")
+        || name.contains("Ottotto")
+        || name.contains("Squat")
+    {
+        Movement
+    } else if name.contains("Passive") {
+        Tech
+    } else if name.contains("Escape") || name.contains("Guard") {
+        Dodge
+    } else if name.contains("NONE") || name.starts_with('_') {
+        None
+    } else {
+        Misc
+    }
+}
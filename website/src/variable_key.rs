@@ -0,0 +1,154 @@
+//! Identifies the variables used by scripts by memory type, data type and address, the way PSA and BrawlBox do e.g. `LA-Basic[1]`.
+//!
+//! `fighter_renderer` includes this file with `#[path]` so the script stepper can look up the names the website passes to it,
+//! so it can only use crates that both the website and `fighter_renderer` depend on.
+
+use brawllib_rs::script::{Variable, VariableDataType, VariableMemoryType};
+use brawllib_rs::script_ast::variable_ast::{
+    InternalConstantInt, LongtermAccessBool, LongtermAccessFloat, LongtermAccessInt,
+    RandomAccessBool, RandomAccessFloat, RandomAccessInt, VariableAst,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::LazyLock;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub enum MemoryType {
+    InternalConstant,
+    LongtermAccess,
+    RandomAccess,
+    Unknown(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub enum DataType {
+    Int,
+    Float,
+    Bool,
+    Unknown(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct VariableKey {
+    pub memory_type: MemoryType,
+    pub data_type: DataType,
+    pub address: u32,
+}
+
+/// brawllib replaces the address of known variables with a name, this maps the `Debug` of every named variable back to its key.
+static NAMED_VARIABLES: LazyLock<HashMap<String, VariableKey>> = LazyLock::new(|| {
+    let mut named = HashMap::new();
+    for memory_type in [
+        VariableMemoryType::InternalConstant,
+        VariableMemoryType::LongtermAccess,
+        VariableMemoryType::RandomAccess,
+    ] {
+        for data_type in [
+            VariableDataType::Int,
+            VariableDataType::Float,
+            VariableDataType::Bool,
+        ] {
+            for address in 0..=u16::MAX as u32 {
+                let variable = VariableAst::new(&Variable {
+                    memory_type: memory_type.clone(),
+                    data_type: data_type.clone(),
+                    address,
+                });
+                if VariableKey::from_address(&variable).is_none() {
+                    let key = VariableKey {
+                        memory_type: MemoryType::from(&memory_type),
+                        data_type: DataType::from(&data_type),
+                        address,
+                    };
+                    named.insert(format!("{variable:?}"), key);
+                }
+            }
+        }
+    }
+    named
+});
+
+impl VariableKey {
+    pub fn new(variable: &VariableAst) -> VariableKey {
+        VariableKey::from_address(variable)
+            .unwrap_or_else(|| NAMED_VARIABLES[&format!("{variable:?}")])
+    }
+
+    /// The key of a variable brawllib doesn't have a name for.
+    fn from_address(variable: &VariableAst) -> Option<VariableKey> {
+        let (memory_type, data_type, address) = match variable {
+            VariableAst::InternalConstantInt(InternalConstantInt::Address(address)) => {
+                (MemoryType::InternalConstant, DataType::Int, *address)
+            }
+            VariableAst::LongtermAccessInt(LongtermAccessInt::Address(address)) => {
+                (MemoryType::LongtermAccess, DataType::Int, *address)
+            }
+            VariableAst::LongtermAccessFloat(LongtermAccessFloat::Address(address)) => {
+                (MemoryType::LongtermAccess, DataType::Float, *address)
+            }
+            VariableAst::LongtermAccessBool(LongtermAccessBool::Address(address)) => {
+                (MemoryType::LongtermAccess, DataType::Bool, *address)
+            }
+            VariableAst::RandomAccessInt(RandomAccessInt::Address(address)) => {
+                (MemoryType::RandomAccess, DataType::Int, *address)
+            }
+            VariableAst::RandomAccessFloat(RandomAccessFloat::Address(address)) => {
+                (MemoryType::RandomAccess, DataType::Float, *address)
+            }
+            VariableAst::RandomAccessBool(RandomAccessBool::Address(address)) => {
+                (MemoryType::RandomAccess, DataType::Bool, *address)
+            }
+            VariableAst::Unknown {
+                memory_type,
+                data_type,
+                address,
+            } => (memory_type.into(), data_type.into(), *address),
+            _ => return None,
+        };
+        Some(VariableKey {
+            memory_type,
+            data_type,
+            address,
+        })
+    }
+}
+
+impl From<&VariableMemoryType> for MemoryType {
+    fn from(memory_type: &VariableMemoryType) -> Self {
+        match memory_type {
+            VariableMemoryType::InternalConstant => MemoryType::InternalConstant,
+            VariableMemoryType::LongtermAccess => MemoryType::LongtermAccess,
+            VariableMemoryType::RandomAccess => MemoryType::RandomAccess,
+            VariableMemoryType::Unknown(value) => MemoryType::Unknown(*value),
+        }
+    }
+}
+
+impl From<&VariableDataType> for DataType {
+    fn from(data_type: &VariableDataType) -> Self {
+        match data_type {
+            VariableDataType::Int => DataType::Int,
+            VariableDataType::Float => DataType::Float,
+            VariableDataType::Bool => DataType::Bool,
+            VariableDataType::Unknown(value) => DataType::Unknown(*value),
+        }
+    }
+}
+
+impl fmt::Display for VariableKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.memory_type {
+            MemoryType::InternalConstant => write!(f, "IC")?,
+            MemoryType::LongtermAccess => write!(f, "LA")?,
+            MemoryType::RandomAccess => write!(f, "RA")?,
+            MemoryType::Unknown(value) => write!(f, "Unknown({value})")?,
+        }
+        match self.data_type {
+            DataType::Int => write!(f, "-Basic")?,
+            DataType::Float => write!(f, "-Float")?,
+            DataType::Bool => write!(f, "-Bit")?,
+            DataType::Unknown(value) => write!(f, "-Unknown({value})")?,
+        }
+        write!(f, "[{}]", self.address)
+    }
+}
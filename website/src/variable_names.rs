@@ -0,0 +1,204 @@
+//! Names and descriptions of the variables used by scripts, keyed by memory type, data type and address.
+//! Keys are written in the notation used by PSA and BrawlBox e.g. `LA-Basic[1]`, `RA-Bit[6]` or `IC-Basic[20001]`.
+//!
+//! `website/variable_names.json` holds the names documented by the community.
+//! A mod overrides and adds to them in `data/{mod}/variable_names.json`,
+//! and a single fighter of the mod in `data/{mod}/variable_names/{fighter}.json` where `{fighter}` is the fighter's directory in urls e.g. `captain-falcon`.
+//! Every file has the same format:
+//!
+//! ```json
+//! {
+//!     "LA-Basic[1]": { "name": "Jumps Used", "description": "Jumps used since the fighter was last on the ground." },
+//!     "LA-Bit[80]": { "name": "Side Special Used" }
+//! }
+//! ```
+
+use crate::variable_key::{DataType, MemoryType, VariableKey};
+use anyhow::{Result, anyhow};
+use brawllib_rs::script_ast::variable_ast::VariableAst;
+use brawllib_rs::script_ast::{
+    BinaryExpression, EventAst, Expression, FloatValue, ForLoop, IfStatement, Interrupt,
+    UnaryExpression,
+};
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::env::current_dir;
+use std::path::Path;
+use std::str::FromStr;
+
+impl FromStr for VariableKey {
+    type Err = anyhow::Error;
+
+    fn from_str(key: &str) -> Result<Self> {
+        let invalid = || anyhow!("{key:?} is not a variable e.g. LA-Basic[1]");
+        let (memory_type, rest) = key.split_once('-').ok_or_else(invalid)?;
+        let (data_type, address) = rest
+            .strip_suffix(']')
+            .and_then(|x| x.split_once('['))
+            .ok_or_else(invalid)?;
+        Ok(VariableKey {
+            memory_type: match memory_type {
+                "IC" => MemoryType::InternalConstant,
+                "LA" => MemoryType::LongtermAccess,
+                "RA" => MemoryType::RandomAccess,
+                _ => return Err(invalid()),
+            },
+            data_type: match data_type {
+                "Basic" => DataType::Int,
+                "Float" => DataType::Float,
+                "Bit" => DataType::Bool,
+                _ => return Err(invalid()),
+            },
+            address: address.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl Serialize for VariableKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VariableInfo {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// A variable as shown in scripts, the variables page and the JSON API.
+#[derive(Serialize, Clone, Debug)]
+pub struct NamedVariable {
+    /// e.g. `LA-Basic[1]`
+    pub key: VariableKey,
+    /// Same as `key` when the variable has no name.
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl NamedVariable {
+    /// The address and the description, if any.
+    pub fn tooltip(&self) -> String {
+        match &self.description {
+            Some(description) => format!("{}\n{description}", self.key),
+            None => self.key.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct VariableNames(HashMap<VariableKey, VariableInfo>);
+
+impl VariableNames {
+    /// Loads `website/variable_names.json`
+    pub fn load() -> Result<Self> {
+        VariableNames::default().with_overrides(&current_dir()?.join("variable_names.json"))
+    }
+
+    /// Returns the names with the names in the file at `path` taking priority, the names are unchanged if the file does not exist.
+    pub fn with_overrides(&self, path: &Path) -> Result<Self> {
+        let mut names = self.clone();
+        if path.exists() {
+            let file: HashMap<String, VariableInfo> = serde_json::from_slice(&std::fs::read(path)?)
+                .map_err(|e| anyhow!("Failed to parse {path:?} {e}"))?;
+            for (key, info) in file {
+                let key = key
+                    .parse()
+                    .map_err(|e| anyhow!("Failed to parse {path:?} {e}"))?;
+                names.0.insert(key, info);
+            }
+        }
+        Ok(names)
+    }
+
+    pub fn get(&self, variable: &VariableAst) -> NamedVariable {
+        self.get_key(VariableKey::new(variable))
+    }
+
+    pub fn get_key(&self, key: VariableKey) -> NamedVariable {
+        match self.0.get(&key) {
+            Some(info) => NamedVariable {
+                key,
+                name: info.name.clone(),
+                description: info.description.clone(),
+            },
+            None => NamedVariable {
+                key,
+                name: key.to_string(),
+                description: None,
+            },
+        }
+    }
+}
+
+/// Whether an event writes to or only reads a variable.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Access {
+    Set,
+    Read,
+}
+
+/// Visits every variable used by the events, including the events of any loops and branches.
+pub fn walk_variables(events: &[EventAst], visit: &mut impl FnMut(&VariableAst, Access)) {
+    for event in events {
+        match event {
+            EventAst::ForLoop(ForLoop { block, .. }) => walk_variables(&block.events, visit),
+            EventAst::IfStatement(IfStatement {
+                test,
+                then_branch,
+                else_branch,
+            }) => {
+                walk_expression(test, visit);
+                walk_variables(&then_branch.events, visit);
+                if let Some(else_branch) = else_branch {
+                    walk_variables(&else_branch.events, visit);
+                }
+            }
+            EventAst::IfStatementAnd(test)
+            | EventAst::IfStatementOr(test)
+            | EventAst::CreateInterrupt(Interrupt { test, .. })
+            | EventAst::PreviousInterruptAddRequirement { test }
+            | EventAst::InterruptAddRequirement { test, .. } => walk_expression(test, visit),
+            EventAst::IntVariableSet { variable, .. }
+            | EventAst::IntVariableAdd { variable, .. }
+            | EventAst::IntVariableSubtract { variable, .. }
+            | EventAst::IntVariableIncrement { variable }
+            | EventAst::IntVariableDecrement { variable }
+            | EventAst::BoolVariableSetTrue { variable }
+            | EventAst::BoolVariableSetFalse { variable } => visit(variable, Access::Set),
+            EventAst::FloatVariableSet { value, variable }
+            | EventAst::FloatVariableAdd { value, variable }
+            | EventAst::FloatVariableSubtract { value, variable }
+            | EventAst::FloatVariableMultiply { value, variable }
+            | EventAst::FloatVariableDivide { value, variable } => {
+                walk_float_value(value, visit);
+                visit(variable, Access::Set);
+            }
+            EventAst::AddHitBoxDamage { add_damage, .. } => walk_float_value(add_damage, visit),
+            EventAst::AddVelocity { x_vel, y_vel } => {
+                walk_float_value(x_vel, visit);
+                walk_float_value(y_vel, visit);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn walk_expression(expr: &Expression, visit: &mut impl FnMut(&VariableAst, Access)) {
+    match expr {
+        Expression::Unary(UnaryExpression { value, .. }) => walk_expression(value, visit),
+        Expression::Binary(BinaryExpression { left, right, .. }) => {
+            walk_expression(left, visit);
+            walk_expression(right, visit);
+        }
+        Expression::Not(expr) => walk_expression(expr, visit),
+        Expression::Variable(variable) => visit(variable, Access::Read),
+        Expression::Nullary(_) | Expression::Value(_) | Expression::Scalar(_) => {}
+    }
+}
+
+fn walk_float_value(value: &FloatValue, visit: &mut impl FnMut(&VariableAst, Access)) {
+    if let FloatValue::Variable(variable) = value {
+        visit(variable, Access::Read);
+    }
+}